@@ -0,0 +1,175 @@
+pub mod analysis;
+pub mod coach;
+pub mod corner;
+pub mod expectimax;
+pub mod greedy;
+pub mod mcts;
+pub mod ntuple;
+pub mod random;
+pub mod rollout;
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::components::board_state::BoardState;
+use crate::resources::game_settings::GameSettings;
+use crate::structs::direction::Direction;
+use rollout::{RolloutPolicy, SearchSettings};
+
+// 盤面を見て動かす方向を決めるもの
+pub trait Agent: Send + Sync {
+    // 動かせる方向がなければ何を返してもよい
+    fn choose(&mut self, board: &BoardState) -> Direction;
+}
+
+// 設定から選べる Agent
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum AgentKind {
+    #[default]
+    Greedy,
+    Random,
+    // GameSettings::ntuple の重みを使う. 重みがなければ代わりに Expectimax(NTUPLE_FALLBACK_DEPTH)
+    NTuple,
+    // 左下の角に集める
+    Corner,
+    // 読む手数
+    Expectimax(u32),
+    Rollout(SearchSettings),
+    Mcts(SearchSettings),
+}
+
+pub const NTUPLE_FALLBACK_DEPTH: u32 = 2;
+
+impl AgentKind {
+    // 読む Agent は GameSettings::spawn の通りに Tile が出現するとして探索する
+    pub fn build(self, settings: &GameSettings, seed: u64) -> Box<dyn Agent> {
+        let spawn = &settings.spawn;
+        match self {
+            AgentKind::Greedy => return Box::new(greedy::GreedyAgent),
+            AgentKind::Random => return Box::new(random::RandomAgent::new(seed)),
+            AgentKind::NTuple => match &settings.ntuple {
+                Some(network) => return Box::new(ntuple::NTupleAgent::new(network.clone())),
+                None => {
+                    return Box::new(expectimax::ExpectimaxAgent::new(
+                        NTUPLE_FALLBACK_DEPTH,
+                        spawn,
+                    ))
+                }
+            },
+            AgentKind::Corner => return Box::new(corner::CornerAgent),
+            AgentKind::Expectimax(depth) => {
+                return Box::new(expectimax::ExpectimaxAgent::new(depth, spawn))
+            }
+            AgentKind::Rollout(settings) => {
+                return Box::new(rollout::RolloutAgent::new(settings, spawn, seed))
+            }
+            AgentKind::Mcts(settings) => {
+                return Box::new(mcts::MctsAgent::new(settings, spawn, seed))
+            }
+        }
+    }
+}
+
+// greedy, random, ntuple, corner, expectimax[:depth],
+// rollout[:budget[:policy[:depth]]], mcts[:budget[:policy[:depth]]]
+// ex: expectimax:3, mcts:500:greedy, rollout:50:random:20
+impl fmt::Display for AgentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, settings) = match self {
+            AgentKind::Greedy => return write!(f, "greedy"),
+            AgentKind::Random => return write!(f, "random"),
+            AgentKind::NTuple => return write!(f, "ntuple"),
+            AgentKind::Corner => return write!(f, "corner"),
+            AgentKind::Expectimax(depth) => return write!(f, "expectimax:{}", depth),
+            AgentKind::Rollout(settings) => ("rollout", settings),
+            AgentKind::Mcts(settings) => ("mcts", settings),
+        };
+        let policy = match settings.policy {
+            RolloutPolicy::Random => "random",
+            RolloutPolicy::Greedy => "greedy",
+        };
+        write!(f, "{}:{}:{}", name, settings.budget, policy)?;
+        if let Some(depth) = settings.depth {
+            write!(f, ":{}", depth)?;
+        }
+        return Ok(());
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAgentKindError(pub String);
+
+impl fmt::Display for ParseAgentKindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "unknown agent: {}", self.0);
+    }
+}
+
+impl std::error::Error for ParseAgentKindError {}
+
+impl FromStr for AgentKind {
+    type Err = ParseAgentKindError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseAgentKindError(s.to_string());
+        let mut parts = s.split(':');
+        let name = parts.next().unwrap_or_default().to_ascii_lowercase();
+        if name == "expectimax" {
+            let depth = match parts.next() {
+                Some(depth) => depth.parse().map_err(|_| error())?,
+                None => 2,
+            };
+            if parts.next().is_some() || depth == 0 {
+                return Err(error());
+            }
+            return Ok(AgentKind::Expectimax(depth));
+        }
+        let mut settings = SearchSettings::default();
+        if let Some(budget) = parts.next() {
+            settings.budget = budget.parse().map_err(|_| error())?;
+        }
+        if let Some(policy) = parts.next() {
+            settings.policy = match policy {
+                "random" => RolloutPolicy::Random,
+                "greedy" => RolloutPolicy::Greedy,
+                _ => return Err(error()),
+            };
+        }
+        if let Some(depth) = parts.next() {
+            settings.depth = Some(depth.parse().map_err(|_| error())?);
+        }
+        let searching = s.contains(':');
+        match name.as_str() {
+            "greedy" if !searching => return Ok(AgentKind::Greedy),
+            "random" if !searching => return Ok(AgentKind::Random),
+            "ntuple" if !searching => return Ok(AgentKind::NTuple),
+            "corner" if !searching => return Ok(AgentKind::Corner),
+            "rollout" if parts.next().is_none() => return Ok(AgentKind::Rollout(settings)),
+            "mcts" if parts.next().is_none() => return Ok(AgentKind::Mcts(settings)),
+            _ => return Err(error()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn agent_kind_round_trip() {
+        for text in [
+            "greedy",
+            "ntuple",
+            "rollout:50:random",
+            "mcts:500:greedy:20",
+        ] {
+            let kind: AgentKind = text.parse().unwrap();
+            assert_eq!(kind.to_string(), text);
+        }
+        assert_eq!(
+            "mcts".parse::<AgentKind>().unwrap().to_string(),
+            "mcts:100:random"
+        );
+        assert!("greedy:3".parse::<AgentKind>().is_err());
+        assert!("mcts:fast".parse::<AgentKind>().is_err());
+    }
+}
@@ -0,0 +1,72 @@
+use super::expectimax::ExpectimaxAgent;
+use crate::components::board_state::BoardState;
+use crate::resources::game_settings::SpawnPolicy;
+use crate::structs::direction::Direction;
+
+// 見直しで読む手数
+pub const ANALYSIS_DEPTH: u32 = 2;
+// 損失の大きい順にこの数までを悪手とする
+pub const BLUNDER_COUNT: usize = 3;
+// これより損失が小さければ悪手にしない
+pub const MIN_BLUNDER_LOSS: f32 = 64.0;
+
+// 1 手の見直し
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoveAnalysis {
+    pub turn: usize,
+    pub before: BoardState,
+    pub direction: Direction,
+    // 指した手の評価. 動かせない方向なら None
+    pub value: Option<f32>,
+    pub best_direction: Direction,
+    pub best_value: f32,
+    pub blunder: bool,
+}
+
+impl MoveAnalysis {
+    // 最善手との評価の差
+    pub fn loss(&self) -> f32 {
+        return self.best_value - self.value.unwrap_or(0.0);
+    }
+}
+
+// 記録した (動かす前の盤面, 指した方向) を ExpectimaxAgent で評価し直す
+// 動かせる方向がない手は飛ばす
+pub fn analyze<'a>(
+    moves: impl IntoIterator<Item = (&'a BoardState, Direction)>,
+    spawn: &SpawnPolicy,
+) -> Vec<MoveAnalysis> {
+    let mut agent = ExpectimaxAgent::new(ANALYSIS_DEPTH, spawn);
+    let mut analyses: Vec<MoveAnalysis> = moves
+        .into_iter()
+        .enumerate()
+        .filter_map(|(turn, (before, direction))| {
+            let values = agent.evaluate(before);
+            let &(best_direction, best_value) =
+                values
+                    .iter()
+                    .reduce(|best, next| if next.1 > best.1 { next } else { best })?;
+            let value = values
+                .iter()
+                .find(|(d, _)| *d == direction)
+                .map(|(_, value)| *value);
+            return Some(MoveAnalysis {
+                turn,
+                before: before.clone(),
+                direction,
+                value,
+                best_direction,
+                best_value,
+                blunder: false,
+            });
+        })
+        .collect();
+    let mut order: Vec<usize> = (0..analyses.len()).collect();
+    order.sort_by(|&a, &b| analyses[b].loss().total_cmp(&analyses[a].loss()));
+    for i in order.into_iter().take(BLUNDER_COUNT) {
+        if analyses[i].loss() >= MIN_BLUNDER_LOSS {
+            analyses[i].blunder = true;
+        }
+    }
+    return analyses;
+}
@@ -0,0 +1,61 @@
+use super::rollout::{RolloutPolicy, Simulator};
+use crate::components::{board_state::BoardState, position::Position};
+use crate::structs::{bitboard::Bitboard, direction::Direction};
+
+// 1 手の危なさ
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Risk {
+    // horizon 手以内に動かせなくなる割合. 4x4 以外の盤面では 0
+    pub loss_chance: f32,
+    // 角にあった最大の Tile が角から外れる
+    pub leaves_corner: bool,
+}
+
+// board を direction に動かした後、samples 回 horizon 手ずつ貪欲に進めて見積もる
+pub fn assess(
+    board: &BoardState,
+    direction: Direction,
+    horizon: u32,
+    samples: u32,
+    simulator: &mut Simulator,
+) -> Risk {
+    let Some((moved, _)) = board.moved(direction) else {
+        return Risk::default();
+    };
+    let leaves_corner = max_in_corner(board) && !max_in_corner(&moved);
+    let Ok(after) = Bitboard::try_from(&moved) else {
+        return Risk {
+            loss_chance: 0.0,
+            leaves_corner,
+        };
+    };
+    let mut lost = 0;
+    for _ in 0..samples.max(1) {
+        let mut board = simulator.spawn(after);
+        for _ in 0..horizon {
+            let Some(direction) = simulator.policy_move(board, RolloutPolicy::Greedy) else {
+                break;
+            };
+            board = simulator.spawn(board.slide(direction).0);
+        }
+        if board.is_game_over() {
+            lost += 1;
+        }
+    }
+    return Risk {
+        loss_chance: lost as f32 / samples.max(1) as f32,
+        leaves_corner,
+    };
+}
+
+// 最大の Tile のどれかが 4 隅のどこかにある
+fn max_in_corner(board: &BoardState) -> bool {
+    let Some(max) = board.max_tile() else {
+        return false;
+    };
+    let grid = board.grid();
+    let (right, top) = (grid.width() - 1, grid.height() - 1);
+    return [(0, 0), (right, 0), (0, top), (right, top)]
+        .into_iter()
+        .any(|(x, y)| board.get(Position::new(x, y)) == Some(max));
+}
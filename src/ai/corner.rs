@@ -0,0 +1,30 @@
+use super::{greedy::GreedyAgent, Agent};
+use crate::components::board_state::BoardState;
+use crate::structs::direction::Direction;
+
+// 大きな Tile を左下の角に集める. Down と Left のうち GreedyAgent の選ぶ方を優先し、
+// どちらも動かせなければ Right, それもだめなら Up
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CornerAgent;
+
+impl Agent for CornerAgent {
+    fn choose(&mut self, board: &BoardState) -> Direction {
+        let preferred = [Direction::Down, Direction::Left]
+            .into_iter()
+            .filter_map(|direction| {
+                let (moved, score) = board.moved(direction)?;
+                return Some(((score, moved.empty_count()), direction));
+            })
+            .max_by_key(|(value, _)| *value);
+        if let Some((_, direction)) = preferred {
+            return direction;
+        }
+        if board.moved(Direction::Right).is_some() {
+            return Direction::Right;
+        }
+        if board.moved(Direction::Up).is_some() {
+            return Direction::Up;
+        }
+        return GreedyAgent.choose(board);
+    }
+}
@@ -0,0 +1,107 @@
+use bevy::utils::HashMap;
+
+use super::{greedy::GreedyAgent, Agent};
+use crate::components::board_state::BoardState;
+use crate::resources::game_settings::SpawnPolicy;
+use crate::structs::{bitboard::Bitboard, direction::Direction};
+
+// 読み切った局面の空きマス 1 つあたりの価値
+const EMPTY_WEIGHT: f32 = 64.0;
+
+// depth 手先まで、Tile の出現はすべての空きマスと spawn の確率で期待値をとる
+// 1 ターンに出現する spawn.tiles_per_turn 個の Tile は、1 つずつ残りの空きマスから選ぶ
+// 4x4 以外の盤面では GreedyAgent と同じ
+pub struct ExpectimaxAgent {
+    depth: u32,
+    spawn: SpawnPolicy,
+    // (Tile が出現する途中の盤面, 残りの手数, このターンに残りの Tile の数) -> 期待値
+    cache: HashMap<(Bitboard, u32, usize), f32>,
+}
+
+impl ExpectimaxAgent {
+    pub fn new(depth: u32, spawn: &SpawnPolicy) -> Self {
+        return Self {
+            depth: depth.max(1),
+            spawn: spawn.clone(),
+            cache: HashMap::default(),
+        };
+    }
+    fn max_node(&mut self, board: Bitboard, depth: u32) -> f32 {
+        if depth == 0 {
+            return board.empty_count() as f32 * EMPTY_WEIGHT;
+        }
+        let mut best: Option<f32> = None;
+        for direction in Direction::ALL {
+            let (after, score) = board.slide(direction);
+            if after == board {
+                continue;
+            }
+            let value = score as f32 + self.chance_node(after, depth, self.spawn.tiles_per_turn);
+            best = Some(best.map_or(value, |best| best.max(value)));
+        }
+        // 動かせなければ終局
+        return best.unwrap_or(0.0);
+    }
+    // Tile を 1 つ出現させる. 出し切るか空きマスがなくなれば次の手へ
+    fn chance_node(&mut self, after: Bitboard, depth: u32, tiles: usize) -> f32 {
+        let empty: Vec<_> = after.empty_positions().collect();
+        if tiles == 0 || empty.is_empty() {
+            return self.max_node(after, depth - 1);
+        }
+        if let Some(value) = self.cache.get(&(after, depth, tiles)) {
+            return *value;
+        }
+        let total_weight = self.spawn.total_weight() as f32;
+        let mut total = 0.0;
+        for pos in empty.iter() {
+            for (tile, weight) in self.spawn.weights.clone() {
+                let mut next = after;
+                next.set(*pos, tile.0);
+                total += weight as f32 / total_weight * self.chance_node(next, depth, tiles - 1);
+            }
+        }
+        let value = total / empty.len() as f32;
+        self.cache.insert((after, depth, tiles), value);
+        return value;
+    }
+}
+
+impl ExpectimaxAgent {
+    // 動かせる方向ごとの期待値. 4x4 以外の盤面では 1 手先の得点と空きマスだけで見る
+    pub fn evaluate(&mut self, board: &BoardState) -> Vec<(Direction, f32)> {
+        let Ok(bitboard) = Bitboard::try_from(board) else {
+            return Direction::ALL
+                .into_iter()
+                .filter_map(|direction| {
+                    let (moved, score) = board.moved(direction)?;
+                    let value = score as f32 + moved.empty_count() as f32 * EMPTY_WEIGHT;
+                    return Some((direction, value));
+                })
+                .collect();
+        };
+        self.cache.clear();
+        return bitboard
+            .legal_directions()
+            .map(|direction| {
+                let (after, score) = bitboard.slide(direction);
+                return (
+                    direction,
+                    score as f32 + self.chance_node(after, self.depth, self.spawn.tiles_per_turn),
+                );
+            })
+            .collect();
+    }
+}
+
+impl Agent for ExpectimaxAgent {
+    fn choose(&mut self, board: &BoardState) -> Direction {
+        if Bitboard::try_from(board).is_err() {
+            return GreedyAgent.choose(board);
+        }
+        return self
+            .evaluate(board)
+            .into_iter()
+            .reduce(|best, next| if next.1 > best.1 { next } else { best })
+            .map_or(Direction::Down, |(direction, _)| direction);
+    }
+}
@@ -0,0 +1,20 @@
+use super::Agent;
+use crate::components::board_state::BoardState;
+use crate::structs::direction::Direction;
+
+// 1 手先だけを見て、得点が最大、次に空きマスが最大になる方向を選ぶ
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GreedyAgent;
+
+impl Agent for GreedyAgent {
+    fn choose(&mut self, board: &BoardState) -> Direction {
+        return Direction::ALL
+            .into_iter()
+            .filter_map(|direction| {
+                let (moved, score) = board.moved(direction)?;
+                return Some(((score, moved.empty_count()), direction));
+            })
+            .max_by_key(|(value, _)| *value)
+            .map_or(Direction::Down, |(_, direction)| direction);
+    }
+}
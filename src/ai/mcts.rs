@@ -0,0 +1,130 @@
+use bevy::utils::HashMap;
+
+use super::{
+    greedy::GreedyAgent,
+    rollout::{SearchSettings, Simulator},
+    Agent,
+};
+use crate::components::board_state::BoardState;
+use crate::resources::game_settings::SpawnPolicy;
+use crate::structs::{bitboard::Bitboard, direction::Direction};
+
+// UCB1 の探索の強さ. 価値は子の平均の最大で割ってから比べる
+const EXPLORATION: f32 = 1.4;
+
+// Tile の出現を標本として木を広げる MCTS. 反復ごとに葉を 1 つ増やし、そこから rollout する
+// 4x4 以外の盤面では GreedyAgent と同じ
+pub struct MctsAgent {
+    settings: SearchSettings,
+    simulator: Simulator,
+}
+
+impl MctsAgent {
+    pub fn new(settings: SearchSettings, spawn: &SpawnPolicy, seed: u64) -> Self {
+        return Self {
+            settings,
+            simulator: Simulator::new(spawn, seed),
+        };
+    }
+}
+
+impl Agent for MctsAgent {
+    fn choose(&mut self, board: &BoardState) -> Direction {
+        let Ok(bitboard) = Bitboard::try_from(board) else {
+            return GreedyAgent.choose(board);
+        };
+        let mut root = DecisionNode::new(bitboard);
+        for _ in 0..self.settings.budget.max(1) {
+            root.iterate(&mut self.simulator, &self.settings);
+        }
+        return root
+            .children
+            .iter()
+            .max_by_key(|child| child.visits)
+            .map_or(Direction::Down, |child| child.direction);
+    }
+}
+
+// 方向を選ぶ局面
+struct DecisionNode {
+    visits: u32,
+    children: Vec<ChanceNode>,
+}
+
+// 動かした後, Tile が出現する前の局面
+struct ChanceNode {
+    direction: Direction,
+    after: Bitboard,
+    score: f32,
+    visits: u32,
+    total: f32,
+    outcomes: HashMap<Bitboard, DecisionNode>,
+}
+
+impl ChanceNode {
+    fn mean(&self) -> f32 {
+        return self.total / self.visits.max(1) as f32;
+    }
+}
+
+impl DecisionNode {
+    fn new(board: Bitboard) -> Self {
+        let children = board
+            .legal_directions()
+            .map(|direction| {
+                let (after, score) = board.slide(direction);
+                return ChanceNode {
+                    direction,
+                    after,
+                    score: score as f32,
+                    visits: 0,
+                    total: 0.0,
+                    outcomes: HashMap::default(),
+                };
+            })
+            .collect();
+        return Self {
+            visits: 0,
+            children,
+        };
+    }
+    // この局面から先で得た得点を返す
+    fn iterate(&mut self, simulator: &mut Simulator, settings: &SearchSettings) -> f32 {
+        let Some(i) = self.select() else {
+            return 0.0;
+        };
+        let child = &mut self.children[i];
+        let next = simulator.spawn(child.after);
+        let rest = match child.outcomes.get_mut(&next) {
+            Some(node) => node.iterate(simulator, settings),
+            None => {
+                child.outcomes.insert(next, DecisionNode::new(next));
+                simulator.rollout(next, settings)
+            }
+        };
+        let value = child.score + rest;
+        child.visits += 1;
+        child.total += value;
+        self.visits += 1;
+        return value;
+    }
+    // まだ試していない方向を先に、その後は UCB1 が最大の方向
+    fn select(&self) -> Option<usize> {
+        if let Some(i) = self.children.iter().position(|child| child.visits == 0) {
+            return Some(i);
+        }
+        let scale = self
+            .children
+            .iter()
+            .map(ChanceNode::mean)
+            .fold(0.0, f32::max)
+            .max(1.0);
+        let log_visits = (self.visits.max(1) as f32).ln();
+        return (0..self.children.len()).max_by(|&a, &b| {
+            let ucb = |child: &ChanceNode| {
+                child.mean() / scale + EXPLORATION * (log_visits / child.visits as f32).sqrt()
+            };
+            return ucb(&self.children[a]).total_cmp(&ucb(&self.children[b]));
+        });
+    }
+}
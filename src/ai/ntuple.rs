@@ -0,0 +1,283 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use super::{greedy::GreedyAgent, Agent};
+use crate::components::{board_state::BoardState, position::Position};
+use crate::structs::{
+    bitboard::{Bitboard, BITBOARD_SIZE},
+    direction::Direction,
+    quater_turn::Symmetry,
+};
+
+// 4x4 の盤面の価値を、いくつかのマスの組 (tuple) ごとの重みの和で表す
+// 各 tuple は盤面の 8 通りの対称 (回転と反転) で同じ重みを共有する
+#[derive(Clone, PartialEq)]
+pub struct NTupleNetwork {
+    patterns: Vec<Vec<Position>>,
+    // patterns ごとに、8 通りの対称で移した各マスの Bitboard 上の shift
+    shifts: Vec<Vec<Vec<u32>>>,
+    weights: Vec<Vec<f32>>,
+}
+
+// 横 1 列が 2 本と 2x2 の正方形が 3 つ
+pub const DEFAULT_PATTERNS: [[(usize, usize); 4]; 5] = [
+    [(0, 0), (1, 0), (2, 0), (3, 0)],
+    [(0, 1), (1, 1), (2, 1), (3, 1)],
+    [(0, 0), (1, 0), (0, 1), (1, 1)],
+    [(1, 0), (2, 0), (1, 1), (2, 1)],
+    [(1, 1), (2, 1), (1, 2), (2, 2)],
+];
+
+// 重みを保存するファイルの先頭
+const MAGIC: &[u8; 8] = b"N2048TUP";
+const VERSION: u32 = 1;
+pub const DEFAULT_WEIGHTS_PATH: &str = "ntuple.weights";
+
+// 重みは多いので数だけ出す
+impl fmt::Debug for NTupleNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f
+            .debug_struct("NTupleNetwork")
+            .field("patterns", &self.patterns.len())
+            .field("weights", &self.weights.iter().map(Vec::len).sum::<usize>())
+            .finish();
+    }
+}
+
+impl Default for NTupleNetwork {
+    fn default() -> Self {
+        let patterns = DEFAULT_PATTERNS
+            .iter()
+            .map(|pattern| pattern.iter().map(|&pos| pos.into()).collect())
+            .collect();
+        return Self::new(patterns);
+    }
+}
+
+impl NTupleNetwork {
+    // 重みは 16^(tuple の長さ) 個ずつ必要になる
+    pub fn new(patterns: Vec<Vec<Position>>) -> Self {
+        let shifts = patterns
+            .iter()
+            .map(|pattern| symmetric_shifts(pattern))
+            .collect();
+        let weights = patterns
+            .iter()
+            .map(|pattern| vec![0.0; 1 << (4 * pattern.len())])
+            .collect();
+        return Self {
+            patterns,
+            shifts,
+            weights,
+        };
+    }
+    pub fn patterns(&self) -> &[Vec<Position>] {
+        return &self.patterns;
+    }
+    // value に足し合わせる重みの数
+    pub fn feature_count(&self) -> usize {
+        return self.shifts.iter().map(Vec::len).sum();
+    }
+    pub fn value(&self, board: Bitboard) -> f32 {
+        let mut value = 0.0;
+        for (shifts, weights) in self.shifts.iter().zip(&self.weights) {
+            for shifts in shifts {
+                value += weights[index(board, shifts)];
+            }
+        }
+        return value;
+    }
+    // board の value に使う重みすべてに amount を足す
+    pub fn update(&mut self, board: Bitboard, amount: f32) {
+        for (shifts, weights) in self.shifts.iter().zip(&mut self.weights) {
+            for shifts in shifts {
+                weights[index(board, shifts)] += amount;
+            }
+        }
+    }
+    // 得点と動かした後の盤面の value の和が最大になる手
+    pub fn best_move(&self, board: Bitboard) -> Option<Evaluation> {
+        return Direction::ALL
+            .into_iter()
+            .filter(|&direction| board.can_move(direction))
+            .map(|direction| {
+                let (after, score) = board.slide(direction);
+                return Evaluation {
+                    direction,
+                    after,
+                    reward: score as f32,
+                    value: self.value(after),
+                };
+            })
+            .max_by(|a, b| a.total().total_cmp(&b.total()));
+    }
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        return writer.flush();
+    }
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        return Self::read_from(&mut BufReader::new(File::open(path)?));
+    }
+    // MAGIC, VERSION, tuple の数, 各 tuple のマス, 重み (すべて little endian)
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&(self.patterns.len() as u32).to_le_bytes())?;
+        for pattern in self.patterns.iter() {
+            writer.write_all(&(pattern.len() as u32).to_le_bytes())?;
+            for pos in pattern.iter() {
+                writer.write_all(&[pos.x as u8, pos.y as u8])?;
+            }
+        }
+        for weight in self.weights.iter().flatten() {
+            writer.write_all(&weight.to_le_bytes())?;
+        }
+        return Ok(());
+    }
+    pub fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC || read_u32(reader)? != VERSION {
+            return Err(invalid_data("not an n-tuple weights file"));
+        }
+        let count = read_u32(reader)? as usize;
+        let mut patterns = Vec::with_capacity(count);
+        for _ in 0..count {
+            let len = read_u32(reader)? as usize;
+            if len == 0 || len > 8 {
+                return Err(invalid_data("invalid tuple length"));
+            }
+            let mut pattern = Vec::with_capacity(len);
+            for _ in 0..len {
+                let mut pos = [0; 2];
+                reader.read_exact(&mut pos)?;
+                if pos.iter().any(|&c| c as usize >= BITBOARD_SIZE) {
+                    return Err(invalid_data("tuple position out of the board"));
+                }
+                pattern.push(Position::new(pos[0] as usize, pos[1] as usize));
+            }
+            patterns.push(pattern);
+        }
+        let mut network = Self::new(patterns);
+        for weight in network.weights.iter_mut().flatten() {
+            let mut bytes = [0; 4];
+            reader.read_exact(&mut bytes)?;
+            *weight = f32::from_le_bytes(bytes);
+        }
+        return Ok(network);
+    }
+}
+
+// best_move で比べた手
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Evaluation {
+    pub direction: Direction,
+    // 動かした後, Tile が出現する前の盤面
+    pub after: Bitboard,
+    pub reward: f32,
+    pub value: f32,
+}
+
+impl Evaluation {
+    pub fn total(&self) -> f32 {
+        return self.reward + self.value;
+    }
+}
+
+fn index(board: Bitboard, shifts: &[u32]) -> usize {
+    let mut index = 0;
+    for &shift in shifts {
+        index = (index << 4) | ((board.0 >> shift) & 0xF) as usize;
+    }
+    return index;
+}
+
+// pattern を正方形の対称性 (Symmetry::ALL の 8 通り) で移したもの
+fn symmetric_shifts(pattern: &[Position]) -> Vec<Vec<u32>> {
+    return Symmetry::ALL
+        .iter()
+        .map(|sym| {
+            pattern
+                .iter()
+                .map(|&pos| sym.apply_position(pos, BITBOARD_SIZE, BITBOARD_SIZE))
+                .map(|pos| ((pos.y * BITBOARD_SIZE + pos.x) * 4) as u32)
+                .collect()
+        })
+        .collect();
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    return Ok(u32::from_le_bytes(bytes));
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    return io::Error::new(io::ErrorKind::InvalidData, message);
+}
+
+// 学習した NTupleNetwork で 1 手先を読む. 4x4 以外の盤面では GreedyAgent と同じ
+pub struct NTupleAgent {
+    network: Arc<NTupleNetwork>,
+}
+
+impl NTupleAgent {
+    pub fn new(network: Arc<NTupleNetwork>) -> Self {
+        return Self { network };
+    }
+}
+
+impl Agent for NTupleAgent {
+    fn choose(&mut self, board: &BoardState) -> Direction {
+        let Ok(bitboard) = Bitboard::try_from(board) else {
+            return GreedyAgent.choose(board);
+        };
+        return self
+            .network
+            .best_move(bitboard)
+            .map_or(Direction::Down, |evaluation| evaluation.direction);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symmetric_boards_share_weights() {
+        let mut network = NTupleNetwork::default();
+        let mut board = Bitboard::default();
+        board.set(Position::new(0, 0), 3);
+        board.set(Position::new(1, 0), 1);
+        board.set(Position::new(2, 3), 5);
+        network.update(board, 1.0);
+        let mut mirrored = Bitboard::default();
+        mirrored.set(Position::new(3, 0), 3);
+        mirrored.set(Position::new(2, 0), 1);
+        mirrored.set(Position::new(1, 3), 5);
+        assert_eq!(network.value(board), network.value(mirrored));
+        assert_eq!(network.value(board.transpose()), network.value(board));
+        for sym in Symmetry::ALL {
+            let moved =
+                Bitboard::from_tiles(board.tiles().map(|(pos, tile)| {
+                    (sym.apply_position(pos, BITBOARD_SIZE, BITBOARD_SIZE), tile)
+                }));
+            assert_eq!(network.value(moved), network.value(board), "{:?}", sym);
+        }
+    }
+
+    #[test]
+    fn save_and_load() {
+        let mut network = NTupleNetwork::default();
+        network.update(Bitboard(0x1234_0000_0021_0003), 0.5);
+        let mut bytes = Vec::new();
+        network.write_to(&mut bytes).unwrap();
+        let loaded = NTupleNetwork::read_from(&mut bytes.as_slice()).unwrap();
+        assert_eq!(loaded, network);
+        assert!(NTupleNetwork::read_from(&mut &bytes[1..]).is_err());
+    }
+}
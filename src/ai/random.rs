@@ -0,0 +1,29 @@
+use bevy_prng::WyRand;
+use rand_core::{RngCore, SeedableRng};
+
+use super::Agent;
+use crate::components::board_state::BoardState;
+use crate::structs::direction::Direction;
+
+// 動かせる方向から一様に選ぶ
+pub struct RandomAgent {
+    rng: WyRand,
+}
+
+impl RandomAgent {
+    pub fn new(seed: u64) -> Self {
+        return Self {
+            rng: WyRand::seed_from_u64(seed),
+        };
+    }
+}
+
+impl Agent for RandomAgent {
+    fn choose(&mut self, board: &BoardState) -> Direction {
+        let legal: Vec<Direction> = board.legal_directions().collect();
+        if legal.is_empty() {
+            return Direction::Down;
+        }
+        return legal[self.rng.next_u32() as usize % legal.len()];
+    }
+}
@@ -0,0 +1,165 @@
+use bevy_prng::WyRand;
+use rand_core::{RngCore, SeedableRng};
+
+use super::{greedy::GreedyAgent, Agent};
+use crate::components::board_state::BoardState;
+use crate::resources::game_settings::SpawnPolicy;
+use crate::structs::{
+    bitboard::{Bitboard, BITBOARD_SIZE},
+    direction::Direction,
+};
+
+// 読み切った先の局面を進める方針
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum RolloutPolicy {
+    // 動かせる方向から一様に選ぶ
+    #[default]
+    Random,
+    // 1 手で得られる得点が最大の方向. 同点なら一様に選ぶ
+    Greedy,
+}
+
+// RolloutAgent と MctsAgent の探索量
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct SearchSettings {
+    // RolloutAgent では方向ごとの rollout の回数, MctsAgent では全体の反復回数
+    pub budget: u32,
+    pub policy: RolloutPolicy,
+    // 1 回の rollout で進める手数. None なら終わるまで
+    pub depth: Option<u32>,
+}
+
+impl Default for SearchSettings {
+    fn default() -> Self {
+        return Self {
+            budget: 100,
+            policy: RolloutPolicy::Random,
+            depth: None,
+        };
+    }
+}
+
+// 4x4 の盤面を描画なしで速く進める. Tile は spawn と同じ数と確率で出現させる
+pub struct Simulator {
+    spawn: SpawnPolicy,
+    rng: WyRand,
+}
+
+impl Simulator {
+    pub fn new(spawn: &SpawnPolicy, seed: u64) -> Self {
+        return Self {
+            spawn: spawn.clone(),
+            rng: WyRand::seed_from_u64(seed),
+        };
+    }
+    pub fn set_spawn(&mut self, spawn: &SpawnPolicy) {
+        self.spawn = spawn.clone();
+    }
+    pub fn rng(&mut self) -> &mut WyRand {
+        return &mut self.rng;
+    }
+    // 1 ターン分の Tile を出現させる
+    pub fn spawn(&mut self, board: Bitboard) -> Bitboard {
+        let mut board = board;
+        for _ in 0..self.spawn.tiles_per_turn {
+            board = self.spawn_one(board);
+        }
+        return board;
+    }
+    // 空きマスの 1 つに Tile を出現させる
+    fn spawn_one(&mut self, board: Bitboard) -> Bitboard {
+        let empty = board.empty_count();
+        if empty == 0 {
+            return board;
+        }
+        let mut nth = self.rng.next_u32() as usize % empty;
+        let tile = self.spawn.choose(self.rng.next_u32());
+        for shift in (0..BITBOARD_SIZE * BITBOARD_SIZE).map(|cell| cell * 4) {
+            if (board.0 >> shift) & 0xF != 0 {
+                continue;
+            }
+            if nth == 0 {
+                return Bitboard(board.0 | ((tile.0 as u64) << shift));
+            }
+            nth -= 1;
+        }
+        return board;
+    }
+    pub fn policy_move(&mut self, board: Bitboard, policy: RolloutPolicy) -> Option<Direction> {
+        let mut legal = [Direction::Down; 4];
+        let mut count = 0;
+        let mut best = 0;
+        for direction in Direction::ALL {
+            let (after, score) = board.slide(direction);
+            if after == board {
+                continue;
+            }
+            if policy == RolloutPolicy::Greedy && count > 0 && score < best {
+                continue;
+            }
+            if policy == RolloutPolicy::Greedy && score > best {
+                best = score;
+                count = 0;
+            }
+            legal[count] = direction;
+            count += 1;
+        }
+        if count == 0 {
+            return None;
+        }
+        return Some(legal[self.rng.next_u32() as usize % count]);
+    }
+    // board (Tile の出現後) から policy で進め、得た得点の和を返す
+    pub fn rollout(&mut self, mut board: Bitboard, settings: &SearchSettings) -> f32 {
+        let mut total = 0.0;
+        let mut moves = 0;
+        while settings.depth.is_none_or(|depth| moves < depth) {
+            let Some(direction) = self.policy_move(board, settings.policy) else {
+                break;
+            };
+            let (after, score) = board.slide(direction);
+            total += score as f32;
+            board = self.spawn(after);
+            moves += 1;
+        }
+        return total;
+    }
+}
+
+// 方向ごとに budget 回ずつ rollout し、得点の平均が最大の方向を選ぶ
+// 4x4 以外の盤面では GreedyAgent と同じ
+pub struct RolloutAgent {
+    settings: SearchSettings,
+    simulator: Simulator,
+}
+
+impl RolloutAgent {
+    pub fn new(settings: SearchSettings, spawn: &SpawnPolicy, seed: u64) -> Self {
+        return Self {
+            settings,
+            simulator: Simulator::new(spawn, seed),
+        };
+    }
+}
+
+impl Agent for RolloutAgent {
+    fn choose(&mut self, board: &BoardState) -> Direction {
+        let Ok(bitboard) = Bitboard::try_from(board) else {
+            return GreedyAgent.choose(board);
+        };
+        let mut best: Option<(f32, Direction)> = None;
+        for direction in bitboard.legal_directions() {
+            let (after, score) = bitboard.slide(direction);
+            let mut total = 0.0;
+            for _ in 0..self.settings.budget.max(1) {
+                let next = self.simulator.spawn(after);
+                total += score as f32 + self.simulator.rollout(next, &self.settings);
+            }
+            let average = total / self.settings.budget.max(1) as f32;
+            if best.is_none_or(|(value, _)| average > value) {
+                best = Some((average, direction));
+            }
+        }
+        return best.map_or(Direction::Down, |(_, direction)| direction);
+    }
+}
@@ -1,3 +1,9 @@
+pub mod achievement;
+pub mod analysis_panel;
+pub mod high_score_table;
 pub mod main_board;
+pub mod notice;
 pub mod score_board;
+pub mod stats_screen;
 pub mod tile;
+pub mod versus_result;
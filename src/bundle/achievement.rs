@@ -0,0 +1,152 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::achievement::{AchievementGallery, AchievementToast},
+    constants::font::{MAIN_FONT_NAME, SCORE_FONT_SIZE},
+    resources::{
+        achievements::{Achievement, Achievements},
+        game_settings::{GameSettings, Theme},
+    },
+};
+
+// お知らせを出しておく秒数
+const TOAST_SECONDS: f32 = 3.0;
+const TOAST_HEIGHT: f32 = SCORE_FONT_SIZE / 2.0 + 8.0;
+
+// 右上に重ねて出す. 入力は遮らない
+#[derive(Bundle)]
+struct ToastBundle {
+    toast: AchievementToast,
+    text_bundle: TextBundle,
+}
+
+impl ToastBundle {
+    fn new(text: String, font: Handle<Font>, theme: &Theme, index: usize) -> Self {
+        return Self {
+            toast: AchievementToast {
+                timer: Timer::from_seconds(TOAST_SECONDS, TimerMode::Once),
+            },
+            text_bundle: TextBundle {
+                z_index: ZIndex::Global(30),
+                ..TextBundle::from_section(
+                    text,
+                    TextStyle {
+                        font,
+                        font_size: SCORE_FONT_SIZE / 2.0,
+                        color: theme.score_text,
+                    },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(TOAST_HEIGHT * index as f32),
+                    right: Val::Px(0.0),
+                    padding: UiRect::all(Val::Px(4.0)),
+                    ..default()
+                })
+                .with_background_color(theme.board)
+            },
+        };
+    }
+}
+
+// index 番目のお知らせとして、既に出ているものの下に並べる
+pub fn show_toast(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    settings: &GameSettings,
+    achievement: &Achievement,
+    index: usize,
+) {
+    let font = asset_server.load(MAIN_FONT_NAME);
+    let text = format!("Unlocked: {}", achievement.name);
+    commands.spawn(ToastBundle::new(text, font, &settings.theme, index));
+}
+
+#[derive(Bundle)]
+struct GalleryBundle {
+    marker: AchievementGallery,
+    node_bundle: NodeBundle,
+}
+
+impl GalleryBundle {
+    fn new(theme: &Theme) -> Self {
+        return Self {
+            marker: AchievementGallery,
+            node_bundle: NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(4.0),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: BackgroundColor(theme.board),
+                z_index: ZIndex::Global(20),
+                ..default()
+            },
+        };
+    }
+    fn child_builder(
+        font: Handle<Font>,
+        theme: &Theme,
+        achievements: &Achievements,
+    ) -> impl FnOnce(&mut ChildBuilder) {
+        let style = TextStyle {
+            font,
+            font_size: SCORE_FONT_SIZE / 2.0,
+            color: theme.score_text,
+        };
+        let small = TextStyle {
+            font_size: SCORE_FONT_SIZE / 3.0,
+            ..style.clone()
+        };
+        let unlocked = achievements.unlocked.len();
+        let total = achievements.definitions.len();
+        let lines: Vec<(String, String)> = achievements
+            .definitions
+            .iter()
+            .map(|achievement| {
+                let mark = match achievements.is_unlocked(&achievement.id) {
+                    true => "[x]",
+                    false => "[ ]",
+                };
+                return (
+                    format!("{} {}", mark, achievement.name),
+                    achievement.description.clone(),
+                );
+            })
+            .collect();
+        return move |parent| {
+            parent.spawn(TextBundle::from_section(
+                format!("ACHIEVEMENTS {}/{}", unlocked, total),
+                style.clone(),
+            ));
+            for (name, description) in lines {
+                parent.spawn(TextBundle::from_sections([
+                    TextSection::new(name, style.clone()),
+                    TextSection::new(format!("  {}", description), small.clone()),
+                ]));
+            }
+            parent.spawn(TextBundle::from_section("G: close", small));
+        };
+    }
+}
+
+pub fn create_gallery(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    settings: &GameSettings,
+    achievements: &Achievements,
+) {
+    let font = asset_server.load(MAIN_FONT_NAME);
+    commands
+        .spawn(GalleryBundle::new(&settings.theme))
+        .with_children(GalleryBundle::child_builder(
+            font,
+            &settings.theme,
+            achievements,
+        ));
+}
@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::analysis_panel::{AnalysisBar, AnalysisDetail, AnalysisPanel},
+    constants::font::{MAIN_FONT_NAME, SCORE_FONT_SIZE},
+    resources::{
+        game_analysis::GameAnalysis,
+        game_settings::{GameSettings, Theme},
+    },
+};
+
+// 評価の推移のグラフの高さ
+const TIMELINE_HEIGHT: f32 = 80.0;
+
+// 盤面の上に重ねて、評価の推移のグラフと選んだ手の説明を出す
+#[derive(Bundle)]
+struct AnalysisPanelBundle {
+    marker: AnalysisPanel,
+    node_bundle: NodeBundle,
+}
+
+impl AnalysisPanelBundle {
+    fn new(theme: &Theme) -> Self {
+        return Self {
+            marker: AnalysisPanel,
+            node_bundle: NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(8.0),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: BackgroundColor(theme.board.with_a(0.9)),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+        };
+    }
+    fn child_builder(
+        font: Handle<Font>,
+        theme: &Theme,
+        analysis: &GameAnalysis,
+    ) -> impl FnOnce(&mut ChildBuilder) {
+        let style = TextStyle {
+            font,
+            font_size: SCORE_FONT_SIZE / 2.0,
+            color: theme.score_text,
+        };
+        let theme = theme.clone();
+        // 指した手の評価を、最善手の評価の最大で割った高さ
+        let max = analysis
+            .moves
+            .iter()
+            .map(|analysis| analysis.best_value)
+            .fold(1.0, f32::max);
+        let heights: Vec<f32> = analysis
+            .moves
+            .iter()
+            .map(|analysis| (analysis.value.unwrap_or(0.0) / max).clamp(0.0, 1.0) * 100.0)
+            .collect();
+        let width = 100.0 / heights.len().max(1) as f32;
+        return move |parent| {
+            parent.spawn(TextBundle::from_section(
+                "REVIEW  N/P: blunders  click: move",
+                style.clone(),
+            ));
+            let timeline = NodeBundle {
+                style: Style {
+                    width: Val::Percent(90.0),
+                    height: Val::Px(TIMELINE_HEIGHT),
+                    align_items: AlignItems::FlexEnd,
+                    ..default()
+                },
+                background_color: BackgroundColor(theme.board_tile),
+                ..default()
+            };
+            parent.spawn(timeline).with_children(|timeline| {
+                for (index, height) in heights.into_iter().enumerate() {
+                    timeline.spawn((
+                        AnalysisBar { index },
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Percent(width),
+                                height: Val::Percent(height.max(2.0)),
+                                ..default()
+                            },
+                            background_color: BackgroundColor(theme.tile_low),
+                            ..default()
+                        },
+                    ));
+                }
+            });
+            parent.spawn((
+                AnalysisDetail,
+                TextBundle::from_section("", style).with_text_justify(JustifyText::Center),
+            ));
+        };
+    }
+}
+
+pub fn create_analysis_panel(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
+    analysis: Res<GameAnalysis>,
+) {
+    let font = asset_server.load(MAIN_FONT_NAME);
+    commands
+        .spawn(AnalysisPanelBundle::new(&settings.theme))
+        .with_children(AnalysisPanelBundle::child_builder(
+            font,
+            &settings.theme,
+            &analysis,
+        ));
+}
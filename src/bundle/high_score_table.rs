@@ -0,0 +1,100 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::high_score_table::HighScoreTable,
+    constants::font::{MAIN_FONT_NAME, SCORE_FONT_SIZE},
+    resources::{
+        game_clock::minutes_seconds,
+        game_settings::{GameSettings, Theme},
+        high_scores::HighScores,
+    },
+    structs::game_mode::GameMode,
+};
+
+#[derive(Bundle)]
+struct HighScoreTableBundle {
+    marker: HighScoreTable,
+    node_bundle: NodeBundle,
+}
+
+impl HighScoreTableBundle {
+    fn new(theme: &Theme) -> Self {
+        return Self {
+            marker: HighScoreTable,
+            node_bundle: NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(4.0),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: BackgroundColor(theme.board),
+                z_index: ZIndex::Global(20),
+                ..default()
+            },
+        };
+    }
+    fn child_builder(
+        font: Handle<Font>,
+        theme: &Theme,
+        mode: &GameMode,
+        high_scores: &HighScores,
+    ) -> impl FnOnce(&mut ChildBuilder) {
+        let style = TextStyle {
+            font,
+            font_size: SCORE_FONT_SIZE / 2.0,
+            color: theme.score_text,
+        };
+        let small = TextStyle {
+            font_size: SCORE_FONT_SIZE / 3.0,
+            ..style.clone()
+        };
+        let title = format!("HIGH SCORES {}", mode.label());
+        let mut rows: Vec<String> = high_scores
+            .table(mode)
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                return format!(
+                    "{:>2}. {:>6}  tile {:>4}  {} moves  {}",
+                    i + 1,
+                    entry.score,
+                    entry.max_tile,
+                    entry.moves,
+                    minutes_seconds(std::time::Duration::from_secs(entry.seconds))
+                );
+            })
+            .collect();
+        if rows.is_empty() {
+            rows.push("no games yet".to_string());
+        }
+        return move |parent| {
+            parent.spawn(TextBundle::from_section(title, style.clone()));
+            for row in rows {
+                parent.spawn(TextBundle::from_section(row, small.clone()));
+            }
+            parent.spawn(TextBundle::from_section("T: close", small));
+        };
+    }
+}
+
+pub fn create_high_score_table(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    settings: &GameSettings,
+    high_scores: &HighScores,
+) {
+    let font = asset_server.load(MAIN_FONT_NAME);
+    commands
+        .spawn(HighScoreTableBundle::new(&settings.theme))
+        .with_children(HighScoreTableBundle::child_builder(
+            font,
+            &settings.theme,
+            &settings.mode,
+            high_scores,
+        ));
+}
@@ -1,49 +1,59 @@
 use crate::{
-    components::{main_board::MainBoard, position::Position},
-    constants::{
-        color::{BOARD_COLOR_0, BOARD_COLOR_1},
-        layout::{
-            GRID_HEIGHT, GRID_WIDTH, MAIN_AND_SCORE_BOARD_MARGIN, MAIN_BOARD_SIZE_2D,
-            SCORE_BOARD_HEIGHT, TILE_SIZE_2D,
-        },
+    components::{
+        ai_controller::AiController, board_state::BoardState, board_status::BoardStatus,
+        garbage_queue::GarbageQueue, main_board::MainBoard, position::Position, score::Score,
     },
+    constants::layout::TILE_SIZE_2D,
+    resources::game_settings::GameSettings,
 };
 use bevy::prelude::*;
-use itertools::iproduct;
+use bevy_prng::WyRand;
+use bevy_rand::{component::EntropyComponent, resource::GlobalEntropy, traits::ForkableRng};
+use rand_core::{RngCore, SeedableRng};
 
-// 背景の大きな盤
+// 背景の大きな盤. 盤面の状態と得点、乱数を持つ
 #[derive(Bundle)]
 struct MainBoardBundle {
     marker: MainBoard,
+    board_state: BoardState,
+    score: Score,
+    status: BoardStatus,
+    garbage: GarbageQueue,
+    rng: EntropyComponent<WyRand>,
     sprite_bunble: SpriteBundle,
 }
 
-impl Default for MainBoardBundle {
-    fn default() -> Self {
+impl MainBoardBundle {
+    fn new(settings: &GameSettings, index: usize, rng: EntropyComponent<WyRand>) -> Self {
+        let size = settings.board_size;
         return Self {
-            marker: MainBoard,
+            marker: MainBoard { index },
+            board_state: BoardState::new(size.width, size.height),
+            score: Score::default(),
+            status: BoardStatus::default(),
+            garbage: GarbageQueue::default(),
+            rng,
             sprite_bunble: SpriteBundle {
                 sprite: Sprite {
-                    color: BOARD_COLOR_0,
-                    custom_size: Some(MAIN_BOARD_SIZE_2D),
+                    color: settings.theme.board,
+                    custom_size: Some(settings.board_size.main_board_size_2d()),
                     ..default()
                 },
-                transform: Transform::from_xyz(
-                    0.0,
-                    -(SCORE_BOARD_HEIGHT + MAIN_AND_SCORE_BOARD_MARGIN) / 2.0,
-                    0.0,
+                transform: Transform::from_translation(
+                    settings.main_board_offset(index).extend(0.0),
                 ),
                 ..default()
             },
         };
     }
-}
-
-impl MainBoardBundle {
-    fn child_builder(parent: &mut ChildBuilder) {
-        for (i, j) in iproduct!(0..GRID_WIDTH, 0..GRID_HEIGHT) {
-            parent.spawn(MainBoardTileBundle::new(i, j));
-        }
+    fn child_builder(settings: &GameSettings) -> impl FnOnce(&mut ChildBuilder) {
+        let settings = settings.clone();
+        return move |parent| {
+            let size = settings.board_size;
+            for (i, j) in itertools::iproduct!(0..size.width, 0..size.height) {
+                parent.spawn(MainBoardTileBundle::new(&settings, Position::new(i, j)));
+            }
+        };
     }
 }
 
@@ -53,31 +63,39 @@ struct MainBoardTileBundle {
     sprite_bunble: SpriteBundle,
 }
 
-impl Default for MainBoardTileBundle {
-    fn default() -> Self {
+impl MainBoardTileBundle {
+    fn new(settings: &GameSettings, position: Position) -> Self {
         return Self {
             sprite_bunble: SpriteBundle {
                 sprite: Sprite {
-                    color: BOARD_COLOR_1,
+                    color: settings.theme.board_tile,
                     custom_size: Some(TILE_SIZE_2D),
                     ..default()
                 },
+                transform: settings.board_size.tile_transform(position, 5.0),
                 ..default()
             },
         };
     }
 }
 
-impl MainBoardTileBundle {
-    fn new(i: usize, j: usize) -> Self {
-        let mut val = Self::default();
-        val.sprite_bunble.transform = Position::new(i, j).to_transform(5.0);
-        return val;
+pub fn create_main_board(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    mut global: ResMut<GlobalEntropy<WyRand>>,
+) {
+    for (index, board_settings) in settings.boards.iter().enumerate() {
+        let rng = match board_settings.seed {
+            Some(seed) => EntropyComponent::seed_from_u64(seed),
+            None => global.fork_rng(),
+        };
+        let mut board = commands.spawn(MainBoardBundle::new(&settings, index, rng));
+        board.with_children(MainBoardBundle::child_builder(&settings));
+        if let Some(ai) = board_settings.ai {
+            board.insert(AiController {
+                agent: ai.agent.build(&settings, global.next_u64()),
+                timer: Timer::from_seconds(ai.interval, TimerMode::Repeating),
+            });
+        }
     }
 }
-
-pub fn create_main_board(mut commands: Commands) {
-    commands
-        .spawn(MainBoardBundle::default())
-        .with_children(MainBoardBundle::child_builder);
-}
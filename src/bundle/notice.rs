@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::notice::Notice,
+    constants::font::{MAIN_FONT_NAME, SCORE_FONT_SIZE},
+    resources::game_settings::{GameSettings, Theme},
+};
+
+#[derive(Bundle)]
+struct NoticeBundle {
+    marker: Notice,
+    text_bundle: TextBundle,
+}
+
+impl NoticeBundle {
+    fn new(text: String, font: Handle<Font>, theme: &Theme) -> Self {
+        return Self {
+            marker: Notice,
+            text_bundle: TextBundle::from_section(
+                text,
+                TextStyle {
+                    font,
+                    font_size: SCORE_FONT_SIZE / 2.0,
+                    color: theme.score_text,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(0.0),
+                left: Val::Px(0.0),
+                ..default()
+            })
+            .with_background_color(theme.board),
+        };
+    }
+}
+
+// 古いお知らせを消して、新しいお知らせを出す
+pub fn show_notice(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    settings: &GameSettings,
+    notices: &Query<Entity, With<Notice>>,
+    text: impl Into<String>,
+) {
+    for entity in notices.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    let font = asset_server.load(MAIN_FONT_NAME);
+    commands.spawn(NoticeBundle::new(text.into(), font, &settings.theme));
+}
@@ -1,15 +1,20 @@
 use bevy::prelude::*;
 
 use crate::{
+    components::main_board::MainBoard,
     components::score_board::ScoreBoard,
-    components::score_text::ScoreText,
-    constants::{
-        color::{BOARD_COLOR_0, SCORE_TEXT_COLOR},
-        font::SCORE_FONT_SIZE,
-        layout::{MAIN_BOARD_WIDTH, SCORE_BOARD_HEIGHT},
+    components::score_text::{BestScoreText, GameClockText, MoveCountText, ScoreDelta, ScoreText},
+    constants::{font::SCORE_FONT_SIZE, layout::SCORE_BOARD_HEIGHT},
+    resources::{
+        best_score::BestScore,
+        game_settings::{GameSettings, Theme},
     },
 };
 
+// "+N" を出しておく秒数と、動き始める高さ
+const SCORE_DELTA_SECONDS: f32 = 0.8;
+const SCORE_DELTA_TOP: f32 = SCORE_FONT_SIZE / 2.0;
+
 #[derive(Bundle)]
 struct ScoreBoardBundle {
     marker: ScoreBoard,
@@ -22,50 +27,149 @@ struct ScoreTextBundle {
     text_bundle: TextBundle,
 }
 
-impl Default for ScoreBoardBundle {
-    fn default() -> Self {
+impl ScoreBoardBundle {
+    fn new(settings: &GameSettings, index: usize) -> Self {
+        let width = settings.board_size.main_board_size_2d().x;
         return Self {
             marker: ScoreBoard,
             node_bundle: NodeBundle {
                 style: Style {
-                    width: Val::Px(MAIN_BOARD_WIDTH),
+                    width: Val::Px(width),
                     height: Val::Px(SCORE_BOARD_HEIGHT),
                     flex_direction: FlexDirection::Row,
                     align_items: AlignItems::Center,
                     justify_content: JustifyContent::SpaceBetween,
                     position_type: PositionType::Absolute,
                     top: Val::Px(0.0),
-                    left: Val::Px(0.0),
+                    left: Val::Px(settings.main_board_left(index)),
                     ..default()
                 },
-                background_color: BackgroundColor(BOARD_COLOR_0),
+                background_color: BackgroundColor(settings.theme.board),
                 ..default()
             },
         };
     }
-}
-
-impl ScoreBoardBundle {
-    fn child_builder(font: Handle<Font>) -> impl FnOnce(&mut ChildBuilder) {
+    // 左に Score, 右に BestScore と手数と時間
+    fn child_builder(
+        font: Handle<Font>,
+        theme: &Theme,
+        board: Entity,
+        best_score: usize,
+    ) -> impl FnOnce(&mut ChildBuilder) {
         let style = TextStyle {
             font: font.clone(),
             font_size: SCORE_FONT_SIZE,
-            color: SCORE_TEXT_COLOR,
+            color: theme.score_text,
+        };
+        let small = TextStyle {
+            font_size: SCORE_FONT_SIZE / 3.0,
+            ..style.clone()
+        };
+        let column = |align_items: AlignItems| NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                align_items,
+                ..default()
+            },
             ..default()
         };
         return move |parent| {
-            parent.spawn(TextBundle::from_section("score:", style.clone()));
-            parent.spawn(ScoreTextBundle {
-                marker: ScoreText,
-                text_bundle: TextBundle::from_section("0", style.clone()),
-            });
+            parent
+                .spawn(column(AlignItems::FlexStart))
+                .with_children(|score| {
+                    score.spawn(TextBundle::from_section("score", small.clone()));
+                    score.spawn(ScoreTextBundle {
+                        marker: ScoreText { board },
+                        text_bundle: TextBundle::from_section("0", style.clone()),
+                    });
+                });
+            parent
+                .spawn(column(AlignItems::FlexEnd))
+                .with_children(|details| {
+                    details.spawn((
+                        BestScoreText,
+                        TextBundle::from_sections([
+                            TextSection::new("best ", small.clone()),
+                            TextSection::new(best_score.to_string(), small.clone()),
+                        ]),
+                    ));
+                    details.spawn((
+                        MoveCountText { board },
+                        TextBundle::from_sections([
+                            TextSection::new("moves ", small.clone()),
+                            TextSection::new("0", small.clone()),
+                        ]),
+                    ));
+                    details.spawn((
+                        GameClockText,
+                        TextBundle::from_sections([
+                            TextSection::new("time ", small.clone()),
+                            TextSection::new("0:00", small.clone()),
+                        ]),
+                    ));
+                });
         };
     }
 }
 
-pub fn create_score_board(mut commands: Commands, asset_server: Res<AssetServer>) {
+// MainBoard ごとに、その上に ScoreBoard を作る
+pub fn create_score_board(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
+    best_score: Res<BestScore>,
+    query: Query<(Entity, &MainBoard)>,
+) {
     let font = asset_server.load("fonts/Kenney Space.ttf");
-    commands
-        .spawn(ScoreBoardBundle::default())
-        .with_children(ScoreBoardBundle::child_builder(font));
+    for (board, main_board) in query.iter() {
+        commands
+            .spawn(ScoreBoardBundle::new(&settings, main_board.index))
+            .with_children(ScoreBoardBundle::child_builder(
+                font.clone(),
+                &settings.theme,
+                board,
+                best_score.value(),
+            ));
+    }
+}
+
+// Score の列の右に "+N" を出す
+pub fn spawn_score_delta(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    theme: &Theme,
+    column: Entity,
+    gained: usize,
+) {
+    let font = asset_server.load("fonts/Kenney Space.ttf");
+    let delta = commands
+        .spawn((
+            ScoreDelta {
+                timer: Timer::from_seconds(SCORE_DELTA_SECONDS, TimerMode::Once),
+            },
+            TextBundle::from_section(
+                format!("+{}", gained),
+                TextStyle {
+                    font,
+                    font_size: SCORE_FONT_SIZE / 2.0,
+                    color: theme.score_text,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(SCORE_DELTA_TOP),
+                left: Val::Percent(100.0),
+                ..default()
+            }),
+        ))
+        .id();
+    commands.entity(column).add_child(delta);
+}
+
+// 経過の割合 t に合わせて上に動かし、薄くする
+pub fn animate_score_delta(style: &mut Style, text: &mut Text, t: f32) {
+    style.top = Val::Px(SCORE_DELTA_TOP * (1.0 - t));
+    for section in text.sections.iter_mut() {
+        section.style.color.set_a(1.0 - t);
+    }
 }
@@ -0,0 +1,185 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{
+        stats_screen::{StatsFooter, StatsScreen},
+        tile::Tile,
+    },
+    constants::font::{MAIN_FONT_NAME, SCORE_FONT_SIZE},
+    resources::{
+        game_settings::{GameSettings, Theme},
+        lifetime_stats::LifetimeStats,
+    },
+    structs::direction::Direction,
+};
+
+// グラフの 1 本の高さ
+const BAR_HEIGHT: f32 = 12.0;
+
+pub const STATS_FOOTER_TEXT: &str = "E: export  Tab: close";
+
+// 盤面の上に重ねて、数値と方向ごと・rank ごとの棒グラフを出す
+#[derive(Bundle)]
+struct StatsScreenBundle {
+    marker: StatsScreen,
+    node_bundle: NodeBundle,
+}
+
+impl StatsScreenBundle {
+    fn new(theme: &Theme) -> Self {
+        return Self {
+            marker: StatsScreen,
+            node_bundle: NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(2.0),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: BackgroundColor(theme.board),
+                z_index: ZIndex::Global(20),
+                ..default()
+            },
+        };
+    }
+    fn child_builder(
+        font: Handle<Font>,
+        theme: &Theme,
+        stats: &LifetimeStats,
+    ) -> impl FnOnce(&mut ChildBuilder) {
+        let style = TextStyle {
+            font,
+            font_size: SCORE_FONT_SIZE / 2.0,
+            color: theme.score_text,
+        };
+        let small = TextStyle {
+            font_size: SCORE_FONT_SIZE / 3.0,
+            ..style.clone()
+        };
+        let theme = theme.clone();
+        let seconds = stats.time_played as u64;
+        let lines = [
+            "STATS".to_string(),
+            format!(
+                "games {}  average {:.0}",
+                stats.games_played,
+                stats.average_score()
+            ),
+            format!(
+                "moves {}  best tile {}",
+                stats.total_moves, stats.highest_tile
+            ),
+            format!(
+                "time {}:{:02}:{:02}",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            ),
+            format!(
+                "2048 streak {} (best {})",
+                stats.current_2048_streak, stats.best_2048_streak
+            ),
+        ];
+        let directions: Vec<(String, u64)> = Direction::ALL
+            .into_iter()
+            .map(|direction| (format!("{:?}", direction), stats.direction_count(direction)))
+            .collect();
+        let merges: Vec<(String, u64)> = stats
+            .merges
+            .iter()
+            .map(|(rank, count)| (Tile(*rank).to_string(), *count))
+            .collect();
+        return move |parent| {
+            for line in lines {
+                parent.spawn(TextBundle::from_section(line, style.clone()));
+            }
+            for bars in [directions, merges] {
+                let max = bars
+                    .iter()
+                    .map(|(_, count)| *count)
+                    .max()
+                    .unwrap_or(0)
+                    .max(1);
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            width: Val::Percent(80.0),
+                            flex_direction: FlexDirection::Column,
+                            margin: UiRect::top(Val::Px(6.0)),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|chart| {
+                        for (label, count) in bars {
+                            spawn_bar(chart, &small, &theme, label, count, max);
+                        }
+                    });
+            }
+            parent.spawn((
+                StatsFooter,
+                TextBundle::from_section(STATS_FOOTER_TEXT, small.clone()),
+            ));
+        };
+    }
+}
+
+// ラベル, 数, 最大値に対する長さの棒
+fn spawn_bar(
+    chart: &mut ChildBuilder,
+    style: &TextStyle,
+    theme: &Theme,
+    label: String,
+    count: u64,
+    max: u64,
+) {
+    chart
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(4.0),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|row| {
+            row.spawn(
+                TextBundle::from_section(format!("{} {}", label, count), style.clone()).with_style(
+                    Style {
+                        width: Val::Percent(40.0),
+                        ..default()
+                    },
+                ),
+            );
+            row.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Percent(60.0 * count as f32 / max as f32),
+                    height: Val::Px(BAR_HEIGHT),
+                    ..default()
+                },
+                background_color: BackgroundColor(theme.tile_low),
+                ..default()
+            });
+        });
+}
+
+pub fn create_stats_screen(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    settings: &GameSettings,
+    stats: &LifetimeStats,
+) {
+    let font = asset_server.load(MAIN_FONT_NAME);
+    commands
+        .spawn(StatsScreenBundle::new(&settings.theme))
+        .with_children(StatsScreenBundle::child_builder(
+            font,
+            &settings.theme,
+            stats,
+        ));
+}
@@ -1,11 +1,14 @@
 use bevy::prelude::*;
 use bevy::text::{Text, Text2dBounds};
+use bevy::utils::HashSet;
 
+use crate::components::board_state::BoardState;
 use crate::components::main_board::MainBoard;
 use crate::constants::font::MAIN_FONT_NAME;
+use crate::resources::game_settings::GameSettings;
 use crate::{
     components::{position::Position, tile::Tile},
-    constants::{color::TILE_TEXT_COLOR, font::TILE_FONT_SIZE, layout::TILE_SIZE_2D},
+    constants::{font::TILE_FONT_SIZE, layout::TILE_SIZE_2D},
 };
 
 // // 盤面の状態の取得
@@ -24,41 +27,29 @@ struct TileBundle {
     sprite_bunble: SpriteBundle,
 }
 
-impl Default for TileBundle {
-    fn default() -> Self {
-        let tile = Tile(1);
-        let position = Position { x: 0, y: 0 };
+impl TileBundle {
+    fn new(tile: Tile, position: Position, settings: &GameSettings) -> Self {
         return Self {
             tile,
             position,
             sprite_bunble: SpriteBundle {
                 sprite: Sprite {
-                    color: Color::from(tile),
+                    color: settings.theme.tile_color(tile),
                     custom_size: Some(TILE_SIZE_2D),
                     ..default()
                 },
-                transform: position.into(),
+                transform: settings.board_size.tile_transform(position, 10.0),
                 ..default()
             },
         };
     }
-}
-
-impl TileBundle {
-    fn new(tile: Tile, position: Position) -> Self {
-        let mut val = Self::default();
-        val.tile = tile;
-        val.position = position;
-        val.sprite_bunble.transform = position.into();
-        return val;
-    }
-    fn child_builder(&self, font: Handle<Font>) -> impl FnOnce(&mut ChildBuilder) {
+    fn child_builder(&self, font: Handle<Font>, color: Color) -> impl FnOnce(&mut ChildBuilder) {
         let text = Text::from_section(
             self.tile.to_string(),
             TextStyle {
                 font,
                 font_size: TILE_FONT_SIZE,
-                color: TILE_TEXT_COLOR,
+                color,
             },
         );
         return move |parent| {
@@ -77,8 +68,9 @@ impl TileBundle {
     }
 }
 
-#[derive(Event, Eq, PartialEq)]
+#[derive(Event, Clone, Copy, Debug, Eq, PartialEq)]
 pub struct TileSpawnEvent {
+    pub board: Entity,
     pub tile: Tile,
     pub position: Position,
 }
@@ -86,18 +78,49 @@ pub struct TileSpawnEvent {
 // 任意の Position への Tile の追加
 pub fn spawn_tiles(
     mut tile_spawn_evr: EventReader<TileSpawnEvent>,
+    mut query: Query<&mut BoardState>,
+) {
+    for ev in tile_spawn_evr.read() {
+        if let Ok(mut board) = query.get_mut(ev.board) {
+            board.set(ev.position, Some(ev.tile));
+        }
+    }
+}
+
+// BoardState に合わせて、子の Tile の Entity を追加・更新・削除
+pub fn sync_tiles(
     mut commands: Commands,
-    query_p: Query<Entity, With<MainBoard>>,
+    query_p: Query<(Entity, &BoardState, Option<&Children>), With<MainBoard>>,
+    mut query_c: Query<(&Position, &mut Tile)>,
     asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
 ) {
-    for ev in tile_spawn_evr.read() {
-        let font = asset_server.load(MAIN_FONT_NAME);
-        let tile_bundle = TileBundle::new(ev.tile, ev.position);
-        let child = commands
-            .spawn(tile_bundle.clone())
-            .with_children(tile_bundle.child_builder(font))
-            .id();
-        let parent = query_p.single();
-        commands.entity(parent).push_children(&[child]);
+    for (parent, board, children) in query_p.iter() {
+        let mut shown: HashSet<Position> = HashSet::new();
+        for child in children.into_iter().flatten() {
+            let Ok((pos, mut tile)) = query_c.get_mut(*child) else {
+                continue;
+            };
+            match board.get(*pos) {
+                Some(t) if shown.insert(*pos) => {
+                    if *tile != t {
+                        *tile = t;
+                    }
+                }
+                _ => commands.entity(*child).despawn_recursive(),
+            }
+        }
+        for (position, tile) in board.tiles() {
+            if shown.contains(&position) {
+                continue;
+            }
+            let font = asset_server.load(MAIN_FONT_NAME);
+            let tile_bundle = TileBundle::new(tile, position, &settings);
+            let child = commands
+                .spawn(tile_bundle.clone())
+                .with_children(tile_bundle.child_builder(font, settings.theme.tile_text))
+                .id();
+            commands.entity(parent).push_children(&[child]);
+        }
     }
 }
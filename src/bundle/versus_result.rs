@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{
+        board_state::BoardState, main_board::MainBoard, score::Score, versus_result::VersusResult,
+    },
+    constants::font::{MAIN_FONT_NAME, SCORE_FONT_SIZE},
+    plugins::versus::{decide_outcome, VersusOutcome},
+    resources::game_settings::{GameSettings, Theme},
+};
+
+// 対戦の結果を盤面の上に重ねて表示する
+#[derive(Bundle)]
+struct VersusResultBundle {
+    marker: VersusResult,
+    node_bundle: NodeBundle,
+}
+
+impl VersusResultBundle {
+    fn new(theme: &Theme) -> Self {
+        return Self {
+            marker: VersusResult,
+            node_bundle: NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: BackgroundColor(theme.board.with_a(0.8)),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+        };
+    }
+    fn child_builder(
+        font: Handle<Font>,
+        theme: &Theme,
+        outcome: VersusOutcome,
+        scores: Vec<Score>,
+    ) -> impl FnOnce(&mut ChildBuilder) {
+        let style = TextStyle {
+            font,
+            font_size: SCORE_FONT_SIZE,
+            color: theme.score_text,
+        };
+        return move |parent| {
+            let headline = match outcome {
+                VersusOutcome::Winner(index) => format!("PLAYER {} WINS!", index + 1),
+                VersusOutcome::Draw => "DRAW".to_string(),
+            };
+            parent.spawn(TextBundle::from_section(headline, style.clone()));
+            for (index, score) in scores.iter().enumerate() {
+                parent.spawn(TextBundle::from_section(
+                    format!("P{}: {}", index + 1, score),
+                    style.clone(),
+                ));
+            }
+        };
+    }
+}
+
+pub fn create_versus_result(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
+    query: Query<(&MainBoard, &BoardState, &Score)>,
+) {
+    let Some(versus) = settings.versus else {
+        return;
+    };
+    let mut boards: Vec<(&MainBoard, &BoardState, &Score)> = query.iter().collect();
+    boards.sort_by_key(|(main_board, _, _)| main_board.index);
+    let boards: Vec<(&BoardState, Score)> = boards
+        .into_iter()
+        .map(|(_, board, score)| (board, *score))
+        .collect();
+    let outcome = decide_outcome(&boards, versus.win_condition);
+    let scores = boards.iter().map(|(_, score)| *score).collect();
+    let font = asset_server.load(MAIN_FONT_NAME);
+    commands
+        .spawn(VersusResultBundle::new(&settings.theme))
+        .with_children(VersusResultBundle::child_builder(
+            font,
+            &settings.theme,
+            outcome,
+            scores,
+        ));
+}
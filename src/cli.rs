@@ -0,0 +1,486 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::ai::{ntuple, AgentKind};
+use crate::components::tile::Tile;
+use crate::headless::{
+    bot::{BotFormat, BotOptions},
+    tournament::{Entrant, TournamentOptions},
+    train::TrainOptions,
+};
+use crate::resources::game_settings::{
+    AchievementSettings, AiSettings, CoachSettings, DailySettings, GameSettings, GarbageRules,
+    HighScoreSettings, HttpApiSettings, PuzzleSettings, StatsSettings, WinCondition,
+};
+use crate::structs::game_mode::GameMode;
+
+// コマンドライン引数の解釈. main.rs は parse で得た Command を実行するだけ
+//
+// 引数なしなら 1 人用
+// versus [--target <数>] [--seed <seed>] [--garbage] [--ai] で 2 人対戦
+// battle [--target <数>] [--seed <seed>] [--garbage] [--rounds <数>] [--p1 <agent>] [--p2 <agent>] で描画なしの AI 同士の対戦
+// simulate [--agent <agent>] [--games <数>] [--seed <seed>] [--max-moves <数>] で描画なしの 1 人用を連続で進める
+// bot [--json] [--timeout-ms <数>] [--seed <seed>] [--max-turns <数>] で標準入出力の bot に遊ばせる
+// host [--port <数>] [--target <数>] [--garbage] と join <addr> [--target <数>] [--garbage] で通信対戦
+// tournament [--agents <agent>,<agent>,...] [--bot <name>=<command>]... [--games <数>] [--seed <seed>]
+//   [--max-moves <数>] [--timeout-ms <数>] [--csv <path>] で同じ seed の列を遊ばせて順位をつける
+// stats [--json] で通算の統計を CSV か JSON で出す
+// daily [--verify <path>] で今日の Daily の seed と記録を出す. --verify で共有された記録を確かめる (- なら標準入力)
+// train [--games <数>] [--alpha <数>] [--seed <seed>] [--weights <path>] [--out <path>] で n-tuple の重みを学習する
+// --weights <path> で GameSettings::ntuple の重みを読み込み, --autoplay で自動操作, --hint で H キーのヒント
+// --agent <agent> で --ai と --autoplay の Agent を選ぶ. <agent> の書き方は AgentKind の FromStr
+// --mode <mode> で 1 人用のモードを選ぶ. <mode> の書き方は GameMode の FromStr
+// --puzzle <id> で assets/puzzles のその id のパズルから始める
+// --http-port <port> で localhost に HTTP の API を開く
+// --coach [--coach-threshold <割合>] で危ない手の前に確認し、coach.log に記録する
+// --analysis で GameOver の後に手を見直す
+// --no-save で記録のファイル (統計, 実績, 得点表, Daily, パズル) を読み書きしない
+
+pub const DEFAULT_PORT: u16 = 20480;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CliError(pub String);
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", self.0);
+    }
+}
+
+impl std::error::Error for CliError {}
+
+#[derive(Clone, Debug)]
+pub enum Command {
+    // 窓を開いて遊ぶ
+    Play(Play),
+    Battle {
+        settings: GameSettings,
+        seed: u64,
+        p1: AgentKind,
+        p2: AgentKind,
+        max_rounds: usize,
+    },
+    Simulate {
+        agent: AgentKind,
+        seeds: Vec<u64>,
+        max_moves: Option<usize>,
+    },
+    Tournament {
+        entrants: Vec<Entrant>,
+        options: TournamentOptions,
+        csv: Option<String>,
+    },
+    Stats {
+        json: bool,
+    },
+    Daily {
+        verify: Option<String>,
+    },
+    Bot(BotOptions),
+    Train {
+        options: TrainOptions,
+        // Some なら続きから学習する
+        weights: Option<String>,
+        out: String,
+    },
+}
+
+// 通信対戦の繋ぎ方. seed は自分の盤面の seed で、None なら時刻から決める
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Connect {
+    Host { port: u16, seed: Option<u64> },
+    Join { addr: String, seed: Option<u64> },
+}
+
+#[derive(Clone, Debug)]
+pub struct Play {
+    // 1 人用か 2 人対戦の設定. 通信対戦では NetSession::game_settings に渡す
+    pub settings: GameSettings,
+    pub connect: Option<Connect>,
+    pub options: PlayOptions,
+}
+
+// 盤面の組み方によらない指定. 通信対戦では NetSession::game_settings の後に入れる
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlayOptions {
+    pub mode: Option<GameMode>,
+    pub puzzle: Option<String>,
+    pub http_port: Option<u16>,
+    pub weights: Option<String>,
+    pub autoplay: Option<AgentKind>,
+    pub hint: bool,
+    pub coach: Option<CoachSettings>,
+    pub analysis: bool,
+    // --no-save がなければ true
+    pub save: bool,
+}
+
+impl PlayOptions {
+    // 記録のファイルを使うか. この端末で人が 1 人で遊ぶときだけ
+    pub fn saves(&self, settings: &GameSettings) -> bool {
+        return self.save && settings.versus.is_none() && self.autoplay.is_none();
+    }
+    // 引数の指定を settings に入れ、saves なら記録のファイルを有効にする
+    pub fn apply(&self, settings: &mut GameSettings) {
+        if self.saves(settings) {
+            // 1 人用の統計を stats.json に残す. Tab で見る
+            settings.stats = Some(StatsSettings::default());
+            // G で実績の一覧
+            settings.achievements = Some(AchievementSettings::default());
+            // T でモードごとの得点表
+            settings.high_scores = Some(HighScoreSettings::default());
+            settings.daily = Some(DailySettings::default());
+            settings.puzzles = Some(PuzzleSettings {
+                start: self.puzzle.clone(),
+                ..default()
+            });
+        }
+        // タイトル画面でも変えられる
+        if let Some(mode) = self.mode {
+            settings.mode = mode;
+        }
+        if let Some(port) = self.http_port {
+            settings.http_api = Some(HttpApiSettings { port });
+        }
+        if let Some(agent) = self.autoplay {
+            settings.boards[0].ai = Some(AiSettings { agent, ..default() });
+        }
+        if self.hint {
+            settings.ui.hint = Some(AgentKind::NTuple);
+        }
+        if let Some(coach) = &self.coach {
+            settings.coach = Some(coach.clone());
+        }
+        if self.analysis {
+            settings.ui.analysis = true;
+            settings.ui.exit_on_game_over = false;
+        }
+    }
+}
+
+struct Args<'a>(&'a [String]);
+
+impl Args<'_> {
+    fn command(&self) -> Option<&str> {
+        return self.0.first().map(String::as_str);
+    }
+    fn flag(&self, name: &str) -> bool {
+        return self.0.iter().any(|arg| arg == name);
+    }
+    fn text(&self, name: &str) -> Option<&str> {
+        let i = self.0.iter().position(|arg| arg == name)?;
+        return self.0.get(i + 1).map(String::as_str);
+    }
+    // 同じ名前の引数をすべて
+    fn texts(&self, name: &str) -> Vec<&str> {
+        return self
+            .0
+            .windows(2)
+            .filter(|pair| pair[0] == name)
+            .map(|pair| pair[1].as_str())
+            .collect();
+    }
+    // 読めない値はエラーにする
+    fn value<T: FromStr>(&self, name: &str) -> Result<Option<T>, CliError>
+    where
+        T::Err: fmt::Display,
+    {
+        let Some(text) = self.text(name) else {
+            return Ok(None);
+        };
+        return text
+            .parse()
+            .map(Some)
+            .map_err(|e| CliError(format!("{} {}: {}", name, text, e)));
+    }
+    fn versus_settings(&self) -> Result<GameSettings, CliError> {
+        let win_condition = match self.value::<u64>("--target")? {
+            Some(num) => WinCondition::TargetTile(Tile(num.max(2).ilog2() as u8)),
+            None => WinCondition::Score,
+        };
+        let mut settings = GameSettings::versus(win_condition, self.value("--seed")?);
+        if self.flag("--garbage") {
+            if let Some(versus) = settings.versus.as_mut() {
+                versus.garbage = Some(GarbageRules::default());
+            }
+        }
+        if self.flag("--ai") {
+            settings.boards[1].ai = Some(AiSettings {
+                agent: self.value("--agent")?.unwrap_or_default(),
+                ..default()
+            });
+        }
+        return Ok(settings);
+    }
+    fn play_options(&self) -> Result<PlayOptions, CliError> {
+        let mut mode = self.value("--mode")?;
+        if self.flag("--puzzle") {
+            mode = Some(GameMode::Puzzle);
+        }
+        let coach = match self.flag("--coach") {
+            true => {
+                let default = CoachSettings::default();
+                Some(CoachSettings {
+                    loss_threshold: self
+                        .value("--coach-threshold")?
+                        .unwrap_or(default.loss_threshold),
+                    log_path: Some("coach.log".to_string()),
+                    ..default
+                })
+            }
+            false => None,
+        };
+        let autoplay = match self.flag("--autoplay") {
+            true => Some(self.value("--agent")?.unwrap_or(AgentKind::NTuple)),
+            false => None,
+        };
+        let save = !self.flag("--no-save");
+        // Daily とパズルは記録のファイルがないと遊べない
+        if let Some(mode @ (GameMode::Daily | GameMode::Puzzle)) = mode {
+            if !save || autoplay.is_some() {
+                return Err(CliError(format!(
+                    "{} can not be played with --no-save or --autoplay",
+                    mode
+                )));
+            }
+        }
+        return Ok(PlayOptions {
+            mode,
+            puzzle: self.text("--puzzle").map(str::to_string),
+            http_port: self.value("--http-port")?,
+            weights: self.text("--weights").map(str::to_string),
+            autoplay,
+            hint: self.flag("--hint"),
+            coach,
+            analysis: self.flag("--analysis"),
+            save,
+        });
+    }
+    // seed から games 個の連続した seed
+    fn seeds(&self, games: u64) -> Result<Vec<u64>, CliError> {
+        let seed = self.value("--seed")?.unwrap_or(0_u64);
+        let games = self.value("--games")?.unwrap_or(games);
+        return Ok((0..games).map(|i| seed.wrapping_add(i)).collect());
+    }
+    fn timeout(&self) -> Result<Duration, CliError> {
+        return Ok(Duration::from_millis(
+            self.value("--timeout-ms")?.unwrap_or(1000),
+        ));
+    }
+}
+
+pub fn parse(args: &[String]) -> Result<Command, CliError> {
+    let args = Args(args);
+    match args.command() {
+        Some("battle") => {
+            return Ok(Command::Battle {
+                settings: args.versus_settings()?,
+                seed: args.value("--seed")?.unwrap_or(0),
+                p1: args.value("--p1")?.unwrap_or(AgentKind::Greedy),
+                p2: args.value("--p2")?.unwrap_or(AgentKind::Random),
+                max_rounds: args.value("--rounds")?.unwrap_or(100_000),
+            });
+        }
+        Some("simulate") => {
+            return Ok(Command::Simulate {
+                agent: args.value("--agent")?.unwrap_or_default(),
+                seeds: args.seeds(10)?,
+                max_moves: args.value("--max-moves")?,
+            });
+        }
+        Some("tournament") => return parse_tournament(&args),
+        Some("stats") => {
+            return Ok(Command::Stats {
+                json: args.flag("--json"),
+            });
+        }
+        Some("daily") => {
+            return Ok(Command::Daily {
+                verify: args.text("--verify").map(str::to_string),
+            });
+        }
+        Some("bot") => {
+            return Ok(Command::Bot(BotOptions {
+                format: match args.flag("--json") {
+                    true => BotFormat::Json,
+                    false => BotFormat::Text,
+                },
+                timeout: args.timeout()?,
+                seed: args.value("--seed")?.unwrap_or(0),
+                max_turns: args.value("--max-turns")?,
+            }));
+        }
+        Some("train") => {
+            return Ok(Command::Train {
+                options: TrainOptions {
+                    games: args.value("--games")?.unwrap_or(10_000),
+                    learning_rate: args.value("--alpha")?.unwrap_or(0.1),
+                    seed: args.value("--seed")?.unwrap_or(0),
+                    ..default()
+                },
+                weights: args.text("--weights").map(str::to_string),
+                out: args
+                    .text("--out")
+                    .unwrap_or(ntuple::DEFAULT_WEIGHTS_PATH)
+                    .to_string(),
+            });
+        }
+        _ => {}
+    }
+    // 引数なしか -- で始まるなら 1 人用. 知らない名前は打ち間違いとして扱う
+    match args.command() {
+        None | Some("versus" | "host" | "join") => {}
+        Some(command) if command.starts_with("--") => {}
+        Some(command) => return Err(CliError(format!("unknown command: {}", command))),
+    }
+    // 対戦のルールは両方で同じ引数を与える
+    let connect = match args.command() {
+        Some("host") => Some(Connect::Host {
+            port: args.value("--port")?.unwrap_or(DEFAULT_PORT),
+            seed: args.value("--seed")?,
+        }),
+        Some("join") => Some(Connect::Join {
+            addr: args.0.get(1).cloned().unwrap_or_default(),
+            seed: args.value("--seed")?,
+        }),
+        _ => None,
+    };
+    let settings = match (args.command(), &connect) {
+        (Some("versus"), _) | (_, Some(_)) => args.versus_settings()?,
+        _ => GameSettings::default(),
+    };
+    return Ok(Command::Play(Play {
+        settings,
+        connect,
+        options: args.play_options()?,
+    }));
+}
+
+fn parse_tournament(args: &Args) -> Result<Command, CliError> {
+    let agents = args
+        .text("--agents")
+        .unwrap_or("random,greedy,corner,expectimax:2");
+    let mut entrants = Vec::new();
+    for text in agents.split(',').filter(|text| !text.is_empty()) {
+        let kind = text
+            .parse()
+            .map_err(|e| CliError(format!("--agents: {}", e)))?;
+        entrants.push(Entrant::Agent(kind));
+    }
+    for bot in args.texts("--bot") {
+        let Some((name, command)) = bot.split_once('=') else {
+            return Err(CliError(format!("--bot needs <name>=<command>: {}", bot)));
+        };
+        entrants.push(Entrant::External {
+            name: name.to_string(),
+            command: command.to_string(),
+        });
+    }
+    let options = TournamentOptions {
+        seeds: args.seeds(100)?,
+        max_moves: args.value("--max-moves")?,
+        timeout: args.timeout()?,
+    };
+    return Ok(Command::Tournament {
+        entrants,
+        options,
+        csv: args.text("--csv").map(str::to_string),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_line(line: &str) -> Result<Command, CliError> {
+        let args: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+        return parse(&args);
+    }
+
+    #[test]
+    fn parse_play_options() {
+        let Ok(Command::Play(play)) = parse_line("--puzzle corner --analysis") else {
+            panic!("not a play command");
+        };
+        assert!(play.connect.is_none());
+        assert!(play.settings.versus.is_none());
+        assert_eq!(play.options.mode, Some(GameMode::Puzzle));
+        assert_eq!(play.options.puzzle.as_deref(), Some("corner"));
+        let mut settings = play.settings.clone();
+        play.options.apply(&mut settings);
+        assert_eq!(settings.mode, GameMode::Puzzle);
+        assert!(settings.ui.analysis && !settings.ui.exit_on_game_over);
+        assert!(settings.stats.is_some() && settings.puzzles.is_some());
+
+        // 自動操作と対戦と --no-save では記録のファイルを使わない
+        for line in ["--autoplay", "versus", "--no-save"] {
+            let Ok(Command::Play(play)) = parse_line(line) else {
+                panic!("not a play command");
+            };
+            let mut settings = play.settings.clone();
+            play.options.apply(&mut settings);
+            assert!(
+                settings.stats.is_none() && settings.daily.is_none(),
+                "{}",
+                line
+            );
+            assert!(settings.high_scores.is_none() && settings.achievements.is_none());
+        }
+
+        let Ok(Command::Play(play)) = parse_line("host --seed 7 --garbage") else {
+            panic!("not a play command");
+        };
+        assert_eq!(
+            play.connect,
+            Some(Connect::Host {
+                port: DEFAULT_PORT,
+                seed: Some(7)
+            })
+        );
+        assert!(play
+            .settings
+            .versus
+            .is_some_and(|versus| versus.garbage.is_some()));
+    }
+
+    #[test]
+    fn parse_subcommands() {
+        let Ok(Command::Tournament {
+            entrants, options, ..
+        }) = parse_line("tournament --agents random,greedy --bot me=./me --games 3 --seed 5")
+        else {
+            panic!("not a tournament");
+        };
+        assert_eq!(entrants.len(), 3);
+        assert_eq!(options.seeds, [5, 6, 7]);
+        let Ok(Command::Simulate { seeds, .. }) = parse_line("simulate") else {
+            panic!("not a simulation");
+        };
+        assert_eq!(seeds.len(), 10);
+        assert!(matches!(
+            parse_line("stats --json"),
+            Ok(Command::Stats { json: true })
+        ));
+    }
+
+    #[test]
+    fn reject_bad_values() {
+        assert!(parse_line("--mode endless").is_err());
+        assert!(parse_line("simulate --games many").is_err());
+        assert!(parse_line("tournament --bot nameless").is_err());
+        assert!(parse_line("versus --ai --agent nobody").is_err());
+        assert!(parse_line("simulte --games 3").is_err());
+        assert!(parse_line("--mode daily --no-save").is_err());
+        assert!(parse_line("--puzzle corner --autoplay").is_err());
+        // u16 に入らない port は丸めずにエラーにする
+        assert!(parse_line("--http-port 70000").is_err());
+        assert!(parse_line("host --port 65536").is_err());
+        let Ok(Command::Play(play)) = parse_line("--http-port 8080") else {
+            panic!("not a play command");
+        };
+        assert_eq!(play.options.http_port, Some(8080));
+    }
+}
@@ -1,5 +1,18 @@
+pub mod achievement;
+pub mod ai_controller;
+pub mod analysis_panel;
+pub mod board_state;
+pub mod board_status;
+pub mod garbage_queue;
+pub mod high_score_table;
+pub mod lockstep;
 pub mod main_board;
+pub mod notice;
 pub mod position;
+pub mod puzzle_spawns;
+pub mod score;
 pub mod score_board;
 pub mod score_text;
+pub mod stats_screen;
 pub mod tile;
+pub mod versus_result;
@@ -0,0 +1,11 @@
+use bevy::prelude::*;
+
+// 実績を解除したときのお知らせ. timer が終わると消える
+#[derive(Component)]
+pub struct AchievementToast {
+    pub timer: Timer,
+}
+
+// 全ての実績と解除したかどうかの一覧
+#[derive(Component)]
+pub struct AchievementGallery;
@@ -0,0 +1,10 @@
+use bevy::prelude::*;
+
+use crate::ai::Agent;
+
+// AI が操作する盤面. timer ごとに 1 手動かす
+#[derive(Component)]
+pub struct AiController {
+    pub agent: Box<dyn Agent>,
+    pub timer: Timer,
+}
@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+
+// GameOver の後に手を見直す画面
+#[derive(Component)]
+pub struct AnalysisPanel;
+
+// 評価の推移のグラフの 1 本. GameAnalysis::moves の index
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AnalysisBar {
+    pub index: usize,
+}
+
+// 選んだ手の説明
+#[derive(Component)]
+pub struct AnalysisDetail;
@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+
+use crate::components::{position::Position, tile::Tile};
+use crate::constants::layout::{GRID_HEIGHT, GRID_WIDTH};
+use crate::plugins::calculate::{calc_tile_movements, TileMovement};
+use crate::structs::{direction::Direction, grid_array::GridArray};
+
+// rows_top_down での邪魔な Tile
+pub const BLOCKER_CELL: i64 = -1;
+
+// 盤面の状態. MainBoard の Entity が持ち、子の Tile の Entity はこれを表示するためのもの
+#[derive(Component, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct BoardState {
+    grid: GridArray<Option<Tile>>,
+    tile_count: usize,
+}
+
+impl Default for BoardState {
+    fn default() -> Self {
+        return Self::new(GRID_WIDTH, GRID_HEIGHT);
+    }
+}
+
+impl BoardState {
+    pub fn new(width: usize, height: usize) -> Self {
+        return Self {
+            grid: GridArray::new(width, height, None),
+            tile_count: 0,
+        };
+    }
+    pub fn grid(&self) -> &GridArray<Option<Tile>> {
+        return &self.grid;
+    }
+    pub fn get(&self, pos: Position) -> Option<Tile> {
+        return self.grid[pos];
+    }
+    pub fn set(&mut self, pos: Position, tile: Option<Tile>) {
+        let cell = &mut self.grid[pos];
+        match (cell.is_some(), tile.is_some()) {
+            (false, true) => self.tile_count += 1,
+            (true, false) => self.tile_count -= 1,
+            _ => {}
+        }
+        *cell = tile;
+    }
+    pub fn is_full(&self) -> bool {
+        return self.empty_count() == 0;
+    }
+    pub fn empty_count(&self) -> usize {
+        return self.grid.width() * self.grid.height() - self.tile_count;
+    }
+    pub fn tiles(&self) -> impl Iterator<Item = (Position, Tile)> + '_ {
+        return self
+            .grid
+            .iter()
+            .filter_map(|(pos, op)| op.map(|tile| (pos, tile)));
+    }
+    pub fn empty_positions(&self) -> impl Iterator<Item = Position> + '_ {
+        return self
+            .grid
+            .iter()
+            .filter_map(|(pos, op)| op.is_none().then_some(pos));
+    }
+    // 移動を盤面に反映し、得点を返す
+    pub fn apply(&mut self, movements: &[TileMovement]) -> usize {
+        let mut score = 0;
+        // 移動元を先に全て空けてから移動先を埋める
+        for movement in movements {
+            match *movement {
+                TileMovement::Slide { from, .. } => self.set(from, None),
+                TileMovement::Merge {
+                    from, partner_from, ..
+                } => {
+                    self.set(from, None);
+                    self.set(partner_from, None);
+                }
+            }
+        }
+        for movement in movements {
+            match *movement {
+                TileMovement::Slide { tile, to, .. } => self.set(to, Some(tile)),
+                TileMovement::Merge { to, rank, .. } => {
+                    self.set(to, Some(rank));
+                    score += rank.num();
+                }
+            }
+        }
+        return score;
+    }
+    // direction に動かした盤面と得点. 何も動かなければ None
+    pub fn moved(&self, direction: Direction) -> Option<(BoardState, usize)> {
+        let movements = calc_tile_movements(self, direction.falling_turn());
+        let mut board = self.clone();
+        let score = board.apply(&movements);
+        if board == *self {
+            return None;
+        }
+        return Some((board, score));
+    }
+    pub fn legal_directions(&self) -> impl Iterator<Item = Direction> + '_ {
+        return Direction::ALL
+            .into_iter()
+            .filter(|direction| self.moved(*direction).is_some());
+    }
+    // 上の行から並べた Tile の数. 0 は空きマス、BLOCKER_CELL は邪魔な Tile
+    pub fn rows_top_down(&self) -> Vec<Vec<i64>> {
+        return (0..self.grid.height())
+            .rev()
+            .map(|y| {
+                (0..self.grid.width())
+                    .map(|x| match self.get(Position::new(x, y)) {
+                        None => 0,
+                        Some(tile) if tile.is_blocker() => BLOCKER_CELL,
+                        Some(tile) => tile.num() as i64,
+                    })
+                    .collect()
+            })
+            .collect();
+    }
+    pub fn max_tile(&self) -> Option<Tile> {
+        return self.tiles().map(|(_, tile)| tile).max();
+    }
+}
+
+impl std::fmt::Display for BoardState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let grid = self.grid.map(|op| match op {
+            Some(tile) => tile.to_string(),
+            None => ".".to_string(),
+        });
+        return write!(f, "{}", grid);
+    }
+}
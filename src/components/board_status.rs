@@ -0,0 +1,11 @@
+use bevy::prelude::*;
+
+// 盤面ごとの進行状況
+#[derive(Component, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BoardStatus {
+    // 次の Spawn で Tile を出現させる
+    #[default]
+    Spawn,
+    Input,
+    GameOver,
+}
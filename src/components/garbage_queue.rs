@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+
+// 他の盤面から送られてきて、まだ出現していない邪魔な Tile
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GarbageQueue {
+    // (数, 出現するまでの残りターン数)
+    pending: Vec<(usize, usize)>,
+}
+
+impl GarbageQueue {
+    pub fn push(&mut self, count: usize, delay: usize) {
+        if count > 0 {
+            self.pending.push((count, delay));
+        }
+    }
+    // 1 ターン進めて、このターンに出現させる数を返す
+    pub fn tick(&mut self) -> usize {
+        let mut due = 0;
+        self.pending
+            .retain_mut(|(count, turns_left)| match *turns_left {
+                0 => {
+                    due += *count;
+                    return false;
+                }
+                _ => {
+                    *turns_left -= 1;
+                    return true;
+                }
+            });
+        return due;
+    }
+    pub fn pending_count(&self) -> usize {
+        return self.pending.iter().map(|(count, _)| count).sum();
+    }
+}
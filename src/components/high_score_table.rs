@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+// 今のモードの得点表
+#[derive(Component)]
+pub struct HighScoreTable;
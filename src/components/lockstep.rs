@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+// 通信対戦の盤面. 両方の入力が揃ってから同時に動かす
+#[derive(Component)]
+pub struct Lockstep;
@@ -1,4 +1,7 @@
 use bevy::prelude::*;
 
-#[derive(Component)]
-pub struct MainBoard;
+// 盤面の Entity. index は GameSettings::boards の何番目か
+#[derive(Component, Clone, Copy, Debug)]
+pub struct MainBoard {
+    pub index: usize,
+}
@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+// 画面の下端に出すお知らせ
+#[derive(Component)]
+pub struct Notice;
@@ -0,0 +1,40 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::components::{board_state::BoardState, position::Position, tile::Tile};
+
+// パズルの盤面で、乱数の代わりに出現させる Tile
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PuzzleSpawnQueue {
+    // 最初の Spawn で置く盤面
+    pub initial: Option<Vec<(Position, Tile)>>,
+    // None なら 2 ターン目からは乱数で出す
+    pub fixed: Option<VecDeque<(Position, Tile)>>,
+}
+
+impl PuzzleSpawnQueue {
+    // このターンに出現させる Tile. None なら乱数で決める
+    pub fn next(&mut self, board: &BoardState) -> Option<Vec<(Position, Tile)>> {
+        if let Some(initial) = self.initial.take() {
+            return Some(initial);
+        }
+        let fixed = self.fixed.as_mut()?;
+        let Some((position, tile)) = fixed.pop_front() else {
+            return Some(Vec::new());
+        };
+        // 埋まっていれば上の行から最初の空きマスに
+        let position = match board.get(position) {
+            None => Some(position),
+            Some(_) => board
+                .empty_positions()
+                .max_by_key(|position| (position.y, std::cmp::Reverse(position.x))),
+        };
+        return Some(
+            position
+                .map(|position| (position, tile))
+                .into_iter()
+                .collect(),
+        );
+    }
+}
@@ -2,10 +2,13 @@ use std::ops::AddAssign;
 
 use bevy::prelude::*;
 
-#[derive(Resource)]
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Score(usize);
 
 impl Score {
+    pub fn value(&self) -> usize {
+        return self.0;
+    }
     pub fn add(&mut self, val: usize) {
         *self += Score(val);
     }
@@ -23,8 +26,8 @@ impl AddAssign for Score {
     }
 }
 
-impl ToString for Score {
-    fn to_string(&self) -> String {
-        return self.0.to_string();
+impl std::fmt::Display for Score {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{}", self.0);
     }
 }
@@ -1,4 +1,27 @@
 use bevy::prelude::*;
 
+// board の Score を表示する Text
 #[derive(Component)]
-pub struct ScoreText;
+pub struct ScoreText {
+    pub board: Entity,
+}
+
+// BestScore を表示する Text
+#[derive(Component)]
+pub struct BestScoreText;
+
+// board の手数を表示する Text
+#[derive(Component)]
+pub struct MoveCountText {
+    pub board: Entity,
+}
+
+// GameClock を表示する Text
+#[derive(Component)]
+pub struct GameClockText;
+
+// 1 ターンで増えた点の "+N". 上に動きながら消える
+#[derive(Component)]
+pub struct ScoreDelta {
+    pub timer: Timer,
+}
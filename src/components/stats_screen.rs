@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+
+// LifetimeStats を表示する画面
+#[derive(Component)]
+pub struct StatsScreen;
+
+// Stats 画面の下端の操作の説明. 書き出した後はその結果
+#[derive(Component)]
+pub struct StatsFooter;
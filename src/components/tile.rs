@@ -1,11 +1,15 @@
 use bevy::prelude::*;
 
-use crate::constants::color::{TILE_COLOR_0, TILE_COLOR_1};
-
-#[derive(Component, Clone, Copy, PartialEq, Eq)]
+#[derive(Component, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct Tile(pub u8);
 
 impl Tile {
+    // 対戦で送られてくる邪魔な Tile. 移動はするが合体しない
+    pub const BLOCKER: Tile = Tile(0);
+
+    pub fn is_blocker(self) -> bool {
+        return self == Self::BLOCKER;
+    }
     pub fn double(&mut self) {
         self.0 += 1;
     }
@@ -14,22 +18,17 @@ impl Tile {
     }
 }
 
-impl From<Tile> for Color {
-    fn from(Tile(rank): Tile) -> Self {
-        // rank = 1 -> r = 0.0, rank >= 11 -> r = 1.0
-        let r: f32 = (rank - 1) as f32 / 10.0;
-        return TILE_COLOR_0 * (1.0 - r) + TILE_COLOR_1 * r;
-    }
-}
-
 impl From<Tile> for usize {
     fn from(Tile(rank): Tile) -> Self {
         return 2_usize.pow(rank as u32);
     }
 }
 
-impl ToString for Tile {
-    fn to_string(&self) -> String {
-        return 2_usize.pow(self.0 as u32).to_string();
+impl std::fmt::Display for Tile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_blocker() {
+            return write!(f, "X");
+        }
+        return write!(f, "{}", 2_usize.pow(self.0 as u32));
     }
 }
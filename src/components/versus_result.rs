@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+// 対戦の結果の画面
+#[derive(Component)]
+pub struct VersusResult;
@@ -10,3 +10,5 @@ pub const BOARD_COLOR_1: Color = Color::GRAY;
 pub const TILE_COLOR_0: Color = Color::rgb(0.0, 0.922, 0.5);
 pub const TILE_COLOR_1: Color = Color::rgb(0.0, 0.5, 0.922);
 pub const TILE_TEXT_COLOR: Color = Color::GRAY;
+pub const BLOCKER_COLOR: Color = Color::DARK_GRAY;
+pub const BLUNDER_COLOR: Color = Color::rgb(0.922, 0.2, 0.2);
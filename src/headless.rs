@@ -0,0 +1,9 @@
+pub mod battle;
+pub mod bot;
+pub mod daily;
+pub mod env;
+pub mod game;
+pub mod simulate;
+pub mod tournament;
+pub mod train;
+pub mod vec_env;
@@ -0,0 +1,81 @@
+use super::game::Game;
+use crate::ai::Agent;
+use crate::plugins::versus::{decide_outcome, VersusOutcome};
+use crate::resources::game_settings::{GameSettings, WinCondition};
+
+// 描画なしの対戦. 各ラウンドで、GameOver でない盤面が index 順に 1 手ずつ動かす
+pub struct Battle {
+    settings: GameSettings,
+    games: Vec<Game>,
+    rounds: usize,
+}
+
+impl Battle {
+    // seeds[i] が i 番目の盤面の seed
+    pub fn new(settings: &GameSettings, seeds: &[u64]) -> Self {
+        return Self {
+            settings: settings.clone(),
+            games: seeds
+                .iter()
+                .map(|seed| Game::new(settings, *seed))
+                .collect(),
+            rounds: 0,
+        };
+    }
+    pub fn games(&self) -> &[Game] {
+        return &self.games;
+    }
+    pub fn rounds(&self) -> usize {
+        return self.rounds;
+    }
+    pub fn is_finished(&self) -> bool {
+        return self.games.iter().all(|game| game.is_game_over());
+    }
+    // 1 ラウンド進める. agents[i] が i 番目の盤面を動かす
+    pub fn play_round(&mut self, agents: &mut [Box<dyn Agent>]) {
+        let versus = self.settings.versus.unwrap_or_default();
+        for (i, agent) in agents.iter_mut().enumerate().take(self.games.len()) {
+            if self.games[i].is_game_over() {
+                continue;
+            }
+            let direction = agent.choose(self.games[i].board());
+            let movements = self.games[i].step(direction);
+            // plugins::versus::send_garbage と同じ規則
+            if let Some(rules) = versus.garbage {
+                let count: usize = movements.iter().map(|m| rules.attack(m)).sum();
+                for (j, game) in self.games.iter_mut().enumerate() {
+                    if j != i {
+                        game.receive_garbage(count, rules.delay);
+                    }
+                }
+            }
+        }
+        // plugins::versus::check_target_tile と同じ規則
+        if let WinCondition::TargetTile(target) = versus.win_condition {
+            let reached = self
+                .games
+                .iter()
+                .any(|game| game.board().max_tile().is_some_and(|tile| tile >= target));
+            if reached {
+                self.games.iter_mut().for_each(|game| game.end());
+            }
+        }
+        self.rounds += 1;
+    }
+    // 決着がつくか max_rounds に達するまで進めて、勝敗を返す
+    pub fn play(&mut self, agents: &mut [Box<dyn Agent>], max_rounds: usize) -> VersusOutcome {
+        while !self.is_finished() && self.rounds < max_rounds {
+            self.play_round(agents);
+        }
+        return self.outcome();
+    }
+    pub fn outcome(&self) -> VersusOutcome {
+        let boards: Vec<_> = self
+            .games
+            .iter()
+            .map(|game| (game.board(), game.score()))
+            .collect();
+        let win_condition = self.settings.versus.unwrap_or_default().win_condition;
+        return decide_outcome(&boards, win_condition);
+    }
+}
@@ -0,0 +1,322 @@
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+use serde_json::json;
+
+use super::game::Game;
+use crate::components::board_state::BoardState;
+use crate::resources::game_settings::GameSettings;
+use crate::structs::direction::Direction;
+
+pub const BOT_PROTOCOL_VERSION: u32 = 1;
+
+// 標準入出力でつないだ bot に遊ばせる
+// ゲーム側が 1 行ずつ書き、bot は STATE を受け取るたびに U, D, L, R のどれか 1 文字を 1 行で返す
+//   HELLO version=1 width=4 height=4 tiles_per_turn=1 spawn=2:1,4:1 timeout_ms=1000
+//   (bot) READY
+//   STATE turn=0 score=0 legal=UDLR board=0,0,0,0/0,2,0,0/0,0,0,0/0,0,0,0
+//   (bot) U
+//   ...
+//   RESULT reason=game_over score=1234 turns=150 max_tile=128
+// board は上の行から. 0 は空きマス
+// Json を選ぶと同じ内容を {"type": "hello", ...} のような JSON で書く
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BotFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BotOptions {
+    pub format: BotFormat,
+    // READY と各手を待つ時間
+    pub timeout: Duration,
+    pub seed: u64,
+    // None なら GameOver まで
+    pub max_turns: Option<usize>,
+}
+
+impl Default for BotOptions {
+    fn default() -> Self {
+        return Self {
+            format: BotFormat::default(),
+            timeout: Duration::from_secs(1),
+            seed: 0,
+            max_turns: None,
+        };
+    }
+}
+
+// 対局が終わった理由
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EndReason {
+    GameOver,
+    MaxTurns,
+    Timeout,
+    // U, D, L, R 以外を返した
+    InvalidMove,
+    // bot が入力を閉じた
+    Disconnected,
+}
+
+impl EndReason {
+    fn name(self) -> &'static str {
+        match self {
+            EndReason::GameOver => return "game_over",
+            EndReason::MaxTurns => return "max_turns",
+            EndReason::Timeout => return "timeout",
+            EndReason::InvalidMove => return "invalid_move",
+            EndReason::Disconnected => return "disconnected",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BotResult {
+    pub reason: EndReason,
+    pub score: usize,
+    pub turns: usize,
+    pub max_tile: usize,
+}
+
+// input は別スレッドで読むので、timeout を過ぎても bot を待ち続けることはない
+pub fn run_bot(
+    settings: &GameSettings,
+    options: &BotOptions,
+    input: impl BufRead + Send + 'static,
+    mut output: impl Write,
+) -> io::Result<BotResult> {
+    let lines = spawn_reader(input);
+    let mut game = Game::new(settings, options.seed);
+    writeln!(output, "{}", hello_line(settings, options))?;
+    output.flush()?;
+    let reason = match read_line(&lines, options.timeout) {
+        Err(reason) => reason,
+        Ok(line) if line.trim().eq_ignore_ascii_case("ready") => {
+            play(&mut game, options, &lines, &mut output)?
+        }
+        Ok(_) => EndReason::InvalidMove,
+    };
+    let result = BotResult {
+        reason,
+        score: game.score().value(),
+        turns: game.moves(),
+        max_tile: game.board().max_tile().map_or(0, |tile| tile.num()),
+    };
+    writeln!(output, "{}", result_line(&result, options.format))?;
+    output.flush()?;
+    return Ok(result);
+}
+
+fn play(
+    game: &mut Game,
+    options: &BotOptions,
+    lines: &Receiver<String>,
+    output: &mut impl Write,
+) -> io::Result<EndReason> {
+    loop {
+        if game.is_game_over() {
+            return Ok(EndReason::GameOver);
+        }
+        if options.max_turns.is_some_and(|max| game.moves() >= max) {
+            return Ok(EndReason::MaxTurns);
+        }
+        writeln!(output, "{}", state_line(game, options.format))?;
+        output.flush()?;
+        let line = match read_line(lines, options.timeout) {
+            Ok(line) => line,
+            Err(reason) => return Ok(reason),
+        };
+        match parse_move(&line) {
+            Some(direction) => {
+                game.step(direction);
+            }
+            None => return Ok(EndReason::InvalidMove),
+        }
+    }
+}
+
+fn spawn_reader(input: impl BufRead + Send + 'static) -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in input.lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    return receiver;
+}
+
+fn read_line(lines: &Receiver<String>, timeout: Duration) -> Result<String, EndReason> {
+    match lines.recv_timeout(timeout) {
+        Ok(line) => return Ok(line),
+        Err(RecvTimeoutError::Timeout) => return Err(EndReason::Timeout),
+        Err(RecvTimeoutError::Disconnected) => return Err(EndReason::Disconnected),
+    }
+}
+
+// "U" の他に、JSON の "U" や {"move": "U"} も受け付ける
+pub fn parse_move(line: &str) -> Option<Direction> {
+    let line = line.trim();
+    let letter = match serde_json::from_str::<serde_json::Value>(line) {
+        Ok(serde_json::Value::String(s)) => s,
+        Ok(value) => value.get("move")?.as_str()?.to_string(),
+        Err(_) => line.to_string(),
+    };
+    let mut chars = letter.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => return Direction::from_letter(c),
+        _ => return None,
+    }
+}
+
+fn legal_letters(board: &BoardState) -> String {
+    return board
+        .legal_directions()
+        .map(|direction| direction.letter())
+        .collect();
+}
+
+fn hello_line(settings: &GameSettings, options: &BotOptions) -> String {
+    let size = settings.board_size;
+    let spawn = &settings.spawn;
+    let timeout_ms = options.timeout.as_millis();
+    match options.format {
+        BotFormat::Text => {
+            let weights: Vec<String> = spawn
+                .weights
+                .iter()
+                .map(|(tile, weight)| format!("{}:{}", tile, weight))
+                .collect();
+            return format!(
+                "HELLO version={} width={} height={} tiles_per_turn={} spawn={} timeout_ms={}",
+                BOT_PROTOCOL_VERSION,
+                size.width,
+                size.height,
+                spawn.tiles_per_turn,
+                weights.join(","),
+                timeout_ms
+            );
+        }
+        BotFormat::Json => {
+            let weights: Vec<(usize, u32)> = spawn
+                .weights
+                .iter()
+                .map(|(tile, weight)| (tile.num(), *weight))
+                .collect();
+            return json!({
+                "type": "hello",
+                "version": BOT_PROTOCOL_VERSION,
+                "width": size.width,
+                "height": size.height,
+                "tiles_per_turn": spawn.tiles_per_turn,
+                "spawn": weights,
+                "timeout_ms": timeout_ms,
+            })
+            .to_string();
+        }
+    }
+}
+
+fn state_line(game: &Game, format: BotFormat) -> String {
+    let rows = game.board().rows_top_down();
+    let legal = legal_letters(game.board());
+    match format {
+        BotFormat::Text => {
+            let rows: Vec<String> = rows
+                .iter()
+                .map(|row| {
+                    let cells: Vec<String> = row.iter().map(|n| n.to_string()).collect();
+                    return cells.join(",");
+                })
+                .collect();
+            return format!(
+                "STATE turn={} score={} legal={} board={}",
+                game.moves(),
+                game.score(),
+                legal,
+                rows.join("/")
+            );
+        }
+        BotFormat::Json => {
+            return json!({
+                "type": "state",
+                "turn": game.moves(),
+                "score": game.score().value(),
+                "legal": legal,
+                "board": rows,
+            })
+            .to_string();
+        }
+    }
+}
+
+fn result_line(result: &BotResult, format: BotFormat) -> String {
+    match format {
+        BotFormat::Text => {
+            return format!(
+                "RESULT reason={} score={} turns={} max_tile={}",
+                result.reason.name(),
+                result.score,
+                result.turns,
+                result.max_tile
+            );
+        }
+        BotFormat::Json => {
+            return json!({
+                "type": "result",
+                "reason": result.reason.name(),
+                "score": result.score,
+                "turns": result.turns,
+                "max_tile": result.max_tile,
+            })
+            .to_string();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripted_bot() {
+        let input = io::Cursor::new("READY\nL\n{\"move\": \"d\"}\nX\n");
+        let mut output = Vec::new();
+        let options = BotOptions::default();
+        let result = run_bot(&GameSettings::default(), &options, input, &mut output).unwrap();
+        assert_eq!(result.reason, EndReason::InvalidMove);
+        assert_eq!(result.turns, 2);
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0].starts_with("HELLO version=1 width=4 height=4"));
+        assert_eq!(lines.iter().filter(|l| l.starts_with("STATE")).count(), 3);
+        assert!(lines
+            .last()
+            .unwrap()
+            .starts_with("RESULT reason=invalid_move"));
+    }
+
+    #[test]
+    fn timeout_without_ready() {
+        let (input, _keep_open) = io::pipe().unwrap();
+        let options = BotOptions {
+            format: BotFormat::Json,
+            timeout: Duration::from_millis(50),
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        let input = io::BufReader::new(input);
+        let result = run_bot(&GameSettings::default(), &options, input, &mut output).unwrap();
+        assert_eq!(result.reason, EndReason::Timeout);
+        let last = String::from_utf8(output).unwrap();
+        let last: serde_json::Value = serde_json::from_str(last.lines().last().unwrap()).unwrap();
+        assert_eq!(last["reason"], "timeout");
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt;
+
+use super::game::Game;
+use crate::resources::{
+    daily::{daily_seed, DailyResult},
+    game_settings::GameSettings,
+};
+
+// 共有された記録を、その日の seed で進め直したときに合わなかったところ
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DailyMismatch {
+    // この手の前に動かせなくなった
+    GameOver { turn: usize },
+    Score { replayed: usize },
+    MaxTile { replayed: usize },
+}
+
+impl fmt::Display for DailyMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DailyMismatch::GameOver { turn } => {
+                return write!(f, "the game was over before move {}", turn + 1);
+            }
+            DailyMismatch::Score { replayed } => {
+                return write!(f, "replayed score is {}", replayed);
+            }
+            DailyMismatch::MaxTile { replayed } => {
+                return write!(f, "replayed max tile is {}", replayed);
+            }
+        }
+    }
+}
+
+// result の手を、その日の seed の盤面で進め直して得点と最大の Tile を確かめる
+// Daily はいつも GameSettings::daily_rules で遊ぶので、共有する文には設定を含めない
+pub fn verify_daily(result: &DailyResult) -> Result<(), DailyMismatch> {
+    let mut game = Game::new(
+        &GameSettings::default().daily_rules(),
+        daily_seed(result.date),
+    );
+    for (turn, direction) in result
+        .directions()
+        .unwrap_or_default()
+        .into_iter()
+        .enumerate()
+    {
+        if game.is_game_over() {
+            return Err(DailyMismatch::GameOver { turn });
+        }
+        game.step(direction);
+    }
+    let score = game.score().value();
+    if score != result.score {
+        return Err(DailyMismatch::Score { replayed: score });
+    }
+    let max_tile = game.board().max_tile().map_or(0, |tile| tile.num());
+    if max_tile != result.max_tile {
+        return Err(DailyMismatch::MaxTile { replayed: max_tile });
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::direction::Direction;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn accept_only_the_replayed_result() {
+        let settings = GameSettings::default();
+        let date = NaiveDate::from_ymd_opt(2024, 5, 17).unwrap();
+        let mut game = Game::new(&settings, daily_seed(date));
+        let mut directions = String::new();
+        for direction in Direction::ALL.into_iter().cycle().take(40) {
+            if game.is_game_over() {
+                break;
+            }
+            game.step(direction);
+            directions.push(direction.letter());
+        }
+        let mut result = DailyResult {
+            date,
+            score: game.score().value(),
+            max_tile: game.board().max_tile().map_or(0, |tile| tile.num()),
+            directions,
+        };
+        assert_eq!(verify_daily(&result), Ok(()));
+        result.score += 4;
+        assert!(matches!(
+            verify_daily(&result),
+            Err(DailyMismatch::Score { .. })
+        ));
+    }
+}
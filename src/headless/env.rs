@@ -0,0 +1,286 @@
+use super::game::Game;
+use crate::components::{board_state::BoardState, position::Position};
+use crate::resources::game_settings::GameSettings;
+use crate::structs::direction::Direction;
+
+// 強化学習用の環境. 行動 a は Direction::ALL[a] (Left, Right, Up, Down)
+pub const ACTIONS: [Direction; 4] = Direction::ALL;
+
+// 観測の表し方. どちらも上の行から、各行は左から並べる
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ObservationEncoding {
+    // 各マスの rank (空きマスは 0). shape = [height, width]
+    Ranks,
+    // rank ごとの 0/1 の面. rank >= planes - 1 は最後の面. shape = [planes, height, width]
+    OneHot { planes: usize },
+}
+
+impl Default for ObservationEncoding {
+    fn default() -> Self {
+        return ObservationEncoding::OneHot { planes: 16 };
+    }
+}
+
+impl ObservationEncoding {
+    pub fn shape(self, width: usize, height: usize) -> Vec<usize> {
+        match self {
+            ObservationEncoding::Ranks => return vec![height, width],
+            ObservationEncoding::OneHot { planes } => return vec![planes, height, width],
+        }
+    }
+    pub fn len(self, width: usize, height: usize) -> usize {
+        return self.shape(width, height).iter().product();
+    }
+    // out の長さは len と同じ
+    pub fn encode(self, board: &BoardState, out: &mut [f32]) {
+        let grid = board.grid();
+        let (width, height) = (grid.width(), grid.height());
+        out.fill(0.0);
+        for row in 0..height {
+            for x in 0..width {
+                let y = height - 1 - row;
+                let rank = board
+                    .get(Position::new(x, y))
+                    .map_or(0, |tile| tile.0 as usize);
+                let cell = row * width + x;
+                match self {
+                    ObservationEncoding::Ranks => out[cell] = rank as f32,
+                    ObservationEncoding::OneHot { planes } => {
+                        out[rank.min(planes - 1) * width * height + cell] = 1.0
+                    }
+                }
+            }
+        }
+    }
+}
+
+// 報酬の重み. 1 手ごとに各項の和を返す
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RewardShaping {
+    // その手で得た Score
+    pub merge_score: f32,
+    // 動かした後の空きマスの数
+    pub empty_cells: f32,
+    // 最大の Tile の log2 が増えた分
+    pub log_max_tile: f32,
+    // 動かせない方向を選んだとき
+    pub invalid_action: f32,
+}
+
+impl Default for RewardShaping {
+    fn default() -> Self {
+        return Self {
+            merge_score: 1.0,
+            empty_cells: 0.0,
+            log_max_tile: 0.0,
+            invalid_action: 0.0,
+        };
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EnvConfig {
+    pub settings: GameSettings,
+    pub encoding: ObservationEncoding,
+    pub reward: RewardShaping,
+    // これだけ step したら打ち切る. 動かせなかった方向も数える
+    pub max_steps: Option<usize>,
+}
+
+impl Default for EnvConfig {
+    fn default() -> Self {
+        return Self {
+            settings: GameSettings::default(),
+            encoding: ObservationEncoding::default(),
+            reward: RewardShaping::default(),
+            max_steps: None,
+        };
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Observation {
+    pub data: Vec<f32>,
+    pub shape: Vec<usize>,
+    // ACTIONS と同じ順で、動かせる方向なら true
+    pub action_mask: [bool; 4],
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StepInfo {
+    pub score: usize,
+    // その手で得た Score
+    pub gained: usize,
+    pub max_tile: usize,
+    // 動かせなかった方向も含めて step した回数
+    pub steps: usize,
+    // 動かせない方向を選んだ. 盤面は変わらない
+    pub invalid_action: bool,
+    // max_steps で打ち切った
+    pub truncated: bool,
+}
+
+// reset(seed) と step(action) で 1 局ずつ進める
+// 動かせない方向を選んだときは何もせず、Tile も出現させない.
+// handle_player_input と Game::step は動かなくても Tile を出現させるので、ここだけ規則が違う
+pub struct Env {
+    config: EnvConfig,
+    game: Game,
+    // reset から step した回数
+    steps: usize,
+}
+
+impl Env {
+    pub fn new(config: EnvConfig) -> Self {
+        let game = Game::new(&config.settings, 0);
+        return Self {
+            config,
+            game,
+            steps: 0,
+        };
+    }
+    pub fn config(&self) -> &EnvConfig {
+        return &self.config;
+    }
+    pub fn game(&self) -> &Game {
+        return &self.game;
+    }
+    pub fn observation_shape(&self) -> Vec<usize> {
+        let size = self.config.settings.board_size;
+        return self.config.encoding.shape(size.width, size.height);
+    }
+    pub fn observation_len(&self) -> usize {
+        let size = self.config.settings.board_size;
+        return self.config.encoding.len(size.width, size.height);
+    }
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.game = Game::new(&self.config.settings, seed);
+        self.steps = 0;
+        return self.observation();
+    }
+    pub fn observation(&self) -> Observation {
+        let mut data = vec![0.0; self.observation_len()];
+        self.encode(&mut data);
+        return Observation {
+            data,
+            shape: self.observation_shape(),
+            action_mask: self.action_mask(),
+        };
+    }
+    pub fn encode(&self, out: &mut [f32]) {
+        self.config.encoding.encode(self.game.board(), out);
+    }
+    pub fn action_mask(&self) -> [bool; 4] {
+        if self.game.is_game_over() {
+            return [false; 4];
+        }
+        return ACTIONS.map(|direction| self.game.board().moved(direction).is_some());
+    }
+    // 動かせる方向がなくなったか、max_steps に達した
+    pub fn is_done(&self) -> bool {
+        return self.is_terminated() || self.is_truncated();
+    }
+    fn is_terminated(&self) -> bool {
+        return self.game.is_game_over() || !self.action_mask().contains(&true);
+    }
+    fn is_truncated(&self) -> bool {
+        return self.config.max_steps.is_some_and(|max| self.steps >= max);
+    }
+    // (観測, 報酬, 終わったか, 情報)
+    // 動かせない方向なら盤面も手数も変わらず、Tile も出現しない. 報酬は invalid_action だけ
+    pub fn step(&mut self, action: usize) -> (Observation, f32, bool, StepInfo) {
+        let (reward, done, info) = self.step_without_observation(action);
+        return (self.observation(), reward, done, info);
+    }
+    // 観測を作らずに進める. VecEnv が自分のバッファに書き込むため
+    pub fn step_without_observation(&mut self, action: usize) -> (f32, bool, StepInfo) {
+        let shaping = self.config.reward;
+        let direction = ACTIONS[action % ACTIONS.len()];
+        let before_score = self.game.score().value();
+        let before_max = max_rank(self.game.board());
+        let valid = !self.is_done() && self.game.board().moved(direction).is_some();
+        self.steps += 1;
+        if valid {
+            self.game.step(direction);
+        }
+        let gained = self.game.score().value() - before_score;
+        let board = self.game.board();
+        let reward = match valid {
+            true => {
+                shaping.merge_score * gained as f32
+                    + shaping.empty_cells * board.empty_count() as f32
+                    + shaping.log_max_tile * (max_rank(board) - before_max) as f32
+            }
+            false => shaping.invalid_action,
+        };
+        let info = StepInfo {
+            score: self.game.score().value(),
+            gained,
+            max_tile: board.max_tile().map_or(0, |tile| tile.num()),
+            steps: self.steps,
+            invalid_action: !valid,
+            truncated: self.is_truncated() && !self.is_terminated(),
+        };
+        return (reward, self.is_done(), info);
+    }
+}
+
+fn max_rank(board: &BoardState) -> i32 {
+    return board.max_tile().map_or(0, |tile| tile.0 as i32);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::tile::Tile;
+
+    #[test]
+    fn one_hot_and_invalid_action() {
+        let mut env = Env::new(EnvConfig {
+            reward: RewardShaping {
+                invalid_action: -1.0,
+                ..RewardShaping::default()
+            },
+            ..EnvConfig::default()
+        });
+        // 左下の角に 1 つだけなので、Left と Down には動かせない
+        let mut board = BoardState::new(4, 4);
+        board.set(Position::new(0, 0), Some(Tile(1)));
+        env.game = Game::from_board(&env.config.settings, board, 1);
+        let observation = env.observation();
+        assert_eq!(observation.shape, vec![16, 4, 4]);
+        assert_eq!(observation.data.iter().sum::<f32>(), 16.0);
+        assert_eq!(observation.data[16 + 3 * 4], 1.0);
+        assert_eq!(observation.action_mask, [false, true, true, false]);
+        let (next, reward, done, info) = env.step(0);
+        assert_eq!(next, observation);
+        assert_eq!(reward, -1.0);
+        assert!(!done);
+        assert!(info.invalid_action);
+        assert_eq!(info.steps, 1);
+    }
+
+    #[test]
+    fn repeated_invalid_actions_are_truncated() {
+        let mut env = Env::new(EnvConfig {
+            reward: RewardShaping {
+                empty_cells: 1.0,
+                ..RewardShaping::default()
+            },
+            max_steps: Some(3),
+            ..EnvConfig::default()
+        });
+        let mut board = BoardState::new(4, 4);
+        board.set(Position::new(0, 0), Some(Tile(1)));
+        env.game = Game::from_board(&env.config.settings, board, 1);
+        // 動かせない Left を選び続けても空きマスの報酬は入らず、max_steps で終わる
+        for step in 1..=3 {
+            let (_, reward, done, info) = env.step(0);
+            assert_eq!(reward, 0.0);
+            assert_eq!(info.steps, step);
+            assert_eq!(done, step == 3);
+            assert_eq!(info.truncated, step == 3);
+        }
+        assert_eq!(env.game().moves(), 0);
+    }
+}
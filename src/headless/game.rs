@@ -0,0 +1,107 @@
+use bevy_prng::WyRand;
+use rand_core::SeedableRng;
+
+use crate::components::{
+    board_state::BoardState, board_status::BoardStatus, garbage_queue::GarbageQueue, score::Score,
+};
+use crate::plugins::{
+    calculate::{calc_tile_movements, TileMovement},
+    spawn::choose_spawns,
+};
+use crate::resources::game_settings::GameSettings;
+use crate::structs::direction::Direction;
+
+// 描画なしで 1 つの盤面を進める
+// 同じ seed なら、Bevy 側で BoardSettings::seed を与えた盤面と同じように進む
+pub struct Game {
+    settings: GameSettings,
+    board: BoardState,
+    score: Score,
+    status: BoardStatus,
+    garbage: GarbageQueue,
+    rng: WyRand,
+    moves: usize,
+}
+
+impl Game {
+    pub fn new(settings: &GameSettings, seed: u64) -> Self {
+        if let Err(e) = settings.spawn.validate() {
+            panic!("invalid GameSettings::spawn: {}", e);
+        }
+        let size = settings.board_size;
+        let mut game = Self {
+            settings: settings.clone(),
+            board: BoardState::new(size.width, size.height),
+            score: Score::default(),
+            status: BoardStatus::Spawn,
+            garbage: GarbageQueue::default(),
+            rng: WyRand::seed_from_u64(seed),
+            moves: 0,
+        };
+        game.spawn();
+        return game;
+    }
+    // board から始める. Tile は出現させない
+    pub fn from_board(settings: &GameSettings, board: BoardState, seed: u64) -> Self {
+        return Self {
+            settings: settings.clone(),
+            board,
+            score: Score::default(),
+            status: BoardStatus::Input,
+            garbage: GarbageQueue::default(),
+            rng: WyRand::seed_from_u64(seed),
+            moves: 0,
+        };
+    }
+    pub fn board(&self) -> &BoardState {
+        return &self.board;
+    }
+    pub fn score(&self) -> Score {
+        return self.score;
+    }
+    pub fn status(&self) -> BoardStatus {
+        return self.status;
+    }
+    pub fn is_game_over(&self) -> bool {
+        return self.status == BoardStatus::GameOver;
+    }
+    pub fn moves(&self) -> usize {
+        return self.moves;
+    }
+    pub fn garbage(&self) -> &GarbageQueue {
+        return &self.garbage;
+    }
+    // direction に動かしてから Tile を出現させ、その移動を返す
+    pub fn step(&mut self, direction: Direction) -> Vec<TileMovement> {
+        if self.status != BoardStatus::Input {
+            return Vec::new();
+        }
+        let movements = calc_tile_movements(&self.board, direction.falling_turn());
+        self.score.add(self.board.apply(&movements));
+        self.moves += 1;
+        self.status = BoardStatus::Spawn;
+        self.spawn();
+        return movements;
+    }
+    // 他の盤面から送られてきた邪魔な Tile
+    pub fn receive_garbage(&mut self, count: usize, delay: usize) {
+        self.garbage.push(count, delay);
+    }
+    // 対戦の決着などで、これ以上動かせないようにする
+    pub fn end(&mut self) {
+        self.status = BoardStatus::GameOver;
+    }
+    // plugins::spawn::create_random_tile と同じ規則
+    fn spawn(&mut self) {
+        if self.board.is_full() {
+            self.status = BoardStatus::GameOver;
+            return;
+        }
+        let garbage = self.garbage.tick();
+        let spawns = choose_spawns(&self.board, &self.settings, garbage, &mut self.rng);
+        for (position, tile) in spawns {
+            self.board.set(position, Some(tile));
+        }
+        self.status = BoardStatus::Input;
+    }
+}
@@ -0,0 +1,66 @@
+use std::thread;
+
+use super::game::Game;
+use crate::ai::AgentKind;
+use crate::resources::game_settings::GameSettings;
+
+// 1 人用の盤面を Agent で終わるまで進めた結果
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameRecord {
+    pub seed: u64,
+    pub score: usize,
+    pub moves: usize,
+    pub max_tile: usize,
+}
+
+// 盤面と Agent の両方を seed で始めるので、同じ seed なら同じ結果になる
+pub fn simulate(
+    settings: &GameSettings,
+    kind: AgentKind,
+    seed: u64,
+    max_moves: Option<usize>,
+) -> GameRecord {
+    let mut game = Game::new(settings, seed);
+    let mut agent = kind.build(settings, seed);
+    while !game.is_game_over() && max_moves.is_none_or(|max| game.moves() < max) {
+        if game.board().legal_directions().next().is_none() {
+            break;
+        }
+        let direction = agent.choose(game.board());
+        game.step(direction);
+    }
+    return GameRecord {
+        seed,
+        score: game.score().value(),
+        moves: game.moves(),
+        max_tile: game.board().max_tile().map_or(0, |tile| tile.num()),
+    };
+}
+
+// seeds を使えるだけのスレッドに分けて進める. 結果は seeds と同じ順
+pub fn simulate_seeds(
+    settings: &GameSettings,
+    kind: AgentKind,
+    seeds: &[u64],
+    max_moves: Option<usize>,
+) -> Vec<GameRecord> {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let per_thread = seeds.len().div_ceil(threads).max(1);
+    return thread::scope(|scope| {
+        let handles: Vec<_> = seeds
+            .chunks(per_thread)
+            .map(|seeds| {
+                return scope.spawn(move || {
+                    return seeds
+                        .iter()
+                        .map(|&seed| simulate(settings, kind, seed, max_moves))
+                        .collect::<Vec<_>>();
+                });
+            })
+            .collect();
+        return handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect();
+    });
+}
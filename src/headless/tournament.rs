@@ -0,0 +1,297 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{self, BufReader};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use super::bot::{run_bot, BotOptions};
+use super::simulate::{simulate_seeds, GameRecord};
+use crate::ai::AgentKind;
+use crate::resources::game_settings::GameSettings;
+
+// 大会に出るもの. External は headless::bot の手順で話す外部のコマンド
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Entrant {
+    Agent(AgentKind),
+    External { name: String, command: String },
+}
+
+impl Entrant {
+    pub fn name(&self) -> String {
+        match self {
+            Entrant::Agent(kind) => return kind.to_string(),
+            Entrant::External { name, .. } => return name.clone(),
+        }
+    }
+    // 全員に同じ seeds を順に遊ばせる
+    fn play(&self, settings: &GameSettings, options: &TournamentOptions) -> Vec<GameRecord> {
+        match self {
+            Entrant::Agent(kind) => {
+                return simulate_seeds(settings, *kind, &options.seeds, options.max_moves)
+            }
+            Entrant::External { name, command } => {
+                return options
+                    .seeds
+                    .iter()
+                    .map(|&seed| {
+                        return play_external(command, settings, options, seed).unwrap_or_else(
+                            |e| {
+                                eprintln!("{} failed on seed {}: {}", name, seed, e);
+                                return GameRecord {
+                                    seed,
+                                    score: 0,
+                                    moves: 0,
+                                    max_tile: 0,
+                                };
+                            },
+                        );
+                    })
+                    .collect();
+            }
+        }
+    }
+}
+
+// 1 局ごとにコマンドを起動し直す
+fn play_external(
+    command: &str,
+    settings: &GameSettings,
+    options: &TournamentOptions,
+    seed: u64,
+) -> io::Result<GameRecord> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+        return Err(io::Error::other("failed to open pipes"));
+    };
+    let bot_options = BotOptions {
+        timeout: options.timeout,
+        seed,
+        max_turns: options.max_moves,
+        ..BotOptions::default()
+    };
+    let result = run_bot(settings, &bot_options, BufReader::new(stdout), stdin);
+    let _ = child.kill();
+    let _ = child.wait();
+    let result = result?;
+    return Ok(GameRecord {
+        seed,
+        score: result.score,
+        moves: result.turns,
+        max_tile: result.max_tile,
+    });
+}
+
+#[derive(Clone, Debug)]
+pub struct TournamentOptions {
+    pub seeds: Vec<u64>,
+    pub max_moves: Option<usize>,
+    // External の 1 手を待つ時間
+    pub timeout: Duration,
+}
+
+impl Default for TournamentOptions {
+    fn default() -> Self {
+        return Self {
+            seeds: (0..100).collect(),
+            max_moves: None,
+            timeout: Duration::from_secs(1),
+        };
+    }
+}
+
+// records[i][j] が entrants[i] の seeds[j] での結果
+pub struct Tournament {
+    pub entrants: Vec<Entrant>,
+    pub seeds: Vec<u64>,
+    pub records: Vec<Vec<GameRecord>>,
+}
+
+// 1 人分の成績. 区間はどれも 95% 信頼区間
+#[derive(Clone, Debug, PartialEq)]
+pub struct Standing {
+    pub name: String,
+    pub games: usize,
+    pub mean_score: f64,
+    // 平均の信頼区間の半分の幅 (正規近似)
+    pub mean_margin: f64,
+    pub median_score: f64,
+    pub max_score: usize,
+    // 最大の Tile -> 局数
+    pub max_tiles: BTreeMap<usize, usize>,
+    // 同じ seed で最も高い Score をとった割合. 同点なら人数で割る
+    pub win_rate: f64,
+    // Wilson の区間
+    pub win_interval: (f64, f64),
+}
+
+pub fn run_tournament(
+    settings: &GameSettings,
+    entrants: Vec<Entrant>,
+    options: &TournamentOptions,
+) -> Tournament {
+    let records = entrants
+        .iter()
+        .map(|entrant| entrant.play(settings, options))
+        .collect();
+    return Tournament {
+        entrants,
+        seeds: options.seeds.clone(),
+        records,
+    };
+}
+
+impl Tournament {
+    // mean_score の大きい順
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut wins = vec![0.0; self.entrants.len()];
+        for j in 0..self.seeds.len() {
+            let best = self.records.iter().map(|records| records[j].score).max();
+            let winners: Vec<usize> = (0..self.entrants.len())
+                .filter(|&i| Some(self.records[i][j].score) == best)
+                .collect();
+            for &i in winners.iter() {
+                wins[i] += 1.0 / winners.len() as f64;
+            }
+        }
+        let mut standings: Vec<Standing> = self
+            .entrants
+            .iter()
+            .zip(&self.records)
+            .zip(wins)
+            .map(|((entrant, records), wins)| standing(entrant.name(), records, wins))
+            .collect();
+        standings.sort_by(|a, b| b.mean_score.total_cmp(&a.mean_score));
+        return standings;
+    }
+    // 順位表
+    pub fn report(&self) -> String {
+        let mut text = String::new();
+        let _ = writeln!(
+            text,
+            "{} entrants, {} seeds (95% confidence intervals)",
+            self.entrants.len(),
+            self.seeds.len()
+        );
+        for (rank, standing) in self.standings().iter().enumerate() {
+            let tiles: Vec<String> = standing
+                .max_tiles
+                .iter()
+                .rev()
+                .map(|(tile, count)| format!("{}:{}", tile, count))
+                .collect();
+            let _ = writeln!(
+                text,
+                "{}. {}\n   mean {:.1} ± {:.1}  median {:.1}  max {}\n   win rate {:.1}% [{:.1}%, {:.1}%]\n   max tile {}",
+                rank + 1,
+                standing.name,
+                standing.mean_score,
+                standing.mean_margin,
+                standing.median_score,
+                standing.max_score,
+                standing.win_rate * 100.0,
+                standing.win_interval.0 * 100.0,
+                standing.win_interval.1 * 100.0,
+                tiles.join(" ")
+            );
+        }
+        return text;
+    }
+    // seed ごとの結果
+    pub fn csv(&self) -> String {
+        let mut text = String::from("entrant,seed,score,moves,max_tile\n");
+        for (entrant, records) in self.entrants.iter().zip(&self.records) {
+            for record in records.iter() {
+                let _ = writeln!(
+                    text,
+                    "{},{},{},{},{}",
+                    entrant.name(),
+                    record.seed,
+                    record.score,
+                    record.moves,
+                    record.max_tile
+                );
+            }
+        }
+        return text;
+    }
+}
+
+// 正規分布の 97.5% 点
+const Z: f64 = 1.96;
+
+fn standing(name: String, records: &[GameRecord], wins: f64) -> Standing {
+    let n = records.len().max(1) as f64;
+    let mut scores: Vec<usize> = records.iter().map(|record| record.score).collect();
+    scores.sort();
+    let mean = scores.iter().sum::<usize>() as f64 / n;
+    let variance = scores
+        .iter()
+        .map(|&score| (score as f64 - mean).powi(2))
+        .sum::<f64>()
+        / (n - 1.0).max(1.0);
+    let median = match scores.len() {
+        0 => 0.0,
+        len if len % 2 == 1 => scores[len / 2] as f64,
+        len => (scores[len / 2 - 1] + scores[len / 2]) as f64 / 2.0,
+    };
+    let mut max_tiles = BTreeMap::new();
+    for record in records.iter() {
+        *max_tiles.entry(record.max_tile).or_insert(0) += 1;
+    }
+    let win_rate = wins / n;
+    return Standing {
+        name,
+        games: records.len(),
+        mean_score: mean,
+        mean_margin: Z * (variance / n).sqrt(),
+        median_score: median,
+        max_score: scores.last().copied().unwrap_or(0),
+        max_tiles,
+        win_rate,
+        win_interval: wilson(win_rate, n),
+    };
+}
+
+fn wilson(p: f64, n: f64) -> (f64, f64) {
+    let z2 = Z * Z;
+    let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+    let margin = Z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / (1.0 + z2 / n);
+    return ((center - margin).max(0.0), (center + margin).min(1.0));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_on_shared_seeds() {
+        let entrants = vec![
+            Entrant::Agent(AgentKind::Random),
+            Entrant::Agent(AgentKind::Greedy),
+            Entrant::External {
+                name: "always-down".to_string(),
+                command: "echo READY; yes D".to_string(),
+            },
+        ];
+        let options = TournamentOptions {
+            seeds: vec![1, 2, 3],
+            max_moves: Some(50),
+            ..TournamentOptions::default()
+        };
+        let tournament = run_tournament(&GameSettings::default(), entrants, &options);
+        let standings = tournament.standings();
+        assert_eq!(standings.len(), 3);
+        assert!(standings[0].mean_score >= standings[2].mean_score);
+        let wins: f64 = standings.iter().map(|s| s.win_rate).sum();
+        assert!((wins - 1.0).abs() < 1e-9);
+        let external = standings.iter().find(|s| s.name == "always-down").unwrap();
+        assert_eq!(external.games, 3);
+        assert!(tournament.records[2].iter().all(|record| record.moves > 0));
+        assert_eq!(tournament.csv().lines().count(), 1 + 3 * 3);
+    }
+}
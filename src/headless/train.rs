@@ -0,0 +1,122 @@
+use std::io;
+
+use super::game::Game;
+use crate::ai::ntuple::NTupleNetwork;
+use crate::resources::game_settings::{BoardSize, GameSettings};
+use crate::structs::bitboard::{Bitboard, BITBOARD_SIZE};
+
+// NTupleNetwork を自己対戦の TD(0) で学習する設定
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrainOptions {
+    pub games: usize,
+    // 1 回の更新で value を誤差のこの割合だけ動かす
+    pub learning_rate: f32,
+    // i 局目は seed + i で始める
+    pub seed: u64,
+    // この局数ごとに TrainReport を出す
+    pub report_interval: usize,
+}
+
+impl Default for TrainOptions {
+    fn default() -> Self {
+        return Self {
+            games: 10_000,
+            learning_rate: 0.1,
+            seed: 0,
+            report_interval: 1000,
+        };
+    }
+}
+
+// 直近の report_interval 局の成績
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrainReport {
+    pub games: usize,
+    pub average_score: f64,
+    pub max_score: usize,
+    // 2048 に届いた局の割合
+    pub reach_2048: f64,
+}
+
+// 盤面は 4x4 だけ. on_report で途中の重みを保存できる
+pub fn train(
+    network: &mut NTupleNetwork,
+    settings: &GameSettings,
+    options: &TrainOptions,
+    mut on_report: impl FnMut(&TrainReport, &NTupleNetwork) -> io::Result<()>,
+) -> io::Result<Vec<TrainReport>> {
+    let size = BoardSize {
+        width: BITBOARD_SIZE,
+        height: BITBOARD_SIZE,
+    };
+    if settings.board_size != size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "n-tuple training needs a 4x4 board",
+        ));
+    }
+    let interval = options.report_interval.max(1);
+    let alpha = options.learning_rate / network.feature_count() as f32;
+    let mut reports = Vec::new();
+    let mut scores = Vec::with_capacity(interval);
+    let mut reached = 0;
+    for i in 0..options.games {
+        let game = play_episode(
+            network,
+            settings,
+            options.seed.wrapping_add(i as u64),
+            alpha,
+        );
+        scores.push(game.score().value());
+        if game
+            .board()
+            .max_tile()
+            .is_some_and(|tile| tile.num() >= 2048)
+        {
+            reached += 1;
+        }
+        if scores.len() == interval || i + 1 == options.games {
+            let report = TrainReport {
+                games: i + 1,
+                average_score: scores.iter().sum::<usize>() as f64 / scores.len() as f64,
+                max_score: scores.iter().copied().max().unwrap_or(0),
+                reach_2048: reached as f64 / scores.len() as f64,
+            };
+            on_report(&report, network)?;
+            reports.push(report);
+            scores.clear();
+            reached = 0;
+        }
+    }
+    return Ok(reports);
+}
+
+// 動かした後の盤面 (afterstate) の value を、次の手の得点と afterstate の value に近づける
+fn play_episode(
+    network: &mut NTupleNetwork,
+    settings: &GameSettings,
+    seed: u64,
+    alpha: f32,
+) -> Game {
+    let mut game = Game::new(settings, seed);
+    let mut previous: Option<Bitboard> = None;
+    while !game.is_game_over() {
+        let Ok(board) = Bitboard::try_from(game.board()) else {
+            break;
+        };
+        let Some(evaluation) = network.best_move(board) else {
+            break;
+        };
+        if let Some(previous) = previous {
+            let error = evaluation.total() - network.value(previous);
+            network.update(previous, alpha * error);
+        }
+        previous = Some(evaluation.after);
+        game.step(evaluation.direction);
+    }
+    if let Some(previous) = previous {
+        let error = -network.value(previous);
+        network.update(previous, alpha * error);
+    }
+    return game;
+}
@@ -0,0 +1,183 @@
+use std::thread;
+
+use super::env::{Env, EnvConfig, StepInfo};
+
+// 複数の Env をまとめて進める. 観測は 1 本のバッファに env ごとに続けて並べる
+// 終わった Env は次の seed ですぐに reset し、final_observations に最後の観測を残す
+pub struct VecEnv {
+    envs: Vec<Env>,
+    seeds: Vec<u64>,
+    threads: usize,
+    observation_len: usize,
+}
+
+pub struct BatchStep {
+    // [env 数 * observation_len]
+    pub observations: Vec<f32>,
+    pub action_masks: Vec<[bool; 4]>,
+    pub rewards: Vec<f32>,
+    pub dones: Vec<bool>,
+    pub infos: Vec<StepInfo>,
+    // done の Env だけ, reset 前の観測
+    pub final_observations: Vec<Option<Vec<f32>>>,
+}
+
+impl VecEnv {
+    pub fn new(config: EnvConfig, count: usize) -> Self {
+        let envs: Vec<Env> = (0..count).map(|_| Env::new(config.clone())).collect();
+        let observation_len = envs.first().map_or(0, Env::observation_len);
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        return Self {
+            envs,
+            seeds: vec![0; count],
+            threads,
+            observation_len,
+        };
+    }
+    // 1 なら呼び出したスレッドだけで進める
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        return self;
+    }
+    pub fn len(&self) -> usize {
+        return self.envs.len();
+    }
+    pub fn is_empty(&self) -> bool {
+        return self.envs.is_empty();
+    }
+    pub fn envs(&self) -> &[Env] {
+        return &self.envs;
+    }
+    pub fn observation_len(&self) -> usize {
+        return self.observation_len;
+    }
+    // i 番目の Env は seed + i から始め、reset のたびに len だけ進める
+    pub fn reset(&mut self, seed: u64) -> (Vec<f32>, Vec<[bool; 4]>) {
+        let mut observations = vec![0.0; self.len() * self.observation_len];
+        let mut masks = Vec::with_capacity(self.len());
+        let chunks = observations.chunks_mut(self.observation_len.max(1));
+        for ((i, env), out) in self.envs.iter_mut().enumerate().zip(chunks) {
+            self.seeds[i] = seed.wrapping_add(i as u64);
+            env.reset(self.seeds[i]);
+            env.encode(out);
+            masks.push(env.action_mask());
+        }
+        return (observations, masks);
+    }
+    pub fn step(&mut self, actions: &[usize]) -> BatchStep {
+        assert_eq!(actions.len(), self.len(), "actions must match env count");
+        let len = self.observation_len;
+        let count = self.len();
+        let mut observations = vec![0.0; count * len];
+        let mut results: Vec<Option<EnvStep>> = (0..count).map(|_| None).collect();
+        let per_thread = count.div_ceil(self.threads.min(count).max(1)).max(1);
+        let stride = count as u64;
+        thread::scope(|scope| {
+            let jobs = self
+                .envs
+                .chunks_mut(per_thread)
+                .zip(self.seeds.chunks_mut(per_thread))
+                .zip(actions.chunks(per_thread))
+                .zip(observations.chunks_mut(per_thread * len.max(1)))
+                .zip(results.chunks_mut(per_thread));
+            for ((((envs, seeds), actions), observations), results) in jobs {
+                let job = move || {
+                    let outs = observations.chunks_mut(len.max(1));
+                    let items = envs.iter_mut().zip(seeds).zip(actions).zip(outs);
+                    for ((((env, seed), &action), out), result) in items.zip(results) {
+                        *result = Some(step_env(env, seed, stride, action, out));
+                    }
+                };
+                if self.threads <= 1 {
+                    job();
+                } else {
+                    scope.spawn(job);
+                }
+            }
+        });
+        let mut batch = BatchStep {
+            observations,
+            action_masks: Vec::with_capacity(count),
+            rewards: Vec::with_capacity(count),
+            dones: Vec::with_capacity(count),
+            infos: Vec::with_capacity(count),
+            final_observations: Vec::with_capacity(count),
+        };
+        for result in results.into_iter().flatten() {
+            batch.action_masks.push(result.action_mask);
+            batch.rewards.push(result.reward);
+            batch.dones.push(result.done);
+            batch.infos.push(result.info);
+            batch.final_observations.push(result.final_observation);
+        }
+        return batch;
+    }
+}
+
+struct EnvStep {
+    action_mask: [bool; 4],
+    reward: f32,
+    done: bool,
+    info: StepInfo,
+    final_observation: Option<Vec<f32>>,
+}
+
+fn step_env(env: &mut Env, seed: &mut u64, stride: u64, action: usize, out: &mut [f32]) -> EnvStep {
+    let (reward, done, info) = env.step_without_observation(action);
+    env.encode(out);
+    let mut final_observation = None;
+    if done {
+        final_observation = Some(out.to_vec());
+        *seed = seed.wrapping_add(stride);
+        env.reset(*seed);
+        env.encode(out);
+    }
+    return EnvStep {
+        action_mask: env.action_mask(),
+        reward,
+        done,
+        info,
+        final_observation,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::env::ObservationEncoding;
+
+    #[test]
+    fn batch_matches_single_env() {
+        let config = EnvConfig {
+            encoding: ObservationEncoding::Ranks,
+            ..EnvConfig::default()
+        };
+        let mut batch = VecEnv::new(config.clone(), 3).with_threads(2);
+        let mut single = Env::new(config);
+        let (observations, masks) = batch.reset(7);
+        assert_eq!(observations.len(), 3 * 16);
+        let observation = single.reset(8);
+        assert_eq!(observations[16..32], observation.data[..]);
+        assert_eq!(masks[1], observation.action_mask);
+        let mut mask = masks[1];
+        for turn in 0..200 {
+            let action = observation_action(&mask, turn);
+            let step = batch.step(&[action; 3]);
+            let (observation, reward, done, _) = single.step(action);
+            assert_eq!(step.rewards[1], reward);
+            assert_eq!(step.dones[1], done);
+            if done {
+                break;
+            }
+            assert_eq!(step.observations[16..32], observation.data[..]);
+            mask = step.action_masks[1];
+        }
+    }
+
+    fn observation_action(mask: &[bool; 4], turn: usize) -> usize {
+        return (0..4)
+            .map(|i| (turn + i) % 4)
+            .find(|&i| mask[i])
+            .unwrap_or(0);
+    }
+}
@@ -0,0 +1,18 @@
+#![allow(clippy::needless_return)]
+
+pub mod ai;
+pub mod bundle;
+pub mod cli;
+pub mod components;
+pub mod constants;
+pub mod error;
+pub mod headless;
+pub mod network;
+pub mod plugins;
+pub mod resources;
+pub mod states;
+pub mod structs;
+pub mod title_menu;
+
+pub use plugins::game_plugin_group::{Game2048Plugin, GamePluginGroup};
+pub use resources::game_settings::GameSettings;
@@ -1,36 +1,294 @@
-use bevy::app::*;
+#![allow(clippy::needless_return)]
+
+use std::io;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use bevy::prelude::*;
-use bevy_prng::WyRand;
-use bevy_rand::plugin::EntropyPlugin;
-
-mod bundle;
-mod components;
-mod constants;
-mod error;
-mod plugins;
-mod resources;
-mod states;
-mod structs;
-mod title_menu;
-mod util;
-
-use constants::layout::{WINDOW_HEIGHT, WINDOW_WIDTH};
-use plugins::game_plugin_group::GamePluginGroup;
 
+use bevy_2048::{
+    ai::{
+        ntuple::{self, NTupleNetwork},
+        AgentKind, NTUPLE_FALLBACK_DEPTH,
+    },
+    cli::{self, Command, Connect, Play},
+    headless::{
+        battle::Battle,
+        bot::{self, BotOptions},
+        daily::verify_daily,
+        simulate::simulate_seeds,
+        tournament::{run_tournament, Entrant, TournamentOptions},
+        train::{self, TrainOptions},
+    },
+    plugins::network::NetSession,
+    resources::game_settings::{DailySettings, StatsSettings},
+    resources::{
+        daily::{daily_seed, today, DailyHistory, DailyResult},
+        lifetime_stats::LifetimeStats,
+    },
+    Game2048Plugin, GameSettings,
+};
+
+// 引数の書き方は cli.rs
 fn main() {
-    dbg!((WINDOW_WIDTH, WINDOW_HEIGHT));
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = cli::parse(&args).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    match command {
+        Command::Play(play) => run_play(play),
+        Command::Battle {
+            settings,
+            seed,
+            p1,
+            p2,
+            max_rounds,
+        } => run_battle(&settings, seed, p1, p2, max_rounds),
+        Command::Simulate {
+            agent,
+            seeds,
+            max_moves,
+        } => run_simulate(agent, &seeds, max_moves),
+        Command::Tournament {
+            entrants,
+            options,
+            csv,
+        } => run_tournament_command(entrants, &options, csv.as_deref()),
+        Command::Stats { json } => run_stats(json),
+        Command::Daily { verify } => run_daily(verify.as_deref()),
+        Command::Bot(options) => run_bot(&options),
+        Command::Train {
+            options,
+            weights,
+            out,
+        } => run_train(&options, weights.as_deref(), &out),
+    }
+}
+
+fn run_play(play: Play) {
+    let session = play.connect.as_ref().map(connect);
+    let mut settings = match &session {
+        Some(session) => session.game_settings(&play.settings),
+        None => play.settings.clone(),
+    };
+    play.options.apply(&mut settings);
+    // --weights がなければ DEFAULT_WEIGHTS_PATH を、あれば使う
+    settings.ntuple = match &play.options.weights {
+        Some(path) => Some(Arc::new(load_weights(path))),
+        None => NTupleNetwork::load(ntuple::DEFAULT_WEIGHTS_PATH)
+            .ok()
+            .map(Arc::new),
+    };
+    let uses_ntuple = settings.ui.hint == Some(AgentKind::NTuple)
+        || settings
+            .boards
+            .iter()
+            .any(|board| board.ai.is_some_and(|ai| ai.agent == AgentKind::NTuple));
+    if uses_ntuple && settings.ntuple.is_none() {
+        eprintln!(
+            "no n-tuple weights in {}: using expectimax:{} instead",
+            ntuple::DEFAULT_WEIGHTS_PATH,
+            NTUPLE_FALLBACK_DEPTH
+        );
+    }
+    let size = settings.window_size();
     let window = Window {
         title: "2048".to_string(),
-        resolution: (WINDOW_WIDTH, WINDOW_HEIGHT).into(),
+        resolution: (size.x, size.y).into(),
         ..default()
     };
 
-    App::new()
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(window),
-            ..default()
-        }))
-        .add_plugins(EntropyPlugin::<WyRand>::default())
-        .add_plugins(GamePluginGroup)
-        .run();
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins.set(WindowPlugin {
+        primary_window: Some(window),
+        ..default()
+    }))
+    .add_plugins(Game2048Plugin::new(settings));
+    if let Some(session) = session {
+        app.insert_resource(session);
+    }
+    app.run();
+}
+
+fn run_battle(settings: &GameSettings, seed: u64, p1: AgentKind, p2: AgentKind, max_rounds: usize) {
+    let settings = &with_ntuple(settings.clone(), [p1, p2]);
+    let mut battle = Battle::new(settings, &[seed, seed]);
+    let mut agents = vec![p1.build(settings, seed), p2.build(settings, seed)];
+    let outcome = battle.play(&mut agents, max_rounds);
+    for (i, game) in battle.games().iter().enumerate() {
+        println!("P{}: score {} moves {}", i + 1, game.score(), game.moves());
+        println!("{}", game.board());
+    }
+    println!("{:?} after {} rounds", outcome, battle.rounds());
+}
+
+// 同じ seed の列で Agent を比べるための 1 人用の連続実行
+fn run_simulate(kind: AgentKind, seeds: &[u64], max_moves: Option<usize>) {
+    let settings = with_ntuple(GameSettings::default(), [kind]);
+    let records = simulate_seeds(&settings, kind, seeds, max_moves);
+    println!("seed,score,moves,max_tile");
+    for record in records.iter() {
+        println!(
+            "{},{},{},{}",
+            record.seed, record.score, record.moves, record.max_tile
+        );
+    }
+    let total: usize = records.iter().map(|record| record.score).sum();
+    eprintln!(
+        "{}: average score {:.1} over {} games",
+        kind,
+        total as f64 / records.len().max(1) as f64,
+        records.len()
+    );
+}
+
+fn run_tournament_command(entrants: Vec<Entrant>, options: &TournamentOptions, csv: Option<&str>) {
+    let kinds = entrants.iter().filter_map(|entrant| match entrant {
+        Entrant::Agent(kind) => Some(*kind),
+        Entrant::External { .. } => None,
+    });
+    let settings = with_ntuple(GameSettings::default(), kinds);
+    let tournament = run_tournament(&settings, entrants, options);
+    print!("{}", tournament.report());
+    if let Some(path) = csv {
+        if let Err(e) = std::fs::write(path, tournament.csv()) {
+            eprintln!("failed to write {}: {}", path, e);
+        }
+    }
+}
+
+fn run_stats(json: bool) {
+    let stats = LifetimeStats::load_or_default(&StatsSettings::default().path);
+    match json {
+        true => println!("{}", stats.to_json()),
+        false => print!("{}", stats.to_csv()),
+    }
+}
+
+fn run_daily(verify: Option<&str>) {
+    if let Some(path) = verify {
+        return verify_daily_share(path);
+    }
+    let date = today();
+    let history = DailyHistory::load_or_default(&DailySettings::default().path);
+    println!("{} seed {}", date, daily_seed(date));
+    match history.played(date) {
+        Some(result) => print!("{}", result.share_text()),
+        None => println!("not played yet"),
+    }
+    println!(
+        "streak {} (best {}) over {} days",
+        history.current_streak(date),
+        history.best_streak(),
+        history.results.len()
+    );
+}
+
+fn verify_daily_share(path: &str) {
+    let text = match path {
+        "-" => io::read_to_string(io::stdin()),
+        _ => std::fs::read_to_string(path),
+    };
+    let text = text.unwrap_or_else(|e| {
+        eprintln!("failed to read {}: {}", path, e);
+        std::process::exit(1);
+    });
+    let Some(result) = DailyResult::parse_share_text(&text) else {
+        eprintln!("not a daily summary");
+        std::process::exit(1);
+    };
+    match verify_daily(&result) {
+        Ok(()) => println!(
+            "ok: {} score {} max tile {} in {} moves",
+            result.date,
+            result.score,
+            result.max_tile,
+            result.moves()
+        ),
+        Err(e) => {
+            eprintln!("mismatch: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_bot(options: &BotOptions) {
+    let stdin = io::BufReader::new(io::stdin());
+    if let Err(e) = bot::run_bot(&GameSettings::default(), options, stdin, io::stdout()) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+// weights を与えると続きから学習する. 1000 局ごとに成績を CSV で出し、重みを保存する
+fn run_train(options: &TrainOptions, weights: Option<&str>, out: &str) {
+    let mut network = match weights {
+        Some(path) => load_weights(path),
+        None => NTupleNetwork::default(),
+    };
+    println!("games,average_score,max_score,reach_2048");
+    let result = train::train(
+        &mut network,
+        &GameSettings::default(),
+        options,
+        |report, network| {
+            println!(
+                "{},{:.1},{},{:.3}",
+                report.games, report.average_score, report.max_score, report.reach_2048
+            );
+            return network.save(out);
+        },
+    );
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+// 描画なしで AgentKind::NTuple を比べるときは、重みがなければ止める
+fn with_ntuple(
+    mut settings: GameSettings,
+    kinds: impl IntoIterator<Item = AgentKind>,
+) -> GameSettings {
+    if kinds.into_iter().any(|kind| kind == AgentKind::NTuple) {
+        settings.ntuple = Some(Arc::new(load_weights(ntuple::DEFAULT_WEIGHTS_PATH)));
+    }
+    return settings;
+}
+
+fn load_weights(path: &str) -> NTupleNetwork {
+    match NTupleNetwork::load(path) {
+        Ok(network) => return network,
+        Err(e) => {
+            eprintln!("failed to load {}: {}", path, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+// 通信対戦の相手と接続する. seed がなければ時刻から決める
+fn connect(connect: &Connect) -> NetSession {
+    let time_seed = || {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        return now.as_nanos() as u64;
+    };
+    let session = match connect {
+        Connect::Host { port, seed } => {
+            println!("waiting for an opponent on port {}", port);
+            NetSession::host(("0.0.0.0", *port), seed.unwrap_or_else(time_seed))
+        }
+        Connect::Join { addr, seed } => {
+            NetSession::join(addr.as_str(), seed.unwrap_or_else(time_seed))
+        }
+    };
+    match session {
+        Ok(session) => return session,
+        Err(e) => {
+            eprintln!("failed to connect: {}", e);
+            std::process::exit(1);
+        }
+    }
 }
@@ -0,0 +1,178 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::protocol::{Message, PROTOCOL_VERSION};
+
+// 受信スレッドから届くもの
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NetEvent {
+    Message(Message),
+    // 相手が切断した, または通信できなくなった
+    Disconnected,
+}
+
+// 1 対 1 の TCP 接続. 受信は別スレッドで行い、try_recv で取り出す
+pub struct Connection {
+    stream: TcpStream,
+    events: Mutex<Receiver<NetEvent>>,
+}
+
+impl Connection {
+    // addr で待ち受けて、最初に来た 1 つと接続する
+    pub fn host(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        return Self::accept(&TcpListener::bind(addr)?);
+    }
+    pub fn accept(listener: &TcpListener) -> io::Result<Self> {
+        let (stream, _) = listener.accept()?;
+        return Self::new(stream);
+    }
+    pub fn join(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        return Self::new(TcpStream::connect(addr)?);
+    }
+    fn new(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nodelay(true)?;
+        let reader = BufReader::new(stream.try_clone()?);
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in reader.lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                match line.parse() {
+                    Ok(message) => {
+                        if sender.send(NetEvent::Message(message)).is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => eprintln!("{}", e),
+                }
+            }
+            let _ = sender.send(NetEvent::Disconnected);
+        });
+        return Ok(Self {
+            stream,
+            events: Mutex::new(receiver),
+        });
+    }
+    pub fn send(&self, message: Message) -> io::Result<()> {
+        return writeln!(&self.stream, "{}", message);
+    }
+    pub fn try_recv(&self) -> Option<NetEvent> {
+        match self.events.lock().unwrap().try_recv() {
+            Ok(event) => return Some(event),
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => return Some(NetEvent::Disconnected),
+        }
+    }
+    // HELLO を送り合い、相手の seed を返す
+    pub fn handshake(&self, seed: u64, timeout: Duration) -> io::Result<u64> {
+        self.send(Message::Hello {
+            version: PROTOCOL_VERSION,
+            seed,
+        })?;
+        let event = match self.events.lock().unwrap().recv_timeout(timeout) {
+            Ok(event) => event,
+            Err(RecvTimeoutError::Timeout) => {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "no HELLO"))
+            }
+            Err(RecvTimeoutError::Disconnected) => NetEvent::Disconnected,
+        };
+        match event {
+            NetEvent::Message(Message::Hello { version, seed }) if version == PROTOCOL_VERSION => {
+                return Ok(seed)
+            }
+            NetEvent::Message(Message::Hello { version, .. }) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("protocol version {} is not supported", version),
+                ))
+            }
+            NetEvent::Message(message) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("expected HELLO, got {}", message),
+                ))
+            }
+            NetEvent::Disconnected => {
+                return Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "disconnected during handshake",
+                ))
+            }
+        }
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let _ = self.send(Message::Bye);
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::direction::Direction;
+
+    fn recv(connection: &Connection) -> NetEvent {
+        for _ in 0..100 {
+            if let Some(event) = connection.try_recv() {
+                return event;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        panic!("nothing received");
+    }
+
+    #[test]
+    fn message_round_trip() {
+        let messages = [
+            Message::Hello {
+                version: PROTOCOL_VERSION,
+                seed: u64::MAX,
+            },
+            Message::Input {
+                turn: 42,
+                direction: Direction::Left,
+            },
+            Message::Hash {
+                turn: 10,
+                hash: 0x0123_4567_89AB_CDEF,
+            },
+            Message::Bye,
+        ];
+        for message in messages {
+            assert_eq!(message.to_string().parse::<Message>(), Ok(message));
+        }
+        assert!("INPUT 1 X".parse::<Message>().is_err());
+    }
+
+    #[test]
+    fn handshake_on_localhost() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let guest = std::thread::spawn(move || {
+            let connection = Connection::join(addr).unwrap();
+            let seed = connection.handshake(2, Duration::from_secs(5)).unwrap();
+            return (connection, seed);
+        });
+        let host = Connection::accept(&listener).unwrap();
+        assert_eq!(host.handshake(1, Duration::from_secs(5)).unwrap(), 2);
+        let (guest, seed) = guest.join().unwrap();
+        assert_eq!(seed, 1);
+
+        let input = Message::Input {
+            turn: 0,
+            direction: Direction::Up,
+        };
+        host.send(input).unwrap();
+        assert_eq!(recv(&guest), NetEvent::Message(input));
+        drop(host);
+        assert_eq!(recv(&guest), NetEvent::Message(Message::Bye));
+        assert_eq!(recv(&guest), NetEvent::Disconnected);
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+// これより大きな body は受け付けない
+const MAX_BODY_SIZE: usize = 64 * 1024;
+// Bevy 側が返信しないまま待つ時間
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    pub body: String,
+}
+
+impl HttpRequest {
+    // 空の body は {} とみなす
+    pub fn json(&self) -> Result<Value, HttpResponse> {
+        if self.body.trim().is_empty() {
+            return Ok(json!({}));
+        }
+        return serde_json::from_str(&self.body)
+            .map_err(|e| HttpResponse::error(400, &format!("invalid JSON: {}", e)));
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl HttpResponse {
+    pub fn ok(body: Value) -> Self {
+        return Self { status: 200, body };
+    }
+    pub fn error(status: u16, message: &str) -> Self {
+        return Self {
+            status,
+            body: json!({ "error": message }),
+        };
+    }
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => return "OK",
+            202 => return "Accepted",
+            400 => return "Bad Request",
+            404 => return "Not Found",
+            405 => return "Method Not Allowed",
+            409 => return "Conflict",
+            413 => return "Payload Too Large",
+            _ => return "Internal Server Error",
+        }
+    }
+}
+
+// 届いたリクエストと、その返信先
+pub struct PendingRequest {
+    pub request: HttpRequest,
+    responder: Sender<HttpResponse>,
+}
+
+impl PendingRequest {
+    pub fn respond(self, response: HttpResponse) {
+        let _ = self.responder.send(response);
+    }
+}
+
+// 127.0.0.1 でのみ待ち受ける、JSON を返すだけの小さな HTTP サーバー
+// 接続ごとにスレッドを立て、リクエストは try_recv で取り出して返信する
+pub struct HttpServer {
+    addr: SocketAddr,
+    requests: Mutex<Receiver<PendingRequest>>,
+}
+
+impl HttpServer {
+    // port = 0 なら空いている port を使う
+    pub fn bind_loopback(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        let addr = listener.local_addr()?;
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                let sender = sender.clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = handle_connection(stream, sender) {
+                        eprintln!("http: {}", e);
+                    }
+                });
+            }
+        });
+        return Ok(Self {
+            addr,
+            requests: Mutex::new(receiver),
+        });
+    }
+    pub fn addr(&self) -> SocketAddr {
+        return self.addr;
+    }
+    pub fn try_recv(&self) -> Option<PendingRequest> {
+        match self.requests.lock().unwrap().try_recv() {
+            Ok(request) => return Some(request),
+            Err(TryRecvError::Empty | TryRecvError::Disconnected) => return None,
+        }
+    }
+}
+
+fn handle_connection(stream: TcpStream, sender: Sender<PendingRequest>) -> io::Result<()> {
+    stream.set_read_timeout(Some(RESPONSE_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let response = match read_request(&mut reader) {
+        Ok(request) => {
+            let (responder, receiver) = mpsc::channel();
+            let pending = PendingRequest { request, responder };
+            match sender.send(pending) {
+                Ok(()) => receiver
+                    .recv_timeout(RESPONSE_TIMEOUT)
+                    .unwrap_or_else(|_| HttpResponse::error(500, "no response from the game")),
+                Err(_) => HttpResponse::error(500, "the game is not running"),
+            }
+        }
+        Err(response) => response,
+    };
+    return write_response(&mut &stream, &response);
+}
+
+pub fn read_request(reader: &mut impl BufRead) -> Result<HttpRequest, HttpResponse> {
+    let bad_request = |message: &str| HttpResponse::error(400, message);
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .map_err(|_| bad_request("failed to read the request"))?;
+    let mut words = line.split_whitespace();
+    let (Some(method), Some(target)) = (words.next(), words.next()) else {
+        return Err(bad_request("invalid request line"));
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        reader
+            .read_line(&mut header)
+            .map_err(|_| bad_request("failed to read headers"))?;
+        let header = header.trim();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| bad_request("invalid Content-Length"))?;
+            }
+        }
+    }
+    if content_length > MAX_BODY_SIZE {
+        return Err(HttpResponse::error(413, "the body is too large"));
+    }
+    let mut body = vec![0; content_length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|_| bad_request("failed to read the body"))?;
+    return Ok(HttpRequest {
+        method: method.to_string(),
+        path: path.to_string(),
+        query,
+        body: String::from_utf8(body).map_err(|_| bad_request("the body is not UTF-8"))?,
+    });
+}
+
+pub fn write_response(writer: &mut impl Write, response: &HttpResponse) -> io::Result<()> {
+    let body = response.body.to_string();
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        response.reason(),
+        body.len(),
+        body
+    )?;
+    return writer.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn request_and_response_on_loopback() {
+        let server = HttpServer::bind_loopback(0).unwrap();
+        assert!(server.addr().ip().is_loopback());
+        let addr = server.addr();
+        let client = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            let body = r#"{"direction": "U"}"#;
+            write!(
+                stream,
+                "POST /move?board=1 HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            return response;
+        });
+        let pending = loop {
+            if let Some(pending) = server.try_recv() {
+                break pending;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(pending.request.method, "POST");
+        assert_eq!(pending.request.path, "/move");
+        assert_eq!(pending.request.query["board"], "1");
+        assert_eq!(pending.request.json().unwrap()["direction"], "U");
+        pending.respond(HttpResponse::ok(json!({ "accepted": true })));
+        let response = client.join().unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(r#"{"accepted":true}"#));
+    }
+}
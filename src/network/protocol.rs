@@ -0,0 +1,81 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::structs::direction::Direction;
+
+pub const PROTOCOL_VERSION: u32 = 1;
+
+// 1 行 1 メッセージのテキストでやり取りする
+//   HELLO <version> <seed>
+//   INPUT <turn> <U|D|L|R>
+//   HASH <turn> <hash (16 進)>
+//   BYE
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Message {
+    // 接続直後にお互いに送る. seed は送った側の盤面の seed
+    Hello { version: u32, seed: u64 },
+    // turn 番目のターンに、送った側の盤面を動かす向き
+    Input { turn: usize, direction: Direction },
+    // turn 番目のターンが始まる時点の、両方の盤面の hash
+    Hash { turn: usize, hash: u64 },
+    Bye,
+}
+
+impl Display for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Message::Hello { version, seed } => return write!(f, "HELLO {} {}", version, seed),
+            Message::Input { turn, direction } => {
+                return write!(f, "INPUT {} {}", turn, direction.letter())
+            }
+            Message::Hash { turn, hash } => return write!(f, "HASH {} {:016x}", turn, hash),
+            Message::Bye => return write!(f, "BYE"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProtocolError(pub String);
+
+impl Display for ProtocolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "protocol error: {}", self.0);
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+impl FromStr for Message {
+    type Err = ProtocolError;
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let error = || ProtocolError(format!("invalid message: {:?}", line));
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words[..] {
+            ["HELLO", version, seed] => {
+                return Ok(Message::Hello {
+                    version: version.parse().map_err(|_| error())?,
+                    seed: seed.parse().map_err(|_| error())?,
+                })
+            }
+            ["INPUT", turn, direction] => {
+                let mut chars = direction.chars();
+                let direction = match (chars.next(), chars.next()) {
+                    (Some(c), None) => Direction::from_letter(c).ok_or_else(error)?,
+                    _ => return Err(error()),
+                };
+                return Ok(Message::Input {
+                    turn: turn.parse().map_err(|_| error())?,
+                    direction,
+                });
+            }
+            ["HASH", turn, hash] => {
+                return Ok(Message::Hash {
+                    turn: turn.parse().map_err(|_| error())?,
+                    hash: u64::from_str_radix(hash, 16).map_err(|_| error())?,
+                })
+            }
+            ["BYE"] => return Ok(Message::Bye),
+            _ => return Err(error()),
+        }
+    }
+}
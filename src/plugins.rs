@@ -1,8 +1,22 @@
 pub mod game_plugin_group;
 
+pub mod achievements;
+pub mod analysis;
 pub mod calculate;
+pub mod coach;
+pub mod daily;
+pub mod game_mode;
 pub mod game_over;
+pub mod high_scores;
+pub mod hint;
+pub mod http_api;
 pub mod input;
 pub mod movement;
+pub mod network;
+pub mod new_game;
+pub mod puzzle;
+pub mod score_board;
 pub mod setup;
 pub mod spawn;
+pub mod stats;
+pub mod versus;
@@ -0,0 +1,156 @@
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::bundle::achievement::{create_gallery, show_toast};
+use crate::components::{
+    achievement::{AchievementGallery, AchievementToast},
+    ai_controller::AiController,
+    board_state::BoardState,
+    main_board::MainBoard,
+    score::Score,
+};
+use crate::plugins::{calculate::ScoreEvent, spawn::BoardGameOverEvent};
+use crate::resources::{
+    achievements::{Achievement, Achievements, GameProgress},
+    game_settings::GameSettings,
+    move_history::MoveHistory,
+};
+use crate::states::game_state::GameState;
+use crate::structs::game_mode::GameMode;
+use crate::title_menu::component::ButtonAction;
+
+pub const GALLERY_KEY: KeyCode = KeyCode::KeyG;
+
+// GameSettings::achievements があるとき、1 人用で人が操作する盤面の実績を解除する
+// ScoreEvent と BoardGameOverEvent が来た盤面について、Tile が出現した後に条件を調べる
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        // パズルの記録は PuzzlePlugin が残す
+        let single_player = |settings: Res<GameSettings>| {
+            settings.versus.is_none() && settings.mode != GameMode::Puzzle
+        };
+        app.add_systems(Startup, load_achievements)
+            .add_systems(Update, check_achievements.run_if(single_player))
+            .add_systems(
+                Update,
+                (update_toasts, toggle_gallery).run_if(resource_exists::<Achievements>),
+            );
+    }
+}
+
+fn load_achievements(mut commands: Commands, settings: Res<GameSettings>) {
+    let Some(achievement_settings) = &settings.achievements else {
+        return;
+    };
+    let definitions = match &achievement_settings.definitions_path {
+        Some(path) if std::path::Path::new(path).exists() => Achievement::load_definitions(path)
+            .unwrap_or_else(|e| {
+                eprintln!("failed to load {}: {}", path, e);
+                return Achievement::defaults();
+            }),
+        _ => Achievement::defaults(),
+    };
+    let mut achievements = Achievements::new(definitions);
+    if std::path::Path::new(&achievement_settings.path).exists() {
+        if let Err(e) = achievements.load_unlocked(&achievement_settings.path) {
+            eprintln!("failed to load {}: {}", achievement_settings.path, e);
+        }
+    }
+    commands.insert_resource(achievements);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn check_achievements(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
+    state: Res<State<GameState>>,
+    history: Res<MoveHistory>,
+    mut score_evr: EventReader<ScoreEvent>,
+    mut game_over_evr: EventReader<BoardGameOverEvent>,
+    query: Query<(&MainBoard, &BoardState, &Score), Without<AiController>>,
+    toasts_query: Query<(), With<AchievementToast>>,
+    achievements: Option<ResMut<Achievements>>,
+    // 動いたが、まだ調べていない盤面
+    mut moved: Local<HashSet<Entity>>,
+) {
+    moved.extend(score_evr.read().map(|ev| ev.board));
+    moved.extend(game_over_evr.read().map(|ev| ev.board));
+    // Tile の出現を待つ
+    if !matches!(state.get(), GameState::Input | GameState::GameOver) {
+        return;
+    }
+    let (Some(mut achievements), Some(achievement_settings)) =
+        (achievements, &settings.achievements)
+    else {
+        moved.clear();
+        return;
+    };
+    let mut unlocked = Vec::new();
+    for entity in moved.drain() {
+        // AI の盤面と、新しいゲームで消えた盤面は数えない
+        let Ok((main_board, board, score)) = query.get(entity) else {
+            continue;
+        };
+        let moves = history.board_moves(main_board.index).count();
+        let progress = GameProgress::new(board, score.value(), moves, &settings.mode);
+        unlocked.extend(achievements.check(&progress));
+    }
+    if unlocked.is_empty() {
+        return;
+    }
+    let shown = toasts_query.iter().count();
+    for (i, achievement) in unlocked.iter().enumerate() {
+        show_toast(
+            &mut commands,
+            &asset_server,
+            &settings,
+            achievement,
+            shown + i,
+        );
+    }
+    if let Err(e) = achievements.save_unlocked(&achievement_settings.path) {
+        eprintln!("failed to save {}: {}", achievement_settings.path, e);
+    }
+}
+
+fn update_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut AchievementToast)>,
+) {
+    for (entity, mut toast) in query.iter_mut() {
+        if toast.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+type TitleButtonQuery<'w, 's> =
+    Query<'w, 's, (&'static Interaction, &'static ButtonAction), Changed<Interaction>>;
+
+// G か、タイトル画面の Awards で開閉する
+fn toggle_gallery(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    achievements: Res<Achievements>,
+    buttons_query: TitleButtonQuery,
+    galleries_query: Query<Entity, With<AchievementGallery>>,
+) {
+    let clicked = buttons_query.iter().any(|(interaction, action)| {
+        *interaction == Interaction::Pressed && matches!(action, ButtonAction::Achievements)
+    });
+    if !clicked && !keyboard.just_pressed(GALLERY_KEY) {
+        return;
+    }
+    if galleries_query.is_empty() {
+        create_gallery(&mut commands, &asset_server, &settings, &achievements);
+        return;
+    }
+    for entity in galleries_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,198 @@
+use bevy::prelude::*;
+use bevy::tasks::{block_on, poll_once, AsyncComputeTaskPool, Task};
+
+use crate::ai::analysis::{analyze, MoveAnalysis};
+use crate::bundle::{analysis_panel::create_analysis_panel, tile::sync_tiles};
+use crate::components::{
+    analysis_panel::{AnalysisBar, AnalysisDetail},
+    board_state::BoardState,
+    main_board::MainBoard,
+};
+use crate::resources::{
+    game_analysis::GameAnalysis, game_settings::GameSettings, move_history::MoveHistory,
+};
+use crate::states::game_state::GameState;
+
+pub const NEXT_BLUNDER_KEY: KeyCode = KeyCode::KeyN;
+pub const PREVIOUS_BLUNDER_KEY: KeyCode = KeyCode::KeyP;
+
+// 1 人用の GameOver の後に、記録した手を AI で見直して悪手を示す
+// 見直しは AsyncComputeTaskPool で進め、終わったら GameOver の画面にパネルを出す
+// 選んだ手の動かす前の盤面を 1 番目の盤面に戻して見せる. 次のゲームで盤面は作り直す
+pub struct AnalysisPlugin;
+
+impl Plugin for AnalysisPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::GameOver),
+            start_analysis.run_if(|settings: Res<GameSettings>| settings.versus.is_none()),
+        )
+        .add_systems(OnExit(GameState::GameOver), cancel_analysis)
+        .add_systems(
+            Update,
+            (
+                poll_analysis.run_if(resource_exists::<AnalysisTask>),
+                create_analysis_panel.run_if(resource_added::<GameAnalysis>),
+                (select_by_key, select_by_click, show_selected)
+                    .chain()
+                    .run_if(resource_exists::<GameAnalysis>),
+                (replay_selected, sync_tiles)
+                    .chain()
+                    .run_if(resource_exists_and_changed::<GameAnalysis>),
+            )
+                .chain()
+                .run_if(in_state(GameState::GameOver)),
+        );
+    }
+}
+
+// 見直しの途中. 落とすと止まる
+#[derive(Resource)]
+pub struct AnalysisTask(Task<Vec<MoveAnalysis>>);
+
+fn start_analysis(mut commands: Commands, settings: Res<GameSettings>, history: Res<MoveHistory>) {
+    let moves: Vec<_> = history
+        .board_moves(0)
+        .map(|record| (record.before.clone(), record.direction))
+        .collect();
+    let spawn = settings.spawn.clone();
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let moves = moves.iter().map(|(before, direction)| (before, *direction));
+        return analyze(moves, &spawn);
+    });
+    commands.insert_resource(AnalysisTask(task));
+}
+
+fn poll_analysis(mut commands: Commands, mut task: ResMut<AnalysisTask>) {
+    let Some(moves) = block_on(poll_once(&mut task.0)) else {
+        return;
+    };
+    commands.remove_resource::<AnalysisTask>();
+    commands.insert_resource(GameAnalysis::new(moves));
+}
+
+// 見直しが終わる前に次のゲームを始めた
+fn cancel_analysis(mut commands: Commands) {
+    commands.remove_resource::<AnalysisTask>();
+}
+
+fn select_by_key(keyboard: Res<ButtonInput<KeyCode>>, mut analysis: ResMut<GameAnalysis>) {
+    if keyboard.just_pressed(NEXT_BLUNDER_KEY) {
+        analysis.next_blunder();
+    }
+    if keyboard.just_pressed(PREVIOUS_BLUNDER_KEY) {
+        analysis.previous_blunder();
+    }
+}
+
+type PressedBarQuery<'w, 's> =
+    Query<'w, 's, (&'static Interaction, &'static AnalysisBar), Changed<Interaction>>;
+
+fn select_by_click(query: PressedBarQuery, mut analysis: ResMut<GameAnalysis>) {
+    for (interaction, bar) in query.iter() {
+        if *interaction == Interaction::Pressed {
+            analysis.selected = bar.index;
+        }
+    }
+}
+
+fn show_selected(
+    analysis: Res<GameAnalysis>,
+    settings: Res<GameSettings>,
+    mut bars_query: Query<(&AnalysisBar, &mut BackgroundColor)>,
+    mut detail_query: Query<&mut Text, With<AnalysisDetail>>,
+) {
+    if !analysis.is_changed() {
+        return;
+    }
+    let theme = &settings.theme;
+    for (bar, mut color) in bars_query.iter_mut() {
+        let blunder = analysis.moves.get(bar.index).is_some_and(|m| m.blunder);
+        *color = match (bar.index == analysis.selected, blunder) {
+            (true, _) => theme.tile_high.into(),
+            (false, true) => theme.blunder.into(),
+            (false, false) => theme.tile_low.into(),
+        };
+    }
+    let text = match analysis.selected() {
+        Some(selected) => {
+            let played = match selected.value {
+                Some(value) => format!("{:.0}", value),
+                None => "-".to_string(),
+            };
+            format!(
+                "move {}{}\nplayed {:?} ({})  best {:?} ({:.0})",
+                selected.turn + 1,
+                if selected.blunder { "  BLUNDER" } else { "" },
+                selected.direction,
+                played,
+                selected.best_direction,
+                selected.best_value
+            )
+        }
+        None => "no moves".to_string(),
+    };
+    for mut detail in detail_query.iter_mut() {
+        detail.sections[0].value = text.clone();
+    }
+}
+
+// 選んだ手の動かす前の盤面を 1 番目の盤面に出す
+fn replay_selected(analysis: Res<GameAnalysis>, mut query: Query<(&MainBoard, &mut BoardState)>) {
+    let Some(selected) = analysis.selected() else {
+        return;
+    };
+    for (main_board, mut board) in query.iter_mut() {
+        if main_board.index == 0 && *board != selected.before {
+            *board = selected.before.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::components::{position::Position, tile::Tile};
+    use crate::structs::direction::Direction;
+
+    fn move_analysis(turn: usize, rank: u8, blunder: bool) -> MoveAnalysis {
+        let mut before = BoardState::new(4, 4);
+        before.set(Position::new(0, 0), Some(Tile(rank)));
+        return MoveAnalysis {
+            turn,
+            before,
+            direction: Direction::Left,
+            value: Some(0.0),
+            best_direction: Direction::Right,
+            best_value: if blunder { 100.0 } else { 0.0 },
+            blunder,
+        };
+    }
+
+    #[test]
+    fn selecting_a_blunder_shows_its_position() {
+        let moves = vec![
+            move_analysis(0, 1, false),
+            move_analysis(1, 2, true),
+            move_analysis(2, 3, false),
+            move_analysis(3, 4, true),
+        ];
+        let mut world = World::new();
+        let mut last = BoardState::new(4, 4);
+        last.set(Position::new(3, 3), Some(Tile(5)));
+        let board = world.spawn((MainBoard { index: 0 }, last)).id();
+        let mut analysis = GameAnalysis::new(moves.clone());
+        analysis.selected = 0;
+        analysis.next_blunder();
+        world.insert_resource(analysis);
+
+        world.run_system_once(replay_selected);
+        assert_eq!(world.get::<BoardState>(board), Some(&moves[1].before));
+
+        world.resource_mut::<GameAnalysis>().next_blunder();
+        world.run_system_once(replay_selected);
+        assert_eq!(world.get::<BoardState>(board), Some(&moves[3].before));
+    }
+}
@@ -1,157 +1,284 @@
-use bevy::ecs::query::QueryEntityError;
-use bevy::ecs::system::QueryLens;
 use bevy::prelude::*;
-use std::collections::VecDeque;
-use std::result::Result;
 
-use crate::components::position::Position;
-use crate::components::tile::Tile;
-use crate::error::handle_query_entity_errors;
+use crate::components::{
+    board_state::BoardState, board_status::BoardStatus, main_board::MainBoard, position::Position,
+    score::Score, tile::Tile,
+};
 use crate::plugins::input::PlayerInputEvent;
+use crate::resources::move_history::MoveHistory;
 use crate::states::game_state::GameState;
-use crate::structs::grid_array::{GridArray, RotatedGridArray};
 use crate::structs::quater_turn::QuarterTurn;
 
 pub struct CalculatePlugin;
 
 impl Plugin for CalculatePlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<SlicedMovementEvent>()
-            .add_event::<TileMovementEvent>()
+        app.add_event::<TileMovementEvent>()
+            .add_event::<ScoreEvent>()
             .add_systems(
                 OnEnter(GameState::Calculate),
-                (
-                    handle_player_input,
-                    calc_sliced_movement.pipe(handle_query_entity_errors),
-                    GameState::Movement.set_next(),
-                )
-                    .chain(),
+                (handle_player_input, GameState::Movement.set_next()).chain(),
             );
     }
 }
 
-#[derive(Event, Eq, PartialEq)]
-pub struct SlicedMovementEvent(Vec<Option<Entity>>, QuarterTurn);
+// board 上での 1 ターン分の Tile の移動
+#[derive(Event, Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TileMovementEvent {
+    pub board: Entity,
+    pub movement: TileMovement,
+}
+
+// board の 1 ターン分の得点
+#[derive(Event, Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ScoreEvent {
+    pub board: Entity,
+    // このターンの合体で増えた点
+    pub gained: usize,
+    // 増えた後の Score
+    pub score: usize,
+}
+
+// Tile 1 つ (合体する場合は 2 つ) の移動
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TileMovement {
+    Slide {
+        tile: Tile,
+        from: Position,
+        to: Position,
+    },
+    // from の Tile が残り、partner_from の Tile は消える
+    Merge {
+        from: Position,
+        partner_from: Position,
+        to: Position,
+        rank: Tile,
+    },
+}
 
-#[derive(Event, Eq, PartialEq)]
-pub enum TileMovementEvent {
-    OneStep(Entity, QuarterTurn),
-    Merge(Entity, Entity, QuarterTurn),
+// スライス上での移動. from, to は落ちる方向の端からの index
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SliceMovement<T> {
+    Slide {
+        item: T,
+        from: usize,
+        to: usize,
+    },
+    Merge {
+        item: T,
+        partner: T,
+        from: usize,
+        partner_from: usize,
+        to: usize,
+        rank: Tile,
+    },
 }
 
-// 盤面の状態を Entity と紐づけて取得
-pub fn get_tiles_layout_with_entity(
-    lens: &mut QueryLens<(Entity, &Position)>,
-) -> GridArray<Option<Entity>> {
-    let mut tiles_layout: GridArray<Option<Entity>> = GridArray::new(None);
-    for (e, pos) in lens.query().iter() {
-        tiles_layout.0[pos.x][pos.y] = Some(e);
+// 盤面を turn の方向に落としたときの移動を計算
+pub fn calc_tile_movements(board: &BoardState, turn: QuarterTurn) -> Vec<TileMovement> {
+    let tiles_layout = board.grid().rotated(turn);
+    let mut movements = Vec::new();
+    // 動いた方向にスライスしてそれぞれの移動先を計算
+    for (i, down_axis) in tiles_layout.lines().enumerate() {
+        let position = |j: usize| tiles_layout.position(i, j);
+        let slice: Vec<Option<(Tile, Tile)>> =
+            down_axis.map(|op| op.map(|tile| (tile, tile))).collect();
+        for movement in calc_tiles_slice(&slice) {
+            movements.push(match movement {
+                SliceMovement::Slide { item, from, to } => TileMovement::Slide {
+                    tile: item,
+                    from: position(from),
+                    to: position(to),
+                },
+                SliceMovement::Merge {
+                    from,
+                    partner_from,
+                    to,
+                    rank,
+                    ..
+                } => TileMovement::Merge {
+                    from: position(from),
+                    partner_from: position(partner_from),
+                    to: position(to),
+                    rank,
+                },
+            });
+        }
     }
-    return tiles_layout;
+    return movements;
 }
 
-// PlayerInputEvent に基づいて SlicedMovementEvent を発行
+// PlayerInputEvent に基づいて盤面を更新し、TileMovementEvent を発行
 pub fn handle_player_input(
     mut input_evr: EventReader<PlayerInputEvent>,
-    mut sliced_move_evw: EventWriter<SlicedMovementEvent>,
-    mut query: Query<(Entity, &mut Transform, &mut Position), With<Tile>>,
+    mut tile_move_evw: EventWriter<TileMovementEvent>,
+    mut score_evw: EventWriter<ScoreEvent>,
+    mut query: Query<(&MainBoard, &mut BoardState, &mut Score, &mut BoardStatus)>,
+    mut history: ResMut<MoveHistory>,
 ) {
-    dbg!("System: handle_player_input");
     for ev in input_evr.read() {
-        // 移動方向と回転回数
-        // tiles_layout[x][y] にアクセスするので、行列の並び方と 90deg ずれることに注意
-        // 回転させなければ下に落ちる
-        // ex: 反時計回りに 90deg 回転させて考えることで左に落ちる
-        let turn: QuarterTurn = match ev {
-            PlayerInputEvent::Down => QuarterTurn::Deg000,
-            PlayerInputEvent::Left => QuarterTurn::Deg090,
-            PlayerInputEvent::Up => QuarterTurn::Deg180,
-            PlayerInputEvent::Right => QuarterTurn::Deg270,
+        let Ok((main_board, mut board, mut score, mut status)) = query.get_mut(ev.board) else {
+            continue;
         };
-        // 盤面の状態の取得
-        let tiles_layout: RotatedGridArray<Option<Entity>> = RotatedGridArray {
-            grid_array: get_tiles_layout_with_entity(
-                &mut query.transmute_lens::<(Entity, &Position)>(),
-            ),
-            turn,
-        };
-        let vec: Vec<Vec<Option<Entity>>> = tiles_layout.into();
-        // 動いた方向にスライスしてそれぞれについて SlicedMovementEvent を発行
-        for down_axis in vec.into_iter() {
-            sliced_move_evw.send(SlicedMovementEvent(down_axis, turn));
+        if *status != BoardStatus::Input {
+            continue;
         }
+        // 移動方向と回転回数
+        // 回転させなければ下に落ちる
+        let turn: QuarterTurn = ev.direction.falling_turn();
+        let movements = calc_tile_movements(&board, turn);
+        let before = board.clone();
+        let gained = board.apply(&movements);
+        score.add(gained);
+        *status = BoardStatus::Spawn;
+        score_evw.send(ScoreEvent {
+            board: ev.board,
+            gained,
+            score: score.value(),
+        });
+        history.push(main_board.index, before, ev.direction, score.value());
+        tile_move_evw.send_batch(movements.into_iter().map(|movement| TileMovementEvent {
+            board: ev.board,
+            movement,
+        }));
     }
 }
 
-fn shift_tiles_one_step(
-    tile_entitys: Vec<Option<Entity>>,
-    turn: QuarterTurn,
-    tile_move_evw: &mut EventWriter<TileMovementEvent>,
-) {
-    for option in tile_entitys {
-        if let Some(e) = option {
-            tile_move_evw.send(TileMovementEvent::OneStep(e, turn));
+// 先頭 (index = 0) に向かって Tile を落としたときの、各 Tile の最終的な移動先を計算
+pub fn calc_tiles_slice<T: Copy>(slice: &[Option<(T, Tile)>]) -> Vec<SliceMovement<T>> {
+    let mut movements: Vec<SliceMovement<T>> = Vec::new();
+    // 次に埋まる index
+    let mut to = 0;
+    // 合体相手を待っている Tile
+    let mut pending: Option<(T, Tile, usize)> = None;
+    for (from, cell) in slice.iter().enumerate() {
+        let Some((item, tile)) = *cell else {
+            continue;
+        };
+        match pending.take() {
+            Some((p_item, p_tile, p_from)) if p_tile == tile && !tile.is_blocker() => {
+                let mut rank = p_tile;
+                rank.double();
+                movements.push(SliceMovement::Merge {
+                    item: p_item,
+                    partner: item,
+                    from: p_from,
+                    partner_from: from,
+                    to,
+                    rank,
+                });
+                to += 1;
+            }
+            Some((p_item, _, p_from)) => {
+                movements.push(SliceMovement::Slide {
+                    item: p_item,
+                    from: p_from,
+                    to,
+                });
+                to += 1;
+                pending = Some((item, tile, from));
+            }
+            None => {
+                pending = Some((item, tile, from));
+            }
         }
     }
+    if let Some((item, _, from)) = pending {
+        movements.push(SliceMovement::Slide { item, from, to });
+    }
+    return movements;
 }
 
-fn calc_tiles_slice(
-    tile_entitys: &mut VecDeque<Option<Entity>>,
-    turn: QuarterTurn,
-    tile_move_evw: &mut EventWriter<TileMovementEvent>,
-    query: &Query<&Tile>,
-) -> Result<(), QueryEntityError> {
-    match tile_entitys.pop_front() {
-        // tile_entitys is empty. i.e. tile_entitys = [].
-        None => {
-            return Ok(());
-        }
-        // 要素を持つが、最初が空白だった場合. i.e. tile_entitys = None: _.
-        Some(None) => {
-            shift_tiles_one_step((*tile_entitys).clone().into(), turn, tile_move_evw);
-            return calc_tiles_slice(tile_entitys, turn, tile_move_evw, &query);
-        }
-        // 最初の要素が Tile だった場合. i.e. tile_entitys = Some(e0): _.
-        Some(Some(e0)) => {
-            match tile_entitys.pop_front() {
-                // 1つしか要素を持たなかった場合. i.e. tile_entitys = Some(e0): [].
-                None => {
-                    return Ok(());
-                }
-                // tile_entitys = Some(e0): None: _.
-                Some(None) => {
-                    shift_tiles_one_step((*tile_entitys).clone().into(), turn, tile_move_evw);
-                    tile_entitys.push_front(Some(e0));
-                    return calc_tiles_slice(tile_entitys, turn, tile_move_evw, &query);
-                }
-                // tile_entitys = Some(e0): Some(e1): _.
-                Some(Some(e1)) => {
-                    let tile0 = query.get(e0)?;
-                    let tile1 = query.get(e1)?;
-                    if tile0 == tile1 {
-                        tile_move_evw.send(TileMovementEvent::Merge(e0, e1, turn));
-                        shift_tiles_one_step((*tile_entitys).clone().into(), turn, tile_move_evw);
-                        return calc_tiles_slice(tile_entitys, turn, tile_move_evw, &query);
-                    } else {
-                        tile_entitys.push_front(Some(e1));
-                        return calc_tiles_slice(tile_entitys, turn, tile_move_evw, &query);
-                    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SliceMovement::{Merge, Slide};
+
+    // rank の列から (index, Tile) を item にしたスライスを作る. 0 は空きマス
+    fn slice(ranks: &[u8]) -> Vec<Option<(usize, Tile)>> {
+        return ranks
+            .iter()
+            .enumerate()
+            .map(|(i, &rank)| (rank > 0).then_some((i, Tile(rank))))
+            .collect();
+    }
+
+    fn merge(item: usize, partner: usize, to: usize, rank: u8) -> SliceMovement<usize> {
+        return Merge {
+            item,
+            partner,
+            from: item,
+            partner_from: partner,
+            to,
+            rank: Tile(rank),
+        };
+    }
+
+    #[test]
+    fn merge_pairs_from_the_front() {
+        // [2, 2, 2, 2] -> [4, 4]
+        assert_eq!(
+            calc_tiles_slice(&slice(&[1, 1, 1, 1])),
+            vec![merge(0, 1, 0, 2), merge(2, 3, 1, 2)]
+        );
+        // [2, 2, 4] -> [4, 4]. 合体してできた Tile は同じターンにもう合体しない
+        assert_eq!(
+            calc_tiles_slice(&slice(&[1, 1, 2])),
+            vec![
+                merge(0, 1, 0, 2),
+                Slide {
+                    item: 2,
+                    from: 2,
+                    to: 1
                 }
-            }
-        }
+            ]
+        );
     }
-}
 
-pub fn calc_sliced_movement(
-    mut sliced_move_evr: EventReader<SlicedMovementEvent>,
-    mut tile_move_evw: EventWriter<TileMovementEvent>,
-    query: Query<&Tile>,
-) -> Result<(), QueryEntityError> {
-    dbg!("System: calc_sliced_movement");
-    for SlicedMovementEvent(tile_entitys, turn) in sliced_move_evr.read() {
-        let mut tile_entitys: VecDeque<Option<Entity>> = (*tile_entitys).clone().into();
+    #[test]
+    fn slide_over_gaps() {
+        // [_, 2, _, 2] -> [4]
+        assert_eq!(
+            calc_tiles_slice(&slice(&[0, 1, 0, 1])),
+            vec![merge(1, 3, 0, 2)]
+        );
+        // [_, 4, _, 2] -> [4, 2]
+        assert_eq!(
+            calc_tiles_slice(&slice(&[0, 2, 0, 1])),
+            vec![
+                Slide {
+                    item: 1,
+                    from: 1,
+                    to: 0
+                },
+                Slide {
+                    item: 3,
+                    from: 3,
+                    to: 1
+                }
+            ]
+        );
+    }
 
-        calc_tiles_slice(&mut tile_entitys, *turn, &mut tile_move_evw, &query)?;
+    #[test]
+    fn blockers_do_not_merge() {
+        let mut blockers = slice(&[0, 1, 1, 0]);
+        blockers[1] = Some((1, Tile::BLOCKER));
+        blockers[2] = Some((2, Tile::BLOCKER));
+        assert_eq!(
+            calc_tiles_slice(&blockers),
+            vec![
+                Slide {
+                    item: 1,
+                    from: 1,
+                    to: 0
+                },
+                Slide {
+                    item: 2,
+                    from: 2,
+                    to: 1
+                }
+            ]
+        );
     }
-    return Ok(());
 }
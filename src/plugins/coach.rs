@@ -0,0 +1,120 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use bevy::prelude::*;
+
+use crate::bundle::notice::show_notice;
+use crate::components::notice::Notice;
+use crate::resources::{
+    coach::Coach,
+    game_settings::{GameSettings, SpawnPolicy},
+};
+
+// GameSettings::coach があるとき、キーボードで選んだ危ない手の前に確認する
+// 確認は input_from_keyboard が Coach::review で行い、ここでは表示と記録だけをする
+pub struct CoachPlugin;
+
+impl Plugin for CoachPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Coach::new(&SpawnPolicy::default(), 0))
+            .add_systems(
+                Update,
+                (
+                    sync_spawn.run_if(resource_changed::<GameSettings>),
+                    (show_warning, write_log).run_if(resource_changed::<Coach>),
+                ),
+            );
+    }
+}
+
+// 先読みの Tile の出現を GameSettings::spawn に合わせる
+fn sync_spawn(settings: Res<GameSettings>, mut coach: ResMut<Coach>) {
+    coach.set_spawn(&settings.spawn);
+}
+
+fn show_warning(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
+    coach: Res<Coach>,
+    notices_query: Query<Entity, With<Notice>>,
+    mut showing: Local<bool>,
+) {
+    let Some(warning) = coach.latest_pending() else {
+        // 確認が済んだら警告を消す
+        if *showing {
+            for entity in notices_query.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+            *showing = false;
+        }
+        return;
+    };
+    let horizon = settings.coach.as_ref().map_or(0, |coach| coach.horizon);
+    let mut reasons = Vec::new();
+    if warning.risk.loss_chance > 0.0 {
+        reasons.push(format!(
+            "{:.0}% to lose in {} turns",
+            warning.risk.loss_chance * 100.0,
+            horizon
+        ));
+    }
+    if warning.risk.leaves_corner {
+        reasons.push("max tile leaves the corner".to_string());
+    }
+    if reasons.is_empty() {
+        reasons.push("risky move".to_string());
+    }
+    show_notice(
+        &mut commands,
+        &asset_server,
+        &settings,
+        &notices_query,
+        format!(
+            "P{}: {:?}? {}. Are you sure? press again",
+            warning.board + 1,
+            warning.direction,
+            reasons.join(", ")
+        ),
+    );
+    *showing = true;
+}
+
+// 確認が済んだ手をファイルに追記する
+fn write_log(settings: Res<GameSettings>, coach: Res<Coach>, mut written: Local<usize>) {
+    let Some(path) = settings
+        .coach
+        .as_ref()
+        .and_then(|coach| coach.log_path.as_ref())
+    else {
+        return;
+    };
+    let new_entries = coach.log.get(*written..).unwrap_or_default();
+    if new_entries.is_empty() {
+        return;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path);
+    let mut file = match file {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("failed to open {}: {}", path, e);
+            return;
+        }
+    };
+    for warning in new_entries {
+        let line = format!(
+            "board={} turn={} direction={} loss_chance={:.3} leaves_corner={} decision={}",
+            warning.board,
+            warning.turn,
+            warning.direction.letter(),
+            warning.risk.loss_chance,
+            warning.risk.leaves_corner,
+            warning.decision.name()
+        );
+        if let Err(e) = writeln!(file, "{}", line) {
+            eprintln!("failed to write {}: {}", path, e);
+            return;
+        }
+    }
+    *written = coach.log.len();
+}
@@ -0,0 +1,142 @@
+use bevy::prelude::*;
+use bevy_prng::WyRand;
+use bevy_rand::component::EntropyComponent;
+use rand_core::SeedableRng;
+
+use crate::bundle::notice::show_notice;
+use crate::components::{
+    ai_controller::AiController, board_state::BoardState, main_board::MainBoard, notice::Notice,
+    score::Score,
+};
+use crate::plugins::new_game::NewGameEvent;
+use crate::resources::{
+    daily::{daily_seed, today, DailyHistory, DailyResult, DailyRun},
+    game_settings::GameSettings,
+    move_history::MoveHistory,
+};
+use crate::states::game_state::GameState;
+use crate::structs::game_mode::GameMode;
+
+// GameSettings::daily があるとき、GameMode::Daily の盤面をその日の seed で始め、
+// 1 日 1 回目の結果を記録する
+pub struct DailyPlugin;
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_history)
+            .add_systems(OnExit(GameState::TitleMenu), start_daily)
+            .add_systems(OnExit(GameState::NewGame), start_daily)
+            .add_systems(OnEnter(GameState::GameOver), record_daily);
+    }
+}
+
+fn load_history(mut commands: Commands, settings: Res<GameSettings>) {
+    let Some(daily_settings) = &settings.daily else {
+        return;
+    };
+    commands.insert_resource(DailyHistory::load_or_default(&daily_settings.path));
+}
+
+// 最初の Spawn の前に、全ての盤面の乱数をその日の seed にする
+#[allow(clippy::too_many_arguments)]
+fn start_daily(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
+    history: Option<Res<DailyHistory>>,
+    notices: Query<Entity, With<Notice>>,
+    mut query: Query<&mut EntropyComponent<WyRand>, With<MainBoard>>,
+    mut new_game_evw: EventWriter<NewGameEvent>,
+) {
+    if settings.mode != GameMode::Daily || settings.versus.is_some() {
+        commands.remove_resource::<DailyRun>();
+        return;
+    }
+    // 共有された記録を確かめられるように、Daily は既定のルールで遊ぶ
+    if !settings.uses_daily_rules() {
+        commands.remove_resource::<DailyRun>();
+        new_game_evw.send(NewGameEvent {
+            settings: Some(settings.daily_rules()),
+            seed: None,
+        });
+        show_notice(
+            &mut commands,
+            &asset_server,
+            &settings,
+            &notices,
+            "daily uses the default board and spawns",
+        );
+        return;
+    }
+    let date = today();
+    for mut rng in query.iter_mut() {
+        *rng = EntropyComponent::seed_from_u64(daily_seed(date));
+    }
+    let practice = history.is_some_and(|history| history.played(date).is_some());
+    commands.insert_resource(DailyRun { date, practice });
+    if practice {
+        show_notice(
+            &mut commands,
+            &asset_server,
+            &settings,
+            &notices,
+            format!("{} is recorded. practice only", date),
+        );
+    }
+}
+
+type PlayerBoardQuery<'w, 's> =
+    Query<'w, 's, (&'static MainBoard, &'static BoardState, &'static Score), Without<AiController>>;
+
+#[allow(clippy::too_many_arguments)]
+fn record_daily(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
+    move_history: Res<MoveHistory>,
+    run: Option<Res<DailyRun>>,
+    history: Option<ResMut<DailyHistory>>,
+    notices: Query<Entity, With<Notice>>,
+    query: PlayerBoardQuery,
+) {
+    let (Some(run), Some(mut history), Some(daily_settings)) = (run, history, &settings.daily)
+    else {
+        return;
+    };
+    let Some((main_board, board, score)) =
+        query.iter().min_by_key(|(main_board, ..)| main_board.index)
+    else {
+        return;
+    };
+    let result = DailyResult {
+        date: run.date,
+        score: score.value(),
+        max_tile: board.max_tile().map_or(0, |tile| tile.num()),
+        directions: move_history
+            .board_moves(main_board.index)
+            .map(|record| record.direction.letter())
+            .collect(),
+    };
+    if !history.record(result.clone()) {
+        return;
+    }
+    if let Err(e) = history.save(&daily_settings.path) {
+        eprintln!("failed to save {}: {}", daily_settings.path, e);
+    }
+    if let Some(path) = &daily_settings.share_path {
+        if let Err(e) = std::fs::write(path, result.share_text()) {
+            eprintln!("failed to write {}: {}", path, e);
+        }
+    }
+    show_notice(
+        &mut commands,
+        &asset_server,
+        &settings,
+        &notices,
+        format!(
+            "daily {}: streak {}",
+            run.date,
+            history.current_streak(run.date)
+        ),
+    );
+}
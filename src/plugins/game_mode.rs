@@ -0,0 +1,151 @@
+use bevy::prelude::*;
+use bevy_prng::WyRand;
+use bevy_rand::resource::GlobalEntropy;
+use rand_core::RngCore;
+
+use crate::components::{
+    ai_controller::AiController, board_state::BoardState, board_status::BoardStatus,
+    lockstep::Lockstep, main_board::MainBoard,
+};
+use crate::plugins::{input::PlayerInputEvent, spawn::BoardGameOverEvent};
+use crate::resources::{
+    game_clock::GameClock, game_settings::GameSettings, move_history::MoveHistory,
+};
+use crate::states::game_state::GameState;
+use crate::structs::game_mode::{BlitzTimeout, GameMode};
+
+// GameClock を進め、1 人用で GameSettings::mode の制限に達したら GameOver にする
+pub struct GameModePlugin;
+
+impl Plugin for GameModePlugin {
+    fn build(&self, app: &mut App) {
+        let single_player = |settings: Res<GameSettings>| settings.versus.is_none();
+        app.add_systems(OnEnter(GameState::NewGame), reset_clock)
+            .add_systems(
+                OnEnter(GameState::Input),
+                (reset_turn_clock, end_after_move_limit.run_if(single_player)),
+            )
+            .add_systems(
+                Update,
+                (
+                    tick_clock,
+                    (end_after_time_limit, force_move_on_timeout)
+                        .run_if(in_state(GameState::Input))
+                        .run_if(single_player),
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn reset_clock(mut clock: ResMut<GameClock>) {
+    *clock = GameClock::default();
+}
+
+fn reset_turn_clock(mut clock: ResMut<GameClock>) {
+    clock.turn_elapsed = default();
+}
+
+// タイトル画面と GameOver の後は止める. 手の時間は Input の間だけ進める
+fn tick_clock(time: Res<Time>, state: Res<State<GameState>>, mut clock: ResMut<GameClock>) {
+    match state.get() {
+        GameState::TitleMenu | GameState::NewGame | GameState::GameOver => return,
+        GameState::Input => clock.turn_elapsed += time.delta(),
+        _ => {}
+    }
+    clock.elapsed += time.delta();
+}
+
+// 全ての盤面を GameOver にする
+fn end_game(
+    query: &mut Query<(Entity, &mut BoardStatus)>,
+    game_over_evw: &mut EventWriter<BoardGameOverEvent>,
+    next_state: &mut NextState<GameState>,
+) {
+    for (board, mut status) in query.iter_mut() {
+        if *status != BoardStatus::GameOver {
+            *status = BoardStatus::GameOver;
+            game_over_evw.send(BoardGameOverEvent { board });
+        }
+    }
+    next_state.set(GameState::GameOver);
+}
+
+fn end_after_move_limit(
+    settings: Res<GameSettings>,
+    history: Res<MoveHistory>,
+    boards_query: Query<&MainBoard>,
+    mut query: Query<(Entity, &mut BoardStatus)>,
+    mut game_over_evw: EventWriter<BoardGameOverEvent>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(limit) = settings.mode.move_limit() else {
+        return;
+    };
+    let reached = boards_query
+        .iter()
+        .any(|main_board| history.board_moves(main_board.index).count() >= limit);
+    if reached {
+        end_game(&mut query, &mut game_over_evw, &mut next_state);
+    }
+}
+
+fn end_after_time_limit(
+    settings: Res<GameSettings>,
+    clock: Res<GameClock>,
+    mut query: Query<(Entity, &mut BoardStatus)>,
+    mut game_over_evw: EventWriter<BoardGameOverEvent>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(limit) = settings.mode.time_limit() else {
+        return;
+    };
+    if clock.elapsed >= limit {
+        end_game(&mut query, &mut game_over_evw, &mut next_state);
+    }
+}
+
+type BlitzBoardQuery<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static BoardState, &'static mut BoardStatus),
+    (With<MainBoard>, Without<AiController>, Without<Lockstep>),
+>;
+
+// Blitz で時間が切れた盤面を BlitzTimeout に従って進める
+fn force_move_on_timeout(
+    settings: Res<GameSettings>,
+    clock: Res<GameClock>,
+    mut query: BlitzBoardQuery,
+    mut rng: ResMut<GlobalEntropy<WyRand>>,
+    mut input_evw: EventWriter<PlayerInputEvent>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let GameMode::Blitz { timeout, .. } = settings.mode else {
+        return;
+    };
+    if settings
+        .mode
+        .move_time_limit()
+        .is_none_or(|limit| clock.turn_elapsed < limit)
+    {
+        return;
+    }
+    for (board, board_state, mut status) in query.iter_mut() {
+        if *status != BoardStatus::Input {
+            continue;
+        }
+        let directions: Vec<_> = board_state.legal_directions().collect();
+        match (timeout, directions.is_empty()) {
+            (BlitzTimeout::RandomMove, false) => {
+                let direction = directions[rng.next_u32() as usize % directions.len()];
+                input_evw.send(PlayerInputEvent { board, direction });
+                next_state.set(GameState::Calculate);
+            }
+            _ => {
+                *status = BoardStatus::Spawn;
+                next_state.set(GameState::Spawn);
+            }
+        }
+    }
+}
@@ -1,9 +1,11 @@
 use bevy::{
     app::{AppExit, Plugin},
-    ecs::{event::EventWriter, schedule::OnEnter},
+    ecs::{event::EventWriter, schedule::OnEnter, system::Res},
 };
 
+use crate::resources::game_settings::GameSettings;
 use crate::states::game_state::GameState;
+use crate::structs::game_mode::GameMode;
 
 pub struct GameOverPlugin;
 
@@ -13,7 +15,10 @@ impl Plugin for GameOverPlugin {
     }
 }
 
-pub fn end_game(mut exit: EventWriter<AppExit>) {
+pub fn end_game(mut exit: EventWriter<AppExit>, settings: Res<GameSettings>) {
     println!("GAME OVER!!");
-    exit.send(AppExit);
+    // パズルは次のパズルかやり直しを選ぶ
+    if settings.ui.exit_on_game_over && settings.mode != GameMode::Puzzle {
+        exit.send(AppExit);
+    }
 }
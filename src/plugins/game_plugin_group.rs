@@ -1,9 +1,16 @@
-use bevy::app::{PluginGroup, PluginGroupBuilder};
+use bevy::app::{App, Plugin, PluginGroup, PluginGroupBuilder};
+use bevy_prng::WyRand;
+use bevy_rand::plugin::EntropyPlugin;
 
 use super::{
-    calculate::CalculatePlugin, game_over::GameOverPlugin, input::InputPlugin,
-    movement::MovementPlugin, setup::SetupPlugin, spawn::SpawnPlugin,
+    achievements::AchievementsPlugin, analysis::AnalysisPlugin, calculate::CalculatePlugin,
+    coach::CoachPlugin, daily::DailyPlugin, game_mode::GameModePlugin, game_over::GameOverPlugin,
+    high_scores::HighScoresPlugin, hint::HintPlugin, http_api::HttpApiPlugin, input::InputPlugin,
+    movement::MovementPlugin, network::NetworkPlugin, new_game::NewGamePlugin,
+    puzzle::PuzzlePlugin, score_board::ScoreBoardPlugin, setup::SetupPlugin, spawn::SpawnPlugin,
+    stats::StatsPlugin, versus::VersusPlugin,
 };
+use crate::resources::game_settings::GameSettings;
 use crate::title_menu::plugin::TitleMenuPlugin;
 
 pub struct GamePluginGroup;
@@ -17,6 +24,69 @@ impl PluginGroup for GamePluginGroup {
             .add(InputPlugin)
             .add(CalculatePlugin)
             .add(MovementPlugin)
+            .add(ScoreBoardPlugin)
+            .add(GameModePlugin)
             .add(GameOverPlugin)
+            .add(VersusPlugin)
+            .add(NetworkPlugin)
+            .add(NewGamePlugin)
+    }
+}
+
+// 他の App に組み込むための Plugin
+#[derive(Default)]
+pub struct Game2048Plugin {
+    pub settings: GameSettings,
+}
+
+impl Game2048Plugin {
+    pub fn new(settings: GameSettings) -> Self {
+        return Self { settings };
+    }
+}
+
+impl Plugin for Game2048Plugin {
+    fn build(&self, app: &mut App) {
+        // 遊んでいる途中ではなく、ここで止める
+        if let Err(e) = self.settings.spawn.validate() {
+            panic!("invalid GameSettings::spawn: {}", e);
+        }
+        app.insert_resource(self.settings.clone());
+        if !app.is_plugin_added::<EntropyPlugin<WyRand>>() {
+            app.add_plugins(EntropyPlugin::<WyRand>::default());
+        }
+        let mut group = GamePluginGroup.build();
+        if !self.settings.ui.title_menu {
+            group = group.disable::<TitleMenuPlugin>();
+        }
+        app.add_plugins(group);
+        // 既定では無効
+        if self.settings.http_api.is_some() {
+            app.add_plugins(HttpApiPlugin);
+        }
+        if self.settings.ui.hint.is_some() {
+            app.add_plugins(HintPlugin);
+        }
+        if self.settings.ui.analysis {
+            app.add_plugins(AnalysisPlugin);
+        }
+        if self.settings.coach.is_some() {
+            app.add_plugins(CoachPlugin);
+        }
+        if self.settings.stats.is_some() {
+            app.add_plugins(StatsPlugin);
+        }
+        if self.settings.achievements.is_some() {
+            app.add_plugins(AchievementsPlugin);
+        }
+        if self.settings.high_scores.is_some() {
+            app.add_plugins(HighScoresPlugin);
+        }
+        if self.settings.daily.is_some() {
+            app.add_plugins(DailyPlugin);
+        }
+        if self.settings.puzzles.is_some() {
+            app.add_plugins(PuzzlePlugin);
+        }
     }
 }
@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+
+use crate::bundle::{high_score_table::create_high_score_table, notice::show_notice};
+use crate::components::{
+    ai_controller::AiController, board_state::BoardState, high_score_table::HighScoreTable,
+    main_board::MainBoard, notice::Notice, score::Score,
+};
+use crate::resources::{
+    best_score::BestScore,
+    daily::DailyRun,
+    game_clock::GameClock,
+    game_settings::GameSettings,
+    high_scores::{HighScore, HighScores},
+    move_history::MoveHistory,
+};
+use crate::states::game_state::GameState;
+use crate::structs::game_mode::GameMode;
+
+pub const HIGH_SCORE_KEY: KeyCode = KeyCode::KeyT;
+
+// GameSettings::high_scores があるとき、1 人用で人が操作する盤面の得点をモードごとの表に残す
+// BestScore は今のモードの表の 1 位に合わせる
+pub struct HighScoresPlugin;
+
+impl Plugin for HighScoresPlugin {
+    fn build(&self, app: &mut App) {
+        // パズルの記録は PuzzlePlugin が残す. Daily は 1 日 1 回目だけ
+        let single_player = |settings: Res<GameSettings>, daily: Option<Res<DailyRun>>| {
+            settings.versus.is_none()
+                && settings.mode != GameMode::Puzzle
+                && !daily.is_some_and(|run| run.practice)
+        };
+        app.add_systems(Startup, load_high_scores)
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                record_high_scores.run_if(single_player),
+            )
+            .add_systems(
+                Update,
+                (sync_best_score, toggle_table).run_if(resource_exists::<HighScores>),
+            );
+    }
+}
+
+fn load_high_scores(mut commands: Commands, settings: Res<GameSettings>) {
+    let Some(high_score_settings) = &settings.high_scores else {
+        return;
+    };
+    commands.insert_resource(HighScores::load_or_default(&high_score_settings.path));
+}
+
+// タイトル画面でモードを変えたとき
+fn sync_best_score(
+    settings: Res<GameSettings>,
+    high_scores: Res<HighScores>,
+    mut best_score: ResMut<BestScore>,
+) {
+    if !settings.is_changed() && !high_scores.is_added() {
+        return;
+    }
+    let best = high_scores.best(&settings.mode);
+    if best != best_score.value() {
+        *best_score = BestScore::new(best);
+    }
+}
+
+type PlayerBoardQuery<'w, 's> =
+    Query<'w, 's, (&'static MainBoard, &'static BoardState, &'static Score), Without<AiController>>;
+
+#[allow(clippy::too_many_arguments)]
+fn record_high_scores(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
+    history: Res<MoveHistory>,
+    clock: Res<GameClock>,
+    query: PlayerBoardQuery,
+    notices: Query<Entity, With<Notice>>,
+    high_scores: Option<ResMut<HighScores>>,
+) {
+    let (Some(mut high_scores), Some(high_score_settings)) = (high_scores, &settings.high_scores)
+    else {
+        return;
+    };
+    for (main_board, board, score) in query.iter() {
+        let entry = HighScore {
+            score: score.value(),
+            max_tile: board.max_tile().map_or(0, |tile| tile.num()),
+            moves: history.board_moves(main_board.index).count(),
+            seconds: clock.elapsed.as_secs(),
+        };
+        if let Some(rank) = high_scores.record(&settings.mode, entry) {
+            show_notice(
+                &mut commands,
+                &asset_server,
+                &settings,
+                &notices,
+                format!("high score #{}", rank + 1),
+            );
+        }
+    }
+    if let Err(e) = high_scores.save(&high_score_settings.path) {
+        eprintln!("failed to save {}: {}", high_score_settings.path, e);
+    }
+}
+
+fn toggle_table(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    high_scores: Res<HighScores>,
+    tables_query: Query<Entity, With<HighScoreTable>>,
+) {
+    if !keyboard.just_pressed(HIGH_SCORE_KEY) {
+        return;
+    }
+    if tables_query.is_empty() {
+        create_high_score_table(&mut commands, &asset_server, &settings, &high_scores);
+        return;
+    }
+    for entity in tables_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
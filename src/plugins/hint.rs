@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+
+use crate::ai::Agent;
+use crate::bundle::notice::show_notice;
+use crate::components::{
+    ai_controller::AiController, board_state::BoardState, board_status::BoardStatus,
+    lockstep::Lockstep, main_board::MainBoard, notice::Notice,
+};
+use crate::resources::game_settings::GameSettings;
+use crate::states::game_state::GameState;
+
+pub const HINT_KEY: KeyCode = KeyCode::KeyH;
+
+// UiSettings::hint の Agent が選ぶ方向を、キーを押したときにお知らせに出す
+pub struct HintPlugin;
+
+impl Plugin for HintPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, show_hint.run_if(in_state(GameState::Input)));
+    }
+}
+
+type HintBoardQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static MainBoard,
+        &'static BoardState,
+        &'static BoardStatus,
+    ),
+    (Without<AiController>, Without<Lockstep>),
+>;
+
+fn show_hint(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    query: HintBoardQuery,
+    notices_query: Query<Entity, With<Notice>>,
+    mut agent: Local<Option<Box<dyn Agent>>>,
+) {
+    // 設定が変われば Tile の出現も変わりうるので作り直す
+    if settings.is_changed() {
+        *agent = None;
+    }
+    if !keyboard.just_pressed(HINT_KEY) {
+        return;
+    }
+    let Some(kind) = settings.ui.hint else {
+        return;
+    };
+    let agent = agent.get_or_insert_with(|| kind.build(&settings, 0));
+    let mut hints: Vec<(usize, String)> = query
+        .iter()
+        .filter(|(_, _, status)| **status == BoardStatus::Input)
+        .map(|(main_board, board_state, _)| {
+            let direction = agent.choose(board_state);
+            return (
+                main_board.index,
+                format!("P{}: {:?}", main_board.index + 1, direction),
+            );
+        })
+        .collect();
+    if hints.is_empty() {
+        return;
+    }
+    hints.sort();
+    let text = hints.into_iter().map(|(_, hint)| hint).collect::<Vec<_>>();
+    show_notice(
+        &mut commands,
+        &asset_server,
+        &settings,
+        &notices_query,
+        format!("hint {}", text.join(" ")),
+    );
+}
@@ -0,0 +1,284 @@
+use bevy::{prelude::*, utils::HashSet};
+use serde_json::{json, Value};
+
+use crate::components::{
+    board_state::BoardState, board_status::BoardStatus, lockstep::Lockstep, main_board::MainBoard,
+    score::Score,
+};
+use crate::constants::layout::{MAX_GRID_SIZE, MIN_GRID_SIZE};
+use crate::network::http::{HttpRequest, HttpResponse, HttpServer};
+use crate::plugins::{input::PlayerInputEvent, new_game::NewGameEvent};
+use crate::resources::{game_settings::GameSettings, move_history::MoveHistory};
+use crate::states::game_state::GameState;
+use crate::structs::direction::Direction;
+
+// GameSettings::http_api が Some のときだけ Game2048Plugin が追加する
+//   GET  /state               全ての盤面と GameState
+//   GET  /board?board=0       盤面. 上の行から並べた Tile の数で、0 は空きマス、-1 は邪魔な Tile
+//   GET  /score?board=0
+//   GET  /history?board=0     新しいゲームを始めてからの手. board を省くと全ての盤面
+//   POST /move                {"direction": "U", "board": 0}. 通信対戦の盤面は 409
+//   POST /new-game            {"seed": 42, "width": 4, "height": 4, "tiles_per_turn": 1}
+//                             tiles_per_turn が 0 なら 400
+pub struct HttpApiPlugin;
+
+impl Plugin for HttpApiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, start_http_api).add_systems(
+            Update,
+            handle_http_requests.run_if(resource_exists::<HttpApi>),
+        );
+    }
+}
+
+#[derive(Resource)]
+pub struct HttpApi(pub HttpServer);
+
+fn start_http_api(mut commands: Commands, settings: Res<GameSettings>) {
+    let Some(api) = settings.http_api else {
+        return;
+    };
+    match HttpServer::bind_loopback(api.port) {
+        Ok(server) => {
+            println!("HTTP API: http://{}", server.addr());
+            commands.insert_resource(HttpApi(server));
+        }
+        Err(e) => eprintln!("failed to start the HTTP API: {}", e),
+    }
+}
+
+type ApiBoardQuery<'w, 's, 'a> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'a MainBoard,
+        &'a BoardState,
+        &'a Score,
+        &'a BoardStatus,
+    ),
+>;
+
+#[allow(clippy::too_many_arguments)]
+pub fn handle_http_requests(
+    api: Res<HttpApi>,
+    settings: Res<GameSettings>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    query: ApiBoardQuery,
+    lockstep_query: Query<(), With<Lockstep>>,
+    history: Res<MoveHistory>,
+    mut input_evw: EventWriter<PlayerInputEvent>,
+    mut new_game_evw: EventWriter<NewGameEvent>,
+) {
+    // 同じフレームで同じ盤面を 2 回動かさない
+    let mut moved: HashSet<Entity> = HashSet::new();
+    while let Some(pending) = api.0.try_recv() {
+        let request = &pending.request;
+        let response = match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/state") => {
+                let boards: Vec<Value> = sorted_boards(&query)
+                    .into_iter()
+                    .map(|(_, main_board, board, score, status)| {
+                        return json!({
+                            "index": main_board.index,
+                            "status": status_name(*status),
+                            "score": score.value(),
+                            "turn": history.board_moves(main_board.index).count(),
+                            "legal": board.legal_directions().map(|d| d.letter()).collect::<String>(),
+                            "board": board.rows_top_down(),
+                        });
+                    })
+                    .collect();
+                HttpResponse::ok(json!({
+                    "state": format!("{:?}", state.get()),
+                    "boards": boards,
+                }))
+            }
+            ("GET", "/board") => match find_board(&query, board_index(request, &Value::Null)) {
+                Some((_, main_board, board, _, _)) => HttpResponse::ok(json!({
+                    "index": main_board.index,
+                    "width": board.grid().width(),
+                    "height": board.grid().height(),
+                    "board": board.rows_top_down(),
+                })),
+                None => HttpResponse::error(404, "no such board"),
+            },
+            ("GET", "/score") => match find_board(&query, board_index(request, &Value::Null)) {
+                Some((_, main_board, _, score, _)) => HttpResponse::ok(json!({
+                    "index": main_board.index,
+                    "score": score.value(),
+                })),
+                None => HttpResponse::error(404, "no such board"),
+            },
+            ("GET", "/history") => {
+                let board: Option<usize> = request.query.get("board").and_then(|b| b.parse().ok());
+                let moves: Vec<Value> = history
+                    .moves
+                    .iter()
+                    .filter(|record| board.is_none_or(|board| record.board == board))
+                    .map(|record| {
+                        return json!({
+                            "board": record.board,
+                            "turn": record.turn,
+                            "direction": record.direction.letter().to_string(),
+                            "score": record.score,
+                        });
+                    })
+                    .collect();
+                HttpResponse::ok(json!({ "moves": moves }))
+            }
+            ("POST", "/move") => {
+                let result = post_move(request, state.get(), &query, &lockstep_query, &mut moved);
+                match result {
+                    Ok(ev) => {
+                        input_evw.send(ev);
+                        next_state.set(GameState::Calculate);
+                        HttpResponse::ok(json!({ "accepted": true }))
+                    }
+                    Err(response) => response,
+                }
+            }
+            ("POST", "/new-game") => match new_game(request, &settings) {
+                Ok(ev) => {
+                    new_game_evw.send(ev);
+                    HttpResponse {
+                        status: 202,
+                        body: json!({ "accepted": true }),
+                    }
+                }
+                Err(response) => response,
+            },
+            (_, "/state" | "/board" | "/score" | "/history" | "/move" | "/new-game") => {
+                HttpResponse::error(405, "method not allowed")
+            }
+            _ => HttpResponse::error(404, "not found"),
+        };
+        pending.respond(response);
+    }
+}
+
+fn sorted_boards<'a>(
+    query: &'a ApiBoardQuery,
+) -> Vec<(
+    Entity,
+    &'a MainBoard,
+    &'a BoardState,
+    &'a Score,
+    &'a BoardStatus,
+)> {
+    let mut boards: Vec<_> = query.iter().collect();
+    boards.sort_by_key(|(_, main_board, _, _, _)| main_board.index);
+    return boards;
+}
+
+fn find_board<'a>(
+    query: &'a ApiBoardQuery,
+    index: usize,
+) -> Option<(
+    Entity,
+    &'a MainBoard,
+    &'a BoardState,
+    &'a Score,
+    &'a BoardStatus,
+)> {
+    return query
+        .iter()
+        .find(|(_, main_board, _, _, _)| main_board.index == index);
+}
+
+// ?board= か JSON の "board". なければ 0
+fn board_index(request: &HttpRequest, body: &Value) -> usize {
+    if let Some(index) = body.get("board").and_then(Value::as_u64) {
+        return index as usize;
+    }
+    return request
+        .query
+        .get("board")
+        .and_then(|b| b.parse().ok())
+        .unwrap_or(0);
+}
+
+fn status_name(status: BoardStatus) -> &'static str {
+    match status {
+        BoardStatus::Spawn => return "spawn",
+        BoardStatus::Input => return "input",
+        BoardStatus::GameOver => return "game_over",
+    }
+}
+
+// "U" の他に "up" のような名前も受け付ける
+fn parse_direction(name: &str) -> Option<Direction> {
+    match name.to_ascii_lowercase().as_str() {
+        "u" | "up" => return Some(Direction::Up),
+        "d" | "down" => return Some(Direction::Down),
+        "l" | "left" => return Some(Direction::Left),
+        "r" | "right" => return Some(Direction::Right),
+        _ => return None,
+    }
+}
+
+fn post_move(
+    request: &HttpRequest,
+    state: &GameState,
+    query: &ApiBoardQuery,
+    lockstep_query: &Query<(), With<Lockstep>>,
+    moved: &mut HashSet<Entity>,
+) -> Result<PlayerInputEvent, HttpResponse> {
+    let body = request.json()?;
+    let direction = body
+        .get("direction")
+        .and_then(Value::as_str)
+        .and_then(parse_direction)
+        .ok_or_else(|| HttpResponse::error(400, "direction must be one of U, D, L, R"))?;
+    let Some((board, _, _, _, status)) = find_board(query, board_index(request, &body)) else {
+        return Err(HttpResponse::error(404, "no such board"));
+    };
+    // 通信対戦の盤面は相手と同時に動かすので、ここからは動かさない
+    if lockstep_query.contains(board) {
+        return Err(HttpResponse::error(
+            409,
+            "the board is played over the network",
+        ));
+    }
+    if *state != GameState::Input || *status != BoardStatus::Input || !moved.insert(board) {
+        return Err(HttpResponse::error(
+            409,
+            "the board is not waiting for a move",
+        ));
+    }
+    return Ok(PlayerInputEvent { board, direction });
+}
+
+fn new_game(request: &HttpRequest, settings: &GameSettings) -> Result<NewGameEvent, HttpResponse> {
+    let body = request.json()?;
+    let field = |name: &str| -> Result<Option<u64>, HttpResponse> {
+        match body.get(name) {
+            None | Some(Value::Null) => return Ok(None),
+            Some(value) => {
+                return value
+                    .as_u64()
+                    .map(Some)
+                    .ok_or_else(|| HttpResponse::error(400, &format!("{} must be a number", name)))
+            }
+        }
+    };
+    let mut new_settings = settings.clone();
+    if let Some(width) = field("width")? {
+        new_settings.board_size.width = (width as usize).clamp(MIN_GRID_SIZE, MAX_GRID_SIZE);
+    }
+    if let Some(height) = field("height")? {
+        new_settings.board_size.height = (height as usize).clamp(MIN_GRID_SIZE, MAX_GRID_SIZE);
+    }
+    if let Some(tiles_per_turn) = field("tiles_per_turn")? {
+        new_settings.spawn.tiles_per_turn = tiles_per_turn as usize;
+    }
+    new_settings
+        .spawn
+        .validate()
+        .map_err(|e| HttpResponse::error(400, &e))?;
+    return Ok(NewGameEvent {
+        settings: Some(new_settings),
+        seed: field("seed")?,
+    });
+}
@@ -1,6 +1,12 @@
 use bevy::prelude::*;
 
+use crate::components::{
+    ai_controller::AiController, board_state::BoardState, board_status::BoardStatus,
+    lockstep::Lockstep, main_board::MainBoard,
+};
+use crate::resources::{coach::Coach, game_settings::GameSettings, move_history::MoveHistory};
 use crate::states::game_state::GameState;
+use crate::structs::direction::Direction;
 
 pub struct InputPlugin;
 
@@ -8,35 +14,76 @@ impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<PlayerInputEvent>().add_systems(
             Update,
-            input_from_keyboard.run_if(in_state(GameState::Input)),
+            (input_from_keyboard, input_from_ai).run_if(in_state(GameState::Input)),
         );
     }
 }
 
-#[derive(Event, PartialEq, Eq)]
-pub enum PlayerInputEvent {
-    Left,
-    Right,
-    Up,
-    Down,
+// board を direction の向きに動かす
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlayerInputEvent {
+    pub board: Entity,
+    pub direction: Direction,
 }
 
+// Coach があれば、危ない手は確認してから動かす
 pub fn input_from_keyboard(
     keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    query: Query<(Entity, &MainBoard, &BoardState, &BoardStatus), Without<Lockstep>>,
     mut input_evw: EventWriter<PlayerInputEvent>,
     mut next_state: ResMut<NextState<GameState>>,
+    mut coach: Option<ResMut<Coach>>,
+    history: Res<MoveHistory>,
 ) {
-    if keyboard.just_pressed(KeyCode::ArrowLeft) {
-        input_evw.send(PlayerInputEvent::Left);
+    for (board, main_board, board_state, status) in query.iter() {
+        if *status != BoardStatus::Input {
+            continue;
+        }
+        let Some(board_settings) = settings.boards.get(main_board.index) else {
+            continue;
+        };
+        let pressed = board_settings
+            .input
+            .key_bindings
+            .iter()
+            .find_map(|bindings| bindings.just_pressed(&keyboard));
+        let Some(direction) = pressed else {
+            continue;
+        };
+        if let (Some(coach), Some(coach_settings)) = (coach.as_mut(), settings.coach.as_ref()) {
+            let turn = history.board_moves(main_board.index).count();
+            if !coach.review(
+                coach_settings,
+                main_board.index,
+                turn,
+                board_state,
+                direction,
+            ) {
+                continue;
+            }
+        }
+        input_evw.send(PlayerInputEvent { board, direction });
         next_state.set(GameState::Calculate);
-    } else if keyboard.just_pressed(KeyCode::ArrowRight) {
-        input_evw.send(PlayerInputEvent::Right);
-        next_state.set(GameState::Calculate);
-    } else if keyboard.just_pressed(KeyCode::ArrowUp) {
-        input_evw.send(PlayerInputEvent::Up);
-        next_state.set(GameState::Calculate);
-    } else if keyboard.just_pressed(KeyCode::ArrowDown) {
-        input_evw.send(PlayerInputEvent::Down);
+    }
+}
+
+// AiController を持つ盤面は、timer ごとに Agent の選んだ方向に動かす
+pub fn input_from_ai(
+    time: Res<Time>,
+    mut query: Query<(Entity, &BoardState, &BoardStatus, &mut AiController), Without<Lockstep>>,
+    mut input_evw: EventWriter<PlayerInputEvent>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (board, board_state, status, mut ai) in query.iter_mut() {
+        if *status != BoardStatus::Input {
+            continue;
+        }
+        if !ai.timer.tick(time.delta()).finished() {
+            continue;
+        }
+        let direction = ai.agent.choose(board_state);
+        input_evw.send(PlayerInputEvent { board, direction });
         next_state.set(GameState::Calculate);
     }
 }
@@ -1,10 +1,11 @@
-use bevy::{ecs::query::QueryEntityError, prelude::*};
+use bevy::{ecs::query::QueryEntityError, prelude::*, utils::HashMap};
 
 use crate::{
-    components::{position::Position, score_text::ScoreText, tile::Tile},
+    bundle::tile::sync_tiles,
+    components::{main_board::MainBoard, position::Position, tile::Tile},
     error::handle_query_entity_errors,
-    plugins::calculate::TileMovementEvent,
-    resources::score::Score,
+    plugins::calculate::{TileMovement, TileMovementEvent},
+    resources::game_settings::GameSettings,
     states::game_state::GameState,
 };
 
@@ -15,8 +16,8 @@ impl Plugin for MovementPlugin {
             OnEnter(GameState::Movement),
             (
                 move_tiles.pipe(handle_query_entity_errors),
+                sync_tiles,
                 update_tiles.pipe(handle_query_entity_errors),
-                update_score.pipe(handle_query_entity_errors),
                 GameState::Spawn.set_next(),
             )
                 .chain(),
@@ -24,56 +25,75 @@ impl Plugin for MovementPlugin {
     }
 }
 
+// TileMovementEvent に合わせて、各 MainBoard の子の Tile の Entity を移動させる
 pub fn move_tiles(
     mut tile_move_evr: EventReader<TileMovementEvent>,
-    mut query: Query<(&mut Position, &mut Tile)>,
+    query_p: Query<(Entity, &Children), With<MainBoard>>,
+    mut query_c: Query<(&mut Position, &mut Tile)>,
     mut commands: Commands,
-    mut score: ResMut<Score>,
 ) -> Result<(), QueryEntityError> {
-    dbg!("System: move_tiles");
+    let mut entities: HashMap<(Entity, Position), Entity> = HashMap::new();
+    for (board, children) in query_p.iter() {
+        for child in children.iter() {
+            if let Ok((pos, _)) = query_c.get(*child) {
+                entities.insert((board, *pos), *child);
+            }
+        }
+    }
     for ev in tile_move_evr.read() {
-        match ev {
-            &TileMovementEvent::OneStep(e, turn) => {
-                let (mut pos, _) = query.get_mut(e)?;
-                pos.shift(turn.downward_unit());
+        let entity = |pos: Position| entities[&(ev.board, pos)];
+        match ev.movement {
+            TileMovement::Slide { from, to, .. } => {
+                if from != to {
+                    let (mut pos, _) = query_c.get_mut(entity(from))?;
+                    *pos = to;
+                }
             }
-            &TileMovementEvent::Merge(e0, e1, _) => {
-                let (_, mut tile0) = query.get_mut(e0)?;
-                tile0.double();
-                score.add(tile0.num());
-                commands.entity(e1).despawn_recursive();
+            TileMovement::Merge {
+                from,
+                partner_from,
+                to,
+                rank,
+            } => {
+                let (mut pos, mut tile) = query_c.get_mut(entity(from))?;
+                *pos = to;
+                *tile = rank;
+                commands.entity(entity(partner_from)).despawn_recursive();
             }
         }
     }
     return Ok(());
 }
 
+type ChangedTileQuery<'w, 's, 'a> = Query<
+    'w,
+    's,
+    (
+        &'a Tile,
+        &'a Position,
+        &'a mut Transform,
+        &'a mut Sprite,
+        &'a Children,
+    ),
+    Or<(Changed<Tile>, Changed<Position>)>,
+>;
+type TileTextQuery<'w, 's, 'a> =
+    Query<'w, 's, (&'a mut Transform, &'a mut Text), (With<Parent>, Without<Children>)>;
+
 pub fn update_tiles(
-    mut query_p: Query<
-        (&Tile, &Position, &mut Transform, &mut Sprite, &Children),
-        Or<(Changed<Tile>, Changed<Position>)>,
-    >,
-    mut query_c: Query<(&mut Transform, &mut Text), (With<Parent>, Without<Children>)>,
+    mut query_p: ChangedTileQuery,
+    mut query_c: TileTextQuery,
+    settings: Res<GameSettings>,
 ) -> Result<(), QueryEntityError> {
-    dbg!("System: update_tiles");
     for (tile, pos, mut trans_p, mut sprite, children) in query_p.iter_mut() {
-        *trans_p = (*pos).into();
+        *trans_p = settings.board_size.tile_transform(*pos, 10.0);
         for child in children.iter() {
             let (mut trans_c, mut text) = query_c.get_mut(*child)?;
             text.sections[0].value = tile.to_string();
             trans_c.scale.x = 1.0 / tile.to_string().len() as f32;
             // text.sections[0].style.font_size = TILE_FONT_SIZE / tile.to_string().len() as f32;
         }
-        sprite.color = (*tile).into();
+        sprite.color = settings.theme.tile_color(*tile);
     }
     return Ok(());
 }
-
-pub fn update_score(
-    mut query: Query<&mut Text, With<ScoreText>>,
-    score: Res<Score>,
-) -> Result<(), QueryEntityError> {
-    let mut text = query.single_mut();
-    text.sections[0].value = score.to_string();
-    return Ok(());
-}
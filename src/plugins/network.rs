@@ -0,0 +1,356 @@
+use std::io;
+use std::net::ToSocketAddrs;
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::bundle::notice::show_notice;
+use crate::components::{
+    board_state::BoardState, board_status::BoardStatus, lockstep::Lockstep, main_board::MainBoard,
+    notice::Notice, position::Position, score::Score,
+};
+use crate::network::{
+    connection::{Connection, NetEvent},
+    protocol::Message,
+};
+use crate::plugins::{input::PlayerInputEvent, spawn::BoardGameOverEvent};
+use crate::resources::game_settings::{GameSettings, InputSettings, KeyBindings};
+use crate::states::game_state::GameState;
+use crate::structs::direction::Direction;
+
+// NetSession を insert した App で、2 つの盤面を通信相手と同時に動かす
+pub struct NetworkPlugin;
+
+impl Plugin for NetworkPlugin {
+    fn build(&self, app: &mut App) {
+        let in_session = resource_exists::<NetSession>;
+        app.add_systems(PostStartup, attach_lockstep.run_if(in_session))
+            .add_systems(Update, receive_messages.run_if(in_session))
+            .add_systems(
+                Update,
+                lockstep_input
+                    .after(receive_messages)
+                    .run_if(in_session.and_then(in_state(GameState::Input))),
+            )
+            .add_systems(OnEnter(GameState::Input), send_hash.run_if(in_session));
+    }
+}
+
+// 通信対戦の状態
+// 自分の盤面が MainBoard::index = LOCAL, 相手の盤面が REMOTE
+#[derive(Resource)]
+pub struct NetSession {
+    connection: Connection,
+    is_host: bool,
+    local_seed: u64,
+    remote_seed: u64,
+    // 何ターンごとに盤面の hash を送るか
+    hash_interval: usize,
+    // 次に進めるターン
+    turn: usize,
+    local_input: Option<Direction>,
+    remote_inputs: HashMap<usize, Direction>,
+    local_hashes: HashMap<usize, u64>,
+    remote_hashes: HashMap<usize, u64>,
+    connected: bool,
+    desynced: bool,
+}
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+impl NetSession {
+    pub const LOCAL: usize = 0;
+    pub const REMOTE: usize = 1;
+
+    // addr で相手を待つ
+    pub fn host(addr: impl ToSocketAddrs, seed: u64) -> io::Result<Self> {
+        return Self::new(Connection::host(addr)?, true, seed);
+    }
+    pub fn join(addr: impl ToSocketAddrs, seed: u64) -> io::Result<Self> {
+        return Self::new(Connection::join(addr)?, false, seed);
+    }
+    fn new(connection: Connection, is_host: bool, seed: u64) -> io::Result<Self> {
+        let remote_seed = connection.handshake(seed, HANDSHAKE_TIMEOUT)?;
+        return Ok(Self {
+            connection,
+            is_host,
+            local_seed: seed,
+            remote_seed,
+            hash_interval: 10,
+            turn: 0,
+            local_input: None,
+            remote_inputs: HashMap::new(),
+            local_hashes: HashMap::new(),
+            remote_hashes: HashMap::new(),
+            connected: true,
+            desynced: false,
+        });
+    }
+    pub fn with_hash_interval(mut self, hash_interval: usize) -> Self {
+        self.hash_interval = hash_interval.max(1);
+        return self;
+    }
+    pub fn is_host(&self) -> bool {
+        return self.is_host;
+    }
+    pub fn turn(&self) -> usize {
+        return self.turn;
+    }
+    pub fn is_connected(&self) -> bool {
+        return self.connected;
+    }
+    pub fn is_desynced(&self) -> bool {
+        return self.desynced;
+    }
+    // base の対戦設定に、両方の盤面の seed と操作を入れたもの
+    // 相手の盤面はキーボードからは動かさない
+    pub fn game_settings(&self, base: &GameSettings) -> GameSettings {
+        let mut settings = base.clone();
+        settings.boards = vec![Default::default(), Default::default()];
+        settings.boards[Self::LOCAL].seed = Some(self.local_seed);
+        settings.boards[Self::LOCAL].input = InputSettings {
+            key_bindings: vec![KeyBindings::ARROWS, KeyBindings::WASD],
+        };
+        settings.boards[Self::REMOTE].seed = Some(self.remote_seed);
+        settings.boards[Self::REMOTE].input = InputSettings {
+            key_bindings: Vec::new(),
+        };
+        settings.ui.title_menu = false;
+        settings.ui.exit_on_game_over = false;
+        return settings;
+    }
+    // 送れなかった場合は受信スレッドが Disconnected を届ける
+    fn send(&self, message: Message) {
+        if let Err(e) = self.connection.send(message) {
+            eprintln!("failed to send {}: {}", message, e);
+        }
+    }
+}
+
+fn attach_lockstep(mut commands: Commands, query: Query<Entity, With<MainBoard>>) {
+    for board in query.iter() {
+        commands.entity(board).insert(Lockstep);
+    }
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+// 両方の盤面の hash. 順番はホストの盤面が先
+// Rust の版や 32/64 bit で変わらないよう、FNV-1a (64 bit) で次の byte 列を畳む
+// 盤面ごとに、上の行から左から順に各マスを 1 byte (空きマスは 0, Tile は rank + 1)
+// 続けて得点を u64 の little endian で 8 byte
+fn boards_hash<'a>(boards: impl Iterator<Item = (&'a BoardState, &'a Score)>) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    let mut fold = |byte: u8| {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    };
+    for (board, score) in boards {
+        let grid = board.grid();
+        for y in (0..grid.height()).rev() {
+            for x in 0..grid.width() {
+                fold(board.get(Position::new(x, y)).map_or(0, |tile| tile.0 + 1));
+            }
+        }
+        for byte in (score.value() as u64).to_le_bytes() {
+            fold(byte);
+        }
+    }
+    return hash;
+}
+
+type LockstepBoardQuery<'w, 's, 'a> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'a MainBoard,
+        &'a BoardState,
+        &'a Score,
+        &'a mut BoardStatus,
+    ),
+    With<Lockstep>,
+>;
+
+// 受信したものを NetSession に溜め、切断と hash の食い違いを処理する
+#[allow(clippy::too_many_arguments)]
+pub fn receive_messages(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
+    mut session: ResMut<NetSession>,
+    mut query: LockstepBoardQuery,
+    notices: Query<Entity, With<Notice>>,
+    mut game_over_evw: EventWriter<BoardGameOverEvent>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !session.connected || session.desynced {
+        return;
+    }
+    while let Some(event) = session.connection.try_recv() {
+        match event {
+            NetEvent::Message(Message::Input { turn, direction }) => {
+                session.remote_inputs.insert(turn, direction);
+            }
+            NetEvent::Message(Message::Hash { turn, hash }) => {
+                session.remote_hashes.insert(turn, hash);
+            }
+            NetEvent::Message(Message::Hello { .. }) => {}
+            NetEvent::Message(Message::Bye) | NetEvent::Disconnected => {
+                session.connected = false;
+                break;
+            }
+        }
+    }
+    if !session.connected {
+        // 相手の盤面はそこで GameOver とし、自分の盤面だけで続ける
+        show_notice(
+            &mut commands,
+            &asset_server,
+            &settings,
+            &notices,
+            "opponent disconnected",
+        );
+        for (board, main_board, _, _, mut status) in query.iter_mut() {
+            if main_board.index == NetSession::REMOTE && *status != BoardStatus::GameOver {
+                *status = BoardStatus::GameOver;
+                game_over_evw.send(BoardGameOverEvent { board });
+            }
+        }
+        if query
+            .iter()
+            .all(|(_, _, _, _, status)| *status == BoardStatus::GameOver)
+        {
+            next_state.set(GameState::GameOver);
+        }
+        return;
+    }
+    let mut desynced_turn = None;
+    for (turn, hash) in session.remote_hashes.iter() {
+        if let Some(local) = session.local_hashes.get(turn) {
+            if local != hash {
+                desynced_turn = Some(*turn);
+            }
+        }
+    }
+    let session = session.as_mut();
+    session
+        .remote_hashes
+        .retain(|turn, _| !session.local_hashes.contains_key(turn));
+    if let Some(turn) = desynced_turn {
+        // どちらの盤面も信用できないので、その時点で対戦を打ち切る
+        session.desynced = true;
+        show_notice(
+            &mut commands,
+            &asset_server,
+            &settings,
+            &notices,
+            format!("desync at turn {}", turn),
+        );
+        for (board, _, _, _, mut status) in query.iter_mut() {
+            if *status != BoardStatus::GameOver {
+                *status = BoardStatus::GameOver;
+                game_over_evw.send(BoardGameOverEvent { board });
+            }
+        }
+        next_state.set(GameState::GameOver);
+    }
+}
+
+// 自分の入力を送り、両方の盤面の入力が揃ったら同時に PlayerInputEvent を発行する
+pub fn lockstep_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    mut session: ResMut<NetSession>,
+    query: Query<(Entity, &MainBoard, &BoardStatus), With<Lockstep>>,
+    mut input_evw: EventWriter<PlayerInputEvent>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if session.desynced {
+        return;
+    }
+    let mut local = None;
+    let mut remote = None;
+    for (board, main_board, status) in query.iter() {
+        if *status != BoardStatus::Input {
+            continue;
+        }
+        match main_board.index {
+            NetSession::LOCAL => local = Some(board),
+            NetSession::REMOTE => remote = Some(board),
+            _ => {}
+        }
+    }
+    if local.is_some() && session.local_input.is_none() {
+        let pressed = settings.boards[NetSession::LOCAL]
+            .input
+            .key_bindings
+            .iter()
+            .find_map(|bindings| bindings.just_pressed(&keyboard));
+        if let Some(direction) = pressed {
+            session.send(Message::Input {
+                turn: session.turn,
+                direction,
+            });
+            session.local_input = Some(direction);
+        }
+    }
+    let local_ready = local.is_none() || session.local_input.is_some();
+    let remote_ready = remote.is_none() || session.remote_inputs.contains_key(&session.turn);
+    if (local.is_none() && remote.is_none()) || !local_ready || !remote_ready {
+        return;
+    }
+    if let (Some(board), Some(direction)) = (local, session.local_input.take()) {
+        input_evw.send(PlayerInputEvent { board, direction });
+    }
+    let turn = session.turn;
+    if let (Some(board), Some(direction)) = (remote, session.remote_inputs.remove(&turn)) {
+        input_evw.send(PlayerInputEvent { board, direction });
+    }
+    session.turn += 1;
+    next_state.set(GameState::Calculate);
+}
+
+// hash_interval ターンごとに、そのターンが始まる時点の盤面の hash を送る
+pub fn send_hash(mut session: ResMut<NetSession>, query: LockstepBoardQuery) {
+    let turn = session.turn;
+    if !session.connected || !turn.is_multiple_of(session.hash_interval) {
+        return;
+    }
+    if session.local_hashes.contains_key(&turn) {
+        return;
+    }
+    let mut boards: Vec<(&MainBoard, &BoardState, &Score)> = query
+        .iter()
+        .map(|(_, main_board, board, score, _)| (main_board, board, score))
+        .collect();
+    let host = match session.is_host {
+        true => NetSession::LOCAL,
+        false => NetSession::REMOTE,
+    };
+    boards.sort_by_key(|(main_board, _, _)| main_board.index != host);
+    let hash = boards_hash(boards.into_iter().map(|(_, board, score)| (board, score)));
+    session.local_hashes.insert(turn, hash);
+    session.send(Message::Hash { turn, hash });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::tile::Tile;
+
+    #[test]
+    fn boards_hash_is_pinned() {
+        let mut board = BoardState::new(4, 4);
+        board.set(Position::new(0, 0), Some(Tile(1)));
+        board.set(Position::new(1, 0), Some(Tile(2)));
+        let mut score = Score::default();
+        score.add(4);
+        assert_eq!(
+            boards_hash([(&board, &score)].into_iter()),
+            0x18FD_C534_02BF_FEF6
+        );
+        assert_eq!(boards_hash(std::iter::empty()), FNV_OFFSET_BASIS);
+    }
+}
@@ -0,0 +1,102 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::bundle::{main_board::create_main_board, score_board::create_score_board};
+use crate::components::{
+    analysis_panel::AnalysisPanel, main_board::MainBoard, notice::Notice, score_board::ScoreBoard,
+    versus_result::VersusResult,
+};
+use crate::resources::{
+    coach::Coach, game_analysis::GameAnalysis, game_settings::GameSettings,
+    move_history::MoveHistory,
+};
+use crate::states::game_state::GameState;
+
+pub struct NewGamePlugin;
+
+impl Plugin for NewGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<NewGameEvent>()
+            .add_systems(Update, request_new_game)
+            .add_systems(
+                OnEnter(GameState::NewGame),
+                (
+                    clear_game,
+                    create_main_board,
+                    create_score_board
+                        .run_if(|settings: Res<GameSettings>| settings.ui.score_board),
+                    resize_window,
+                    GameState::Spawn.set_next(),
+                )
+                    .chain(),
+            );
+    }
+}
+
+// 盤面を全て作り直して、最初から始める
+#[derive(Event, Clone, Debug, Default)]
+pub struct NewGameEvent {
+    // None なら今の GameSettings のまま
+    pub settings: Option<GameSettings>,
+    // 全ての盤面の seed をこれにする
+    pub seed: Option<u64>,
+}
+
+pub fn request_new_game(
+    mut new_game_evr: EventReader<NewGameEvent>,
+    mut settings: ResMut<GameSettings>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(ev) = new_game_evr.read().last() else {
+        return;
+    };
+    if let Some(new_settings) = &ev.settings {
+        *settings = new_settings.clone();
+    }
+    if let Some(seed) = ev.seed {
+        for board in settings.boards.iter_mut() {
+            board.seed = Some(seed);
+        }
+    }
+    next_state.set(GameState::NewGame);
+}
+
+type GameEntityQuery<'w, 's> = Query<
+    'w,
+    's,
+    Entity,
+    Or<(
+        With<MainBoard>,
+        With<ScoreBoard>,
+        With<VersusResult>,
+        With<Notice>,
+        With<AnalysisPanel>,
+    )>,
+>;
+
+fn clear_game(
+    mut commands: Commands,
+    query: GameEntityQuery,
+    mut history: ResMut<MoveHistory>,
+    coach: Option<ResMut<Coach>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    history.clear();
+    // 前のゲームの手の確認は取り消す
+    if let Some(mut coach) = coach {
+        coach.pending.clear();
+    }
+    commands.remove_resource::<GameAnalysis>();
+}
+
+// 盤面の大きさや数が変わったときのため
+fn resize_window(
+    settings: Res<GameSettings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let size = settings.window_size();
+    for mut window in windows.iter_mut() {
+        window.resolution.set(size.x, size.y);
+    }
+}
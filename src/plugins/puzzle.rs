@@ -0,0 +1,270 @@
+use bevy::prelude::*;
+use bevy_prng::WyRand;
+use bevy_rand::component::EntropyComponent;
+use rand_core::SeedableRng;
+
+use crate::bundle::main_board::create_main_board;
+use crate::bundle::notice::show_notice;
+use crate::components::{
+    ai_controller::AiController, board_state::BoardState, board_status::BoardStatus,
+    main_board::MainBoard, notice::Notice, puzzle_spawns::PuzzleSpawnQueue, score::Score,
+};
+use crate::plugins::{
+    new_game::NewGameEvent,
+    spawn::{create_random_tile, BoardGameOverEvent},
+};
+use crate::resources::{
+    game_settings::{BoardSize, GameSettings},
+    move_history::MoveHistory,
+    puzzle::{Puzzle, PuzzleOutcome, PuzzleProgress, PuzzleSpawns, Puzzles},
+};
+use crate::states::game_state::GameState;
+use crate::structs::game_mode::GameMode;
+
+// 解いた後に次のパズルへ
+pub const NEXT_PUZZLE_KEY: KeyCode = KeyCode::Enter;
+// 同じパズルをやり直す. 遊んでいる途中でも使える
+pub const RETRY_PUZZLE_KEY: KeyCode = KeyCode::KeyR;
+
+// GameSettings::puzzles があるとき、GameMode::Puzzle で PuzzleSettings::dir のパズルを
+// 盤面に置き、毎ターン目的を調べる. 解いたパズルと最少の手数を記録する
+pub struct PuzzlePlugin;
+
+impl Plugin for PuzzlePlugin {
+    fn build(&self, app: &mut App) {
+        let puzzle_mode = |settings: Res<GameSettings>, puzzles: Option<Res<Puzzles>>| {
+            settings.mode == GameMode::Puzzle
+                && settings.versus.is_none()
+                && puzzles.is_some_and(|puzzles| puzzles.current().is_some())
+        };
+        app.add_systems(Startup, load_puzzles)
+            .add_systems(
+                OnEnter(GameState::NewGame),
+                restore_board_size
+                    .before(create_main_board)
+                    .run_if(resource_exists::<Puzzles>),
+            )
+            .add_systems(
+                OnExit(GameState::TitleMenu),
+                start_puzzle.run_if(puzzle_mode),
+            )
+            .add_systems(OnExit(GameState::NewGame), start_puzzle.run_if(puzzle_mode))
+            .add_systems(
+                OnEnter(GameState::Spawn),
+                check_objective
+                    .before(create_random_tile)
+                    .run_if(puzzle_mode),
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                show_failure.run_if(puzzle_mode),
+            )
+            .add_systems(
+                Update,
+                next_puzzle
+                    .run_if(in_state(GameState::Input).or_else(in_state(GameState::GameOver)))
+                    .run_if(puzzle_mode),
+            );
+    }
+}
+
+fn load_puzzles(mut commands: Commands, settings: Res<GameSettings>) {
+    let Some(puzzle_settings) = &settings.puzzles else {
+        return;
+    };
+    let list = Puzzle::load_dir(&puzzle_settings.dir).unwrap_or_else(|e| {
+        eprintln!("failed to load {}: {}", puzzle_settings.dir, e);
+        return Vec::new();
+    });
+    let progress = PuzzleProgress::load_or_default(&puzzle_settings.path);
+    let current = match &puzzle_settings.start {
+        Some(id) => list.iter().position(|puzzle| &puzzle.id == id),
+        None => list
+            .iter()
+            .position(|puzzle| progress.best(&puzzle.id).is_none()),
+    };
+    commands.insert_resource(Puzzles {
+        list,
+        current: current.unwrap_or(0),
+        outcome: PuzzleOutcome::Playing,
+        saved_board_size: None,
+    });
+    commands.insert_resource(progress);
+}
+
+// Puzzle 以外のモードで盤面を作る前に、パズルに合わせる前の大きさに戻す
+fn restore_board_size(mut settings: ResMut<GameSettings>, mut puzzles: ResMut<Puzzles>) {
+    if settings.mode == GameMode::Puzzle {
+        return;
+    }
+    if let Some(size) = puzzles.saved_board_size.take() {
+        settings.board_size = size;
+    }
+}
+
+// 最初の Spawn の前に、パズルの盤面と出現する Tile を決める
+// 盤面の大きさが違えば、大きさを合わせて作り直す
+#[allow(clippy::too_many_arguments)]
+fn start_puzzle(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
+    mut puzzles: ResMut<Puzzles>,
+    progress: Res<PuzzleProgress>,
+    notices: Query<Entity, With<Notice>>,
+    mut query: Query<(Entity, &mut EntropyComponent<WyRand>), With<MainBoard>>,
+    mut new_game_evw: EventWriter<NewGameEvent>,
+) {
+    puzzles.outcome = PuzzleOutcome::Playing;
+    let Some(puzzle) = puzzles.current() else {
+        return;
+    };
+    let (width, height) = puzzle.size();
+    if settings.board_size.width != width || settings.board_size.height != height {
+        for (entity, _) in query.iter() {
+            commands.entity(entity).insert(PuzzleSpawnQueue {
+                initial: Some(Vec::new()),
+                fixed: Some(default()),
+            });
+        }
+        // Puzzle 以外のモードに戻るときのため、最初の大きさを覚えておく
+        if puzzles.saved_board_size.is_none() {
+            puzzles.saved_board_size = Some(settings.board_size);
+        }
+        let mut new_settings = settings.clone();
+        new_settings.board_size = BoardSize { width, height };
+        new_game_evw.send(NewGameEvent {
+            settings: Some(new_settings),
+            seed: None,
+        });
+        return;
+    }
+    // load_dir で確かめてある
+    let initial = puzzle.initial_tiles().unwrap_or_default();
+    let fixed = puzzle.fixed_spawns().unwrap_or_default();
+    for (entity, mut rng) in query.iter_mut() {
+        if let PuzzleSpawns::Seeded { seed } = puzzle.spawns {
+            *rng = EntropyComponent::seed_from_u64(seed);
+        }
+        commands.entity(entity).insert(PuzzleSpawnQueue {
+            initial: Some(initial.clone()),
+            fixed: fixed.clone().map(Into::into),
+        });
+    }
+    let best = progress
+        .best(&puzzle.id)
+        .map_or(String::new(), |moves| format!(" (best {} moves)", moves));
+    show_notice(
+        &mut commands,
+        &asset_server,
+        &settings,
+        &notices,
+        format!(
+            "{}: {}{}",
+            puzzle.name,
+            puzzle.objective.description(),
+            best
+        ),
+    );
+}
+
+type PuzzleBoardQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static MainBoard,
+        &'static BoardState,
+        &'static Score,
+        &'static mut BoardStatus,
+    ),
+    Without<AiController>,
+>;
+
+// 動かした後、Tile が出現する前に目的を調べる
+#[allow(clippy::too_many_arguments)]
+fn check_objective(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
+    history: Res<MoveHistory>,
+    mut puzzles: ResMut<Puzzles>,
+    mut progress: ResMut<PuzzleProgress>,
+    notices: Query<Entity, With<Notice>>,
+    mut query: PuzzleBoardQuery,
+    mut game_over_evw: EventWriter<BoardGameOverEvent>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(puzzle) = puzzles.current().cloned() else {
+        return;
+    };
+    for (board_entity, main_board, board, score, mut status) in query.iter_mut() {
+        let moves = history.board_moves(main_board.index).count();
+        if *status != BoardStatus::Spawn || moves == 0 {
+            continue;
+        }
+        let outcome = puzzle.objective.check(board, score.value(), moves);
+        if outcome == PuzzleOutcome::Playing {
+            continue;
+        }
+        puzzles.outcome = outcome;
+        *status = BoardStatus::GameOver;
+        game_over_evw.send(BoardGameOverEvent {
+            board: board_entity,
+        });
+        next_state.set(GameState::GameOver);
+        if outcome != PuzzleOutcome::Solved {
+            continue;
+        }
+        let new_best = progress.record(&puzzle.id, moves);
+        if let Some(puzzle_settings) = &settings.puzzles {
+            if let Err(e) = progress.save(&puzzle_settings.path) {
+                eprintln!("failed to save {}: {}", puzzle_settings.path, e);
+            }
+        }
+        let best = match new_best {
+            true => "new best!".to_string(),
+            false => format!("best {}", progress.best(&puzzle.id).unwrap_or(moves)),
+        };
+        show_notice(
+            &mut commands,
+            &asset_server,
+            &settings,
+            &notices,
+            format!("solved in {} moves, {}. Enter: next", moves, best),
+        );
+    }
+}
+
+// 目的を果たす前に終わった
+fn show_failure(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
+    puzzles: Res<Puzzles>,
+    notices: Query<Entity, With<Notice>>,
+) {
+    if puzzles.outcome == PuzzleOutcome::Solved {
+        return;
+    }
+    show_notice(
+        &mut commands,
+        &asset_server,
+        &settings,
+        &notices,
+        "failed. R: retry",
+    );
+}
+
+fn next_puzzle(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut puzzles: ResMut<Puzzles>,
+    mut new_game_evw: EventWriter<NewGameEvent>,
+) {
+    if keys.just_pressed(NEXT_PUZZLE_KEY) && puzzles.outcome == PuzzleOutcome::Solved {
+        puzzles.current = (puzzles.current + 1) % puzzles.list.len();
+        new_game_evw.send(NewGameEvent::default());
+    } else if keys.just_pressed(RETRY_PUZZLE_KEY) {
+        new_game_evw.send(NewGameEvent::default());
+    }
+}
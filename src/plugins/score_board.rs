@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+
+use crate::bundle::score_board::{animate_score_delta, spawn_score_delta};
+use crate::components::{
+    ai_controller::AiController,
+    lockstep::Lockstep,
+    main_board::MainBoard,
+    score_text::{BestScoreText, GameClockText, MoveCountText, ScoreDelta, ScoreText},
+};
+use crate::plugins::calculate::ScoreEvent;
+use crate::resources::{
+    best_score::BestScore,
+    game_clock::{minutes_seconds, GameClock},
+    game_settings::GameSettings,
+    move_history::MoveHistory,
+};
+use crate::structs::game_mode::GameMode;
+
+// ScoreBoard の表示を ScoreEvent と GameClock に合わせる
+// GameClock は GameModePlugin が進める
+pub struct ScoreBoardPlugin;
+
+impl Plugin for ScoreBoardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                update_score,
+                (update_best_score, update_best_score_text).chain(),
+                update_move_count,
+                update_clock_text,
+                animate_score_deltas,
+            ),
+        );
+    }
+}
+
+// 各 ScoreText に対応する MainBoard の Score を表示し、増えた点を "+N" で出す
+pub fn update_score(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
+    mut score_evr: EventReader<ScoreEvent>,
+    mut query: Query<(&mut Text, &ScoreText, &Parent)>,
+) {
+    for ev in score_evr.read() {
+        for (mut text, score_text, parent) in query.iter_mut() {
+            if score_text.board != ev.board {
+                continue;
+            }
+            text.sections[0].value = ev.score.to_string();
+            if ev.gained > 0 {
+                spawn_score_delta(
+                    &mut commands,
+                    &asset_server,
+                    &settings.theme,
+                    parent.get(),
+                    ev.gained,
+                );
+            }
+        }
+    }
+}
+
+// この端末で人が動かしている盤面. 対戦相手や AI の Score は BestScore に入れない
+type PlayerBoard = (With<MainBoard>, Without<AiController>, Without<Lockstep>);
+
+fn update_best_score(
+    mut score_evr: EventReader<ScoreEvent>,
+    mut best_score: ResMut<BestScore>,
+    query: Query<(), PlayerBoard>,
+) {
+    let scores = score_evr.read().filter(|ev| query.contains(ev.board));
+    let Some(score) = scores.map(|ev| ev.score).max() else {
+        return;
+    };
+    if score > best_score.value() {
+        best_score.update(score);
+    }
+}
+
+// モードを変えたときにも BestScore は変わる
+fn update_best_score_text(
+    best_score: Res<BestScore>,
+    mut query: Query<&mut Text, With<BestScoreText>>,
+) {
+    if !best_score.is_changed() {
+        return;
+    }
+    for mut text in query.iter_mut() {
+        text.sections[1].value = best_score.to_string();
+    }
+}
+
+// LimitedMoves なら残りの手数がわかるように上限も出す
+fn update_move_count(
+    settings: Res<GameSettings>,
+    history: Res<MoveHistory>,
+    boards_query: Query<&MainBoard>,
+    mut query: Query<(&mut Text, &MoveCountText)>,
+) {
+    if !history.is_changed() && !settings.is_changed() {
+        return;
+    }
+    for (mut text, move_count) in query.iter_mut() {
+        let Ok(main_board) = boards_query.get(move_count.board) else {
+            continue;
+        };
+        let moves = history.board_moves(main_board.index).count();
+        text.sections[1].value = match settings.mode.move_limit() {
+            Some(limit) if settings.versus.is_none() => format!("{}/{}", moves, limit),
+            _ => moves.to_string(),
+        };
+    }
+}
+
+// TimeAttack なら残りの時間, Blitz なら今の手の残りの秒数を出す
+fn update_clock_text(
+    settings: Res<GameSettings>,
+    clock: Res<GameClock>,
+    mut query: Query<&mut Text, With<GameClockText>>,
+) {
+    let mode = match settings.versus {
+        Some(_) => GameMode::Classic,
+        None => settings.mode,
+    };
+    let (label, value) = match (mode.time_limit(), mode.move_time_limit()) {
+        (Some(limit), _) => (
+            "left ",
+            minutes_seconds(limit.saturating_sub(clock.elapsed)),
+        ),
+        (_, Some(limit)) => (
+            "move ",
+            format!(
+                "{:.1}",
+                limit.saturating_sub(clock.turn_elapsed).as_secs_f32()
+            ),
+        ),
+        _ => ("time ", clock.to_string()),
+    };
+    for mut text in query.iter_mut() {
+        if text.sections[0].value != label {
+            text.sections[0].value = label.to_string();
+        }
+        if text.sections[1].value != value {
+            text.sections[1].value = value.clone();
+        }
+    }
+}
+
+fn animate_score_deltas(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut ScoreDelta, &mut Style, &mut Text)>,
+) {
+    for (entity, mut delta, mut style, mut text) in query.iter_mut() {
+        if delta.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        animate_score_delta(&mut style, &mut text, delta.timer.fraction());
+    }
+}
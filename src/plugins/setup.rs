@@ -4,8 +4,10 @@ use crate::{
     bundle::{
         main_board::create_main_board, score_board::create_score_board, tile::TileSpawnEvent,
     },
-    constants::color::BACKGROUND_COLOR,
-    resources::score::Score,
+    resources::{
+        best_score::BestScore, game_clock::GameClock, game_settings::GameSettings,
+        move_history::MoveHistory,
+    },
     states::game_state::GameState,
 };
 
@@ -13,23 +15,35 @@ pub struct SetupPlugin;
 
 impl Plugin for SetupPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.insert_resource(ClearColor(BACKGROUND_COLOR))
+        app.init_resource::<GameSettings>()
+            .init_resource::<MoveHistory>()
+            .init_resource::<BestScore>()
+            .init_resource::<GameClock>()
             .init_state::<GameState>()
             .add_event::<TileSpawnEvent>()
             .add_systems(
                 Startup,
                 (
                     setup,
-                    create_main_board,
-                    create_score_board,
-                    GameState::TitleMenu.set_next(),
+                    (
+                        create_main_board,
+                        create_score_board
+                            .run_if(|settings: Res<GameSettings>| settings.ui.score_board),
+                    )
+                        .chain(),
+                    GameState::TitleMenu
+                        .set_next()
+                        .run_if(|settings: Res<GameSettings>| settings.ui.title_menu),
+                    GameState::Spawn
+                        .set_next()
+                        .run_if(|settings: Res<GameSettings>| !settings.ui.title_menu),
                 ),
             )
             .add_systems(Update, bevy::window::close_on_esc);
     }
 }
 
-fn setup(mut commands: Commands) {
+fn setup(mut commands: Commands, settings: Res<GameSettings>) {
     commands.spawn(Camera2dBundle::default());
-    commands.init_resource::<Score>();
+    commands.insert_resource(ClearColor(settings.theme.background));
 }
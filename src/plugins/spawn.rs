@@ -1,46 +1,117 @@
 use bevy::prelude::*;
 use bevy_prng::WyRand;
-use bevy_rand::resource::GlobalEntropy;
+use bevy_rand::component::EntropyComponent;
 use rand_core::RngCore;
-use std::collections::BTreeSet;
 
-use crate::bundle::tile::{spawn_tiles, TileSpawnEvent};
-use crate::components::position::Position;
-use crate::components::tile::Tile;
+use crate::bundle::tile::{spawn_tiles, sync_tiles, TileSpawnEvent};
+use crate::components::{
+    board_state::BoardState, board_status::BoardStatus, garbage_queue::GarbageQueue,
+    position::Position, puzzle_spawns::PuzzleSpawnQueue, tile::Tile,
+};
+use crate::resources::game_settings::GameSettings;
 use crate::states::game_state::GameState;
-use crate::util::position::{board_is_full, get_positions_complement_set};
 
 pub struct SpawnPlugin;
 
 impl Plugin for SpawnPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.add_event::<BoardGameOverEvent>().add_systems(
             OnEnter(GameState::Spawn),
-            (
-                (create_random_tile, spawn_tiles, GameState::Input.set_next())
-                    .chain()
-                    .run_if(not(board_is_full)),
-                GameState::GameOver.set_next().run_if(board_is_full),
-            ),
+            (create_random_tile, spawn_tiles, sync_tiles, next_state).chain(),
         );
     }
 }
 
-// 空いた Position への Tile の追加
+// board が GameOver になった
+#[derive(Event, Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BoardGameOverEvent {
+    pub board: Entity,
+}
+
+type SpawningBoardQuery<'w, 's, 'a> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'a BoardState,
+        &'a mut BoardStatus,
+        &'a mut EntropyComponent<WyRand>,
+        Option<&'a mut GarbageQueue>,
+        Option<&'a mut PuzzleSpawnQueue>,
+    ),
+>;
+
+// Spawn 待ちの各盤面の、空いた Position への Tile の追加
 pub fn create_random_tile(
-    mut query: Query<&Position, With<Tile>>,
-    mut rng: ResMut<GlobalEntropy<WyRand>>,
+    mut query: SpawningBoardQuery,
+    settings: Res<GameSettings>,
     mut tile_spawn_evw: EventWriter<TileSpawnEvent>,
+    mut game_over_evw: EventWriter<BoardGameOverEvent>,
 ) {
-    dbg!("System: create_random_tile");
-    let candidates_of_positions: BTreeSet<Position> =
-        get_positions_complement_set(query.transmute_lens());
-    let rnd_n = rng.next_u32() as usize % candidates_of_positions.len();
-    let position = candidates_of_positions
-        .into_iter()
-        .nth(rnd_n)
-        .expect("candidates_of_positions: out of range!!");
-    let rnd_1_or_2 = 1_u8 + (rng.next_u32() % 2) as u8;
-    let tile = Tile(rnd_1_or_2);
-    tile_spawn_evw.send(TileSpawnEvent { tile, position });
+    for (board_entity, board, mut status, mut rng, garbage, puzzle) in query.iter_mut() {
+        if *status != BoardStatus::Spawn {
+            continue;
+        }
+        if board.is_full() {
+            *status = BoardStatus::GameOver;
+            game_over_evw.send(BoardGameOverEvent {
+                board: board_entity,
+            });
+            continue;
+        }
+        let garbage = garbage.map_or(0, |mut queue| queue.tick());
+        // パズルなら決められた Tile を出す
+        let spawns = puzzle
+            .and_then(|mut queue| queue.next(board))
+            .unwrap_or_else(|| choose_spawns(board, &settings, garbage, &mut *rng));
+        tile_spawn_evw.send_batch(spawns.into_iter().map(|(position, tile)| TileSpawnEvent {
+            board: board_entity,
+            tile,
+            position,
+        }));
+        *status = BoardStatus::Input;
+    }
+}
+
+// 1 ターン分に出現させる Tile
+// 届いた邪魔な Tile を garbage 個置いてから、SpawnPolicy に従って置く
+pub fn choose_spawns(
+    board: &BoardState,
+    settings: &GameSettings,
+    garbage: usize,
+    rng: &mut impl RngCore,
+) -> Vec<(Position, Tile)> {
+    let mut candidates_of_positions: Vec<Position> = board.empty_positions().collect();
+    let mut spawns = Vec::new();
+    let garbage_tile = settings
+        .versus
+        .and_then(|versus| versus.garbage)
+        .map(|rules| rules.tile());
+    if let Some(garbage_tile) = garbage_tile {
+        for _ in 0..garbage {
+            if candidates_of_positions.is_empty() {
+                break;
+            }
+            let rnd_n = rng.next_u32() as usize % candidates_of_positions.len();
+            spawns.push((candidates_of_positions.remove(rnd_n), garbage_tile));
+        }
+    }
+    for _ in 0..settings.spawn.tiles_per_turn {
+        if candidates_of_positions.is_empty() {
+            break;
+        }
+        let rnd_n = rng.next_u32() as usize % candidates_of_positions.len();
+        let position = candidates_of_positions.remove(rnd_n);
+        let tile = settings.spawn.choose(rng.next_u32());
+        spawns.push((position, tile));
+    }
+    return spawns;
+}
+
+// 全ての盤面が GameOver なら GameOver へ、そうでなければ Input へ
+fn next_state(query: Query<&BoardStatus>, mut next_state: ResMut<NextState<GameState>>) {
+    match query.iter().all(|status| *status == BoardStatus::GameOver) {
+        true => next_state.set(GameState::GameOver),
+        false => next_state.set(GameState::Input),
+    }
 }
@@ -0,0 +1,177 @@
+use bevy::{app::AppExit, prelude::*};
+
+use crate::bundle::stats_screen::create_stats_screen;
+use crate::components::{
+    ai_controller::AiController,
+    board_state::BoardState,
+    main_board::MainBoard,
+    score::Score,
+    stats_screen::{StatsFooter, StatsScreen},
+};
+use crate::plugins::{
+    calculate::{TileMovement, TileMovementEvent},
+    input::PlayerInputEvent,
+};
+use crate::resources::{game_settings::GameSettings, lifetime_stats::LifetimeStats};
+use crate::states::game_state::GameState;
+use crate::title_menu::component::ButtonAction;
+
+pub const STATS_KEY: KeyCode = KeyCode::Tab;
+pub const EXPORT_KEY: KeyCode = KeyCode::KeyE;
+
+// GameSettings::stats があるとき、1 人用で人が操作する盤面の統計をとる
+// 統計は GameOver と終了のときに保存する
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        let single_player = |settings: Res<GameSettings>| settings.versus.is_none();
+        app.add_systems(Startup, load_stats)
+            .add_systems(
+                Update,
+                (
+                    (count_moves, count_merges, count_time).run_if(single_player),
+                    toggle_screen,
+                    export_stats,
+                )
+                    .run_if(resource_exists::<LifetimeStats>),
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                record_games.run_if(single_player),
+            )
+            .add_systems(Last, save_on_exit.run_if(resource_exists::<LifetimeStats>));
+    }
+}
+
+// 人が操作する盤面
+type PlayerBoard = (With<MainBoard>, Without<AiController>);
+type PlayerBoardQuery<'w, 's> = Query<'w, 's, (), PlayerBoard>;
+
+fn load_stats(mut commands: Commands, settings: Res<GameSettings>) {
+    let Some(stats_settings) = &settings.stats else {
+        return;
+    };
+    commands.insert_resource(LifetimeStats::load_or_default(&stats_settings.path));
+}
+
+fn save(settings: &GameSettings, stats: &LifetimeStats) {
+    let Some(stats_settings) = &settings.stats else {
+        return;
+    };
+    if let Err(e) = stats.save(&stats_settings.path) {
+        eprintln!("failed to save {}: {}", stats_settings.path, e);
+    }
+}
+
+fn count_moves(
+    mut input_evr: EventReader<PlayerInputEvent>,
+    query: PlayerBoardQuery,
+    mut stats: ResMut<LifetimeStats>,
+) {
+    for ev in input_evr.read() {
+        if query.contains(ev.board) {
+            stats.record_move(ev.direction);
+        }
+    }
+}
+
+fn count_merges(
+    mut tile_move_evr: EventReader<TileMovementEvent>,
+    query: PlayerBoardQuery,
+    mut stats: ResMut<LifetimeStats>,
+) {
+    for ev in tile_move_evr.read() {
+        if let TileMovement::Merge { rank, .. } = ev.movement {
+            if query.contains(ev.board) {
+                stats.record_merge(rank);
+            }
+        }
+    }
+}
+
+// タイトル画面と GameOver の後は数えない
+fn count_time(time: Res<Time>, state: Res<State<GameState>>, mut stats: ResMut<LifetimeStats>) {
+    if matches!(state.get(), GameState::TitleMenu | GameState::GameOver) {
+        return;
+    }
+    stats.time_played += time.delta_seconds_f64();
+}
+
+fn record_games(
+    settings: Res<GameSettings>,
+    query: Query<(&BoardState, &Score), PlayerBoard>,
+    stats: Option<ResMut<LifetimeStats>>,
+) {
+    let Some(mut stats) = stats else {
+        return;
+    };
+    for (board, score) in query.iter() {
+        let max_tile = board.max_tile().map_or(0, |tile| tile.num());
+        stats.record_game(score.value(), max_tile);
+    }
+    save(&settings, &stats);
+}
+
+fn save_on_exit(
+    mut exit_evr: EventReader<AppExit>,
+    settings: Res<GameSettings>,
+    stats: Res<LifetimeStats>,
+) {
+    if exit_evr.read().last().is_some() {
+        save(&settings, &stats);
+    }
+}
+
+type TitleButtonQuery<'w, 's> =
+    Query<'w, 's, (&'static Interaction, &'static ButtonAction), Changed<Interaction>>;
+
+// Tab か、タイトル画面の Scores で開閉する
+fn toggle_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    stats: Res<LifetimeStats>,
+    buttons_query: TitleButtonQuery,
+    screens_query: Query<Entity, With<StatsScreen>>,
+) {
+    let clicked = buttons_query.iter().any(|(interaction, action)| {
+        *interaction == Interaction::Pressed && matches!(action, ButtonAction::ScoreBoard)
+    });
+    if !clicked && !keyboard.just_pressed(STATS_KEY) {
+        return;
+    }
+    if screens_query.is_empty() {
+        create_stats_screen(&mut commands, &asset_server, &settings, &stats);
+        return;
+    }
+    for entity in screens_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// Stats 画面を開いているときに、CSV と JSON に書き出す
+fn export_stats(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    stats: Res<LifetimeStats>,
+    mut footer_query: Query<&mut Text, With<StatsFooter>>,
+) {
+    if footer_query.is_empty() || !keyboard.just_pressed(EXPORT_KEY) {
+        return;
+    }
+    let Some(stats_settings) = &settings.stats else {
+        return;
+    };
+    let stem = &stats_settings.export_stem;
+    let result = std::fs::write(format!("{}.csv", stem), stats.to_csv())
+        .and_then(|_| std::fs::write(format!("{}.json", stem), stats.to_json()));
+    let message = match result {
+        Ok(()) => format!("exported {}.csv and {}.json", stem, stem),
+        Err(e) => format!("failed to export: {}", e),
+    };
+    for mut footer in footer_query.iter_mut() {
+        footer.sections[0].value = message.clone();
+    }
+}
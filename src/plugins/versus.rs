@@ -0,0 +1,115 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::bundle::versus_result::create_versus_result;
+use crate::components::{
+    board_state::BoardState, board_status::BoardStatus, garbage_queue::GarbageQueue, score::Score,
+    tile::Tile,
+};
+use crate::plugins::calculate::TileMovementEvent;
+use crate::plugins::spawn::BoardGameOverEvent;
+use crate::resources::game_settings::{GameSettings, VersusSettings, WinCondition};
+use crate::states::game_state::GameState;
+
+pub struct VersusPlugin;
+
+impl Plugin for VersusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Movement),
+            (send_garbage, check_target_tile).run_if(is_versus),
+        )
+        .add_systems(
+            OnEnter(GameState::GameOver),
+            create_versus_result.run_if(is_versus),
+        );
+    }
+}
+
+// 対戦の結果. index は MainBoard::index
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VersusOutcome {
+    Winner(usize),
+    Draw,
+}
+
+fn is_versus(settings: Res<GameSettings>) -> bool {
+    return settings.versus.is_some();
+}
+
+// 盤面ごとの (BoardState, Score) から勝敗を決める
+// 目標の Tile を作った盤面があればその中で、なければ全員の中で Score が最大のものが勝ち
+pub fn decide_outcome(
+    boards: &[(&BoardState, Score)],
+    win_condition: WinCondition,
+) -> VersusOutcome {
+    let mut candidates: Vec<usize> = (0..boards.len()).collect();
+    if let WinCondition::TargetTile(target) = win_condition {
+        let reached: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|i| has_tile(boards[*i].0, target))
+            .collect();
+        if !reached.is_empty() {
+            candidates = reached;
+        }
+    }
+    let best = candidates.iter().map(|i| boards[*i].1).max();
+    let winners: Vec<usize> = candidates
+        .into_iter()
+        .filter(|i| Some(boards[*i].1) == best)
+        .collect();
+    match winners[..] {
+        [winner] => return VersusOutcome::Winner(winner),
+        _ => return VersusOutcome::Draw,
+    }
+}
+
+fn has_tile(board: &BoardState, target: Tile) -> bool {
+    return board.max_tile().is_some_and(|tile| tile >= target);
+}
+
+// 誰かが目標の Tile を作ったら、その時点で全ての盤面を GameOver にする
+pub fn check_target_tile(
+    settings: Res<GameSettings>,
+    mut query: Query<(Entity, &BoardState, &mut BoardStatus)>,
+    mut game_over_evw: EventWriter<BoardGameOverEvent>,
+) {
+    let Some(VersusSettings {
+        win_condition: WinCondition::TargetTile(target),
+        ..
+    }) = settings.versus
+    else {
+        return;
+    };
+    if !query.iter().any(|(_, board, _)| has_tile(board, target)) {
+        return;
+    }
+    for (board, _, mut status) in query.iter_mut() {
+        if *status != BoardStatus::GameOver {
+            *status = BoardStatus::GameOver;
+            game_over_evw.send(BoardGameOverEvent { board });
+        }
+    }
+}
+
+// 合体で大きな Tile を作った盤面から、他の全ての盤面に邪魔な Tile を送る
+pub fn send_garbage(
+    settings: Res<GameSettings>,
+    mut tile_move_evr: EventReader<TileMovementEvent>,
+    mut query: Query<(Entity, &mut GarbageQueue)>,
+) {
+    let Some(rules) = settings.versus.and_then(|versus| versus.garbage) else {
+        return;
+    };
+    let mut attacks: HashMap<Entity, usize> = HashMap::new();
+    for ev in tile_move_evr.read() {
+        *attacks.entry(ev.board).or_default() += rules.attack(&ev.movement);
+    }
+    for (attacker, count) in attacks {
+        for (board, mut queue) in query.iter_mut() {
+            if board != attacker {
+                queue.push(count, rules.delay);
+            }
+        }
+    }
+}
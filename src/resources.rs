@@ -1,2 +1,11 @@
+pub mod achievements;
 pub mod best_score;
-pub mod score;
+pub mod coach;
+pub mod daily;
+pub mod game_analysis;
+pub mod game_clock;
+pub mod game_settings;
+pub mod high_scores;
+pub mod lifetime_stats;
+pub mod move_history;
+pub mod puzzle;
@@ -0,0 +1,213 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::board_state::BoardState;
+use crate::structs::game_mode::GameMode;
+
+// 1 人用で勝ちとする Tile
+pub const WIN_TILE: usize = 2048;
+
+// 達成の条件. 定義のファイルでは kind で種類を選ぶ
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AchievementCondition {
+    // この数の Tile を作る
+    ReachTile {
+        tile: usize,
+    },
+    // WIN_TILE に届く. max_moves があればその手数以内で
+    // undo はないので、undo を使わない勝ちの条件は別に作らない
+    Win {
+        max_moves: Option<usize>,
+    },
+    // mode があれば GameMode::kind がそれのときだけ
+    Score {
+        score: usize,
+        #[serde(default)]
+        mode: Option<String>,
+    },
+    // after_moves 手以上進めた後に、盤面の Tile を tiles 個以下にする
+    FewTiles {
+        tiles: usize,
+        after_moves: usize,
+    },
+}
+
+// 1 つの盤面のゲームの途中の様子
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GameProgress {
+    pub max_tile: usize,
+    pub score: usize,
+    pub moves: usize,
+    pub tiles: usize,
+    // GameMode::kind
+    pub mode: &'static str,
+}
+
+impl GameProgress {
+    pub fn new(board: &BoardState, score: usize, moves: usize, mode: &GameMode) -> Self {
+        return Self {
+            max_tile: board.max_tile().map_or(0, |tile| tile.num()),
+            score,
+            moves,
+            tiles: board.tiles().count(),
+            mode: mode.kind(),
+        };
+    }
+}
+
+impl AchievementCondition {
+    pub fn is_met(&self, progress: &GameProgress) -> bool {
+        match self {
+            Self::ReachTile { tile } => return progress.max_tile >= *tile,
+            Self::Win { max_moves } => {
+                return progress.max_tile >= WIN_TILE
+                    && max_moves.is_none_or(|max_moves| progress.moves <= max_moves);
+            }
+            Self::Score { score, mode } => {
+                return progress.score >= *score
+                    && mode.as_ref().is_none_or(|mode| mode == progress.mode);
+            }
+            Self::FewTiles { tiles, after_moves } => {
+                return progress.moves >= *after_moves && progress.tiles <= *tiles;
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Achievement {
+    // 保存に使う. 変えると達成が消える
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub condition: AchievementCondition,
+}
+
+// 組み込みの実績. 定義のファイルと同じもの
+const BUILTIN_DEFINITIONS: &str = include_str!("../../assets/achievements.json");
+
+impl Achievement {
+    // 定義のファイルがないときの実績. assets/achievements.json をビルドに含めて読む
+    pub fn defaults() -> Vec<Self> {
+        return Self::parse_definitions(BUILTIN_DEFINITIONS)
+            .expect("assets/achievements.json is a valid definition");
+    }
+    // JSON の配列で定義する
+    pub fn parse_definitions(text: &str) -> io::Result<Vec<Self>> {
+        return serde_json::from_str(text).map_err(io::Error::other);
+    }
+    pub fn load_definitions(path: impl AsRef<Path>) -> io::Result<Vec<Self>> {
+        let text = std::fs::read_to_string(path)?;
+        return Self::parse_definitions(&text);
+    }
+}
+
+// 実績の定義と達成したもの. 達成したものは AchievementSettings::path に保存する
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct Achievements {
+    pub definitions: Vec<Achievement>,
+    // id -> 達成した時刻 (UNIX 時間の秒)
+    pub unlocked: BTreeMap<String, u64>,
+}
+
+impl Achievements {
+    pub fn new(definitions: Vec<Achievement>) -> Self {
+        return Self {
+            definitions,
+            unlocked: BTreeMap::new(),
+        };
+    }
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        return self.unlocked.contains_key(id);
+    }
+    // 新しく達成した実績を記録して返す
+    pub fn check(&mut self, progress: &GameProgress) -> Vec<Achievement> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        let unlocked: Vec<Achievement> = self
+            .definitions
+            .iter()
+            .filter(|achievement| !self.is_unlocked(&achievement.id))
+            .filter(|achievement| achievement.condition.is_met(progress))
+            .cloned()
+            .collect();
+        for achievement in unlocked.iter() {
+            self.unlocked.insert(achievement.id.clone(), now);
+        }
+        return unlocked;
+    }
+    pub fn load_unlocked(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        let text = std::fs::read_to_string(path)?;
+        self.unlocked = serde_json::from_str(&text).map_err(io::Error::other)?;
+        return Ok(());
+    }
+    pub fn save_unlocked(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let text = serde_json::to_string_pretty(&self.unlocked).map_err(io::Error::other)?;
+        return std::fs::write(path, text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlock_each_achievement_once() {
+        let mut achievements = Achievements::new(Achievement::defaults());
+        let progress = GameProgress {
+            max_tile: 1024,
+            score: 12000,
+            moves: 900,
+            tiles: 10,
+            mode: "classic",
+        };
+        let ids: Vec<String> = achievements
+            .check(&progress)
+            .into_iter()
+            .map(|achievement| achievement.id)
+            .collect();
+        assert_eq!(ids, ["tile_512", "tile_1024", "score_10000"]);
+        let progress = GameProgress {
+            max_tile: 2048,
+            moves: 1200,
+            ..progress
+        };
+        let ids: Vec<String> = achievements
+            .check(&progress)
+            .into_iter()
+            .map(|achievement| achievement.id)
+            .collect();
+        assert_eq!(ids, ["win"]);
+        assert!(!achievements.is_unlocked("fast_win"));
+        let progress = GameProgress {
+            mode: "time",
+            ..progress
+        };
+        let ids: Vec<String> = achievements
+            .check(&progress)
+            .into_iter()
+            .map(|achievement| achievement.id)
+            .collect();
+        assert_eq!(ids, ["time_attack_5000"]);
+    }
+
+    #[test]
+    fn read_definitions_as_json() {
+        let text = r#"[{"id":"tidy","name":"Tidy","description":"","condition":{"kind":"few_tiles","tiles":2,"after_moves":10}}]"#;
+        let definitions = Achievement::parse_definitions(text).unwrap();
+        assert_eq!(
+            definitions[0].condition,
+            AchievementCondition::FewTiles {
+                tiles: 2,
+                after_moves: 10
+            }
+        );
+    }
+}
@@ -4,6 +4,12 @@ use bevy::prelude::*;
 pub struct BestScore(usize);
 
 impl BestScore {
+    pub fn new(value: usize) -> Self {
+        return Self(value);
+    }
+    pub fn value(&self) -> usize {
+        return self.0;
+    }
     pub fn update(&mut self, post: usize) {
         let pre = self.0;
         *self = BestScore(pre.max(post));
@@ -16,8 +22,8 @@ impl Default for BestScore {
     }
 }
 
-impl ToString for BestScore {
-    fn to_string(&self) -> String {
-        return self.0.to_string();
+impl std::fmt::Display for BestScore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{}", self.0);
     }
 }
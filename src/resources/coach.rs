@@ -0,0 +1,158 @@
+use bevy::prelude::*;
+
+use crate::ai::{
+    coach::{assess, Risk},
+    rollout::Simulator,
+};
+use crate::components::board_state::BoardState;
+use crate::resources::game_settings::{CoachSettings, SpawnPolicy};
+use crate::structs::direction::Direction;
+
+// 危ない手を 1 回止めて、同じ手をもう一度選んだら通す
+#[derive(Resource)]
+pub struct Coach {
+    simulator: Simulator,
+    // 確認を待っている手. 盤面ごとに 1 つまで、新しいものが後
+    pub pending: Vec<CoachWarning>,
+    // 確認が済んだ手. 古い順
+    pub log: Vec<CoachWarning>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CoachWarning {
+    // MainBoard::index
+    pub board: usize,
+    // その盤面での何手目か
+    pub turn: usize,
+    pub direction: Direction,
+    pub risk: Risk,
+    pub decision: CoachDecision,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CoachDecision {
+    Pending,
+    // もう一度選んで動かした
+    Confirmed,
+    // 別の方向を選んだ
+    Cancelled,
+}
+
+impl CoachDecision {
+    pub fn name(self) -> &'static str {
+        match self {
+            CoachDecision::Pending => return "pending",
+            CoachDecision::Confirmed => return "confirmed",
+            CoachDecision::Cancelled => return "cancelled",
+        }
+    }
+}
+
+impl Coach {
+    pub fn new(spawn: &SpawnPolicy, seed: u64) -> Self {
+        return Self {
+            simulator: Simulator::new(spawn, seed),
+            pending: Vec::new(),
+            log: Vec::new(),
+        };
+    }
+    pub fn set_spawn(&mut self, spawn: &SpawnPolicy) {
+        self.simulator.set_spawn(spawn);
+    }
+    // 最後に止めた手
+    pub fn latest_pending(&self) -> Option<&CoachWarning> {
+        return self.pending.last();
+    }
+    // 動かしてよければ true. 確認を待っている手は同じ盤面の入力でだけ決まる
+    pub fn review(
+        &mut self,
+        settings: &CoachSettings,
+        board: usize,
+        turn: usize,
+        state: &BoardState,
+        direction: Direction,
+    ) -> bool {
+        if let Some(index) = self
+            .pending
+            .iter()
+            .position(|pending| pending.board == board)
+        {
+            let mut pending = self.pending.remove(index);
+            let confirmed = pending.direction == direction;
+            pending.decision = match confirmed {
+                true => CoachDecision::Confirmed,
+                false => CoachDecision::Cancelled,
+            };
+            self.log.push(pending);
+            if confirmed {
+                return true;
+            }
+        }
+        let risk = assess(
+            state,
+            direction,
+            settings.horizon,
+            settings.samples,
+            &mut self.simulator,
+        );
+        let risky = risk.loss_chance >= settings.loss_threshold
+            || (settings.guard_corner && risk.leaves_corner);
+        if !risky {
+            return true;
+        }
+        self.pending.push(CoachWarning {
+            board,
+            turn,
+            direction,
+            risk,
+            decision: CoachDecision::Pending,
+        });
+        return false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{position::Position, tile::Tile};
+
+    #[test]
+    fn confirm_moving_max_tile_out_of_corner() {
+        let mut board = BoardState::new(4, 4);
+        board.set(Position::new(0, 0), Some(Tile(3)));
+        board.set(Position::new(2, 0), Some(Tile(2)));
+        board.set(Position::new(3, 0), Some(Tile(1)));
+        let settings = CoachSettings::default();
+        let mut coach = Coach::new(&SpawnPolicy::default(), 0);
+        assert!(coach.review(&settings, 0, 0, &board, Direction::Down));
+        assert!(!coach.review(&settings, 0, 1, &board, Direction::Right));
+        assert!(coach.latest_pending().unwrap().risk.leaves_corner);
+        // 最大の Tile は別の角に移るだけ
+        assert!(coach.review(&settings, 0, 1, &board, Direction::Up));
+        assert_eq!(coach.log[0].decision, CoachDecision::Cancelled);
+        assert!(!coach.review(&settings, 0, 1, &board, Direction::Right));
+        assert!(coach.review(&settings, 0, 1, &board, Direction::Right));
+        assert_eq!(coach.log[1].decision, CoachDecision::Confirmed);
+        assert!(coach.pending.is_empty());
+    }
+
+    #[test]
+    fn keep_pending_per_board() {
+        let mut board = BoardState::new(4, 4);
+        board.set(Position::new(0, 0), Some(Tile(3)));
+        board.set(Position::new(2, 0), Some(Tile(2)));
+        board.set(Position::new(3, 0), Some(Tile(1)));
+        let settings = CoachSettings::default();
+        let mut coach = Coach::new(&SpawnPolicy::default(), 0);
+        assert!(!coach.review(&settings, 0, 0, &board, Direction::Right));
+        // もう 1 人の入力では 1 人目の確認は消えない
+        assert!(coach.review(&settings, 1, 0, &board, Direction::Down));
+        assert!(!coach.review(&settings, 1, 1, &board, Direction::Right));
+        assert_eq!(coach.pending.len(), 2);
+        assert!(coach.log.is_empty());
+        assert!(coach.review(&settings, 0, 0, &board, Direction::Right));
+        assert_eq!(coach.log[0].board, 0);
+        assert_eq!(coach.log[0].decision, CoachDecision::Confirmed);
+        assert_eq!(coach.latest_pending().unwrap().board, 1);
+    }
+}
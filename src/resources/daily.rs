@@ -0,0 +1,214 @@
+use std::io;
+use std::path::Path;
+
+use bevy::prelude::*;
+use chrono::{Datelike, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::structs::direction::Direction;
+
+// 共有する文の 1 行目
+const SHARE_HEADER: &str = "2048 daily";
+
+// 手元の暦の今日
+pub fn today() -> NaiveDate {
+    return Local::now().date_naive();
+}
+
+// 日付から決まる seed. どの環境でも同じ値になるように splitmix64 で混ぜる
+pub fn daily_seed(date: NaiveDate) -> u64 {
+    let ymd = date.year() as u64 * 10000 + date.month() as u64 * 100 + date.day() as u64;
+    let mut z = ymd.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    return z ^ (z >> 31);
+}
+
+// 今遊んでいる Daily の日付. 途中で日付が変わってもこの日の記録にする
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DailyRun {
+    pub date: NaiveDate,
+    // 始めたときにその日の記録があった. 練習なのでどの表にも残さない
+    pub practice: bool,
+}
+
+// 1 日 1 回の記録
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub date: NaiveDate,
+    pub score: usize,
+    pub max_tile: usize,
+    // 選んだ方向を Direction::letter で並べたもの. 同じ seed で進め直して確かめる
+    pub directions: String,
+}
+
+impl DailyResult {
+    pub fn moves(&self) -> usize {
+        return self.directions.len();
+    }
+    pub fn directions(&self) -> Option<Vec<Direction>> {
+        return self
+            .directions
+            .chars()
+            .map(Direction::from_letter)
+            .collect();
+    }
+    // 日付, 得点, 最大の Tile, 手数と、確かめるための手
+    pub fn share_text(&self) -> String {
+        return format!(
+            "{} {}\nscore {} / max tile {} / {} moves\n{}\n",
+            SHARE_HEADER,
+            self.date,
+            self.score,
+            self.max_tile,
+            self.moves(),
+            self.directions
+        );
+    }
+    pub fn parse_share_text(text: &str) -> Option<Self> {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        let date = lines
+            .next()?
+            .strip_prefix(SHARE_HEADER)?
+            .trim()
+            .parse()
+            .ok()?;
+        let numbers: Vec<usize> = lines
+            .next()?
+            .split_whitespace()
+            .filter_map(|word| word.parse().ok())
+            .collect();
+        let [score, max_tile, moves] = numbers[..] else {
+            return None;
+        };
+        let directions = lines.next().unwrap_or_default().to_string();
+        let result = Self {
+            date,
+            score,
+            max_tile,
+            directions,
+        };
+        if result.moves() != moves || result.directions().is_none() {
+            return None;
+        }
+        return Some(result);
+    }
+}
+
+// これまでの Daily の記録. DailySettings::path に JSON で保存する
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DailyHistory {
+    // 日付の順
+    pub results: Vec<DailyResult>,
+}
+
+impl DailyHistory {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        return serde_json::from_str(&text).map_err(io::Error::other);
+    }
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let text = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        return std::fs::write(path, text);
+    }
+    // ファイルがなければ空から
+    pub fn load_or_default(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Self::default();
+        }
+        return Self::load(path).unwrap_or_else(|e| {
+            eprintln!("failed to load {}: {}", path.display(), e);
+            return Self::default();
+        });
+    }
+    pub fn played(&self, date: NaiveDate) -> Option<&DailyResult> {
+        return self.results.iter().find(|result| result.date == date);
+    }
+    // 記録するのはその日の最初の 1 回だけ
+    pub fn record(&mut self, result: DailyResult) -> bool {
+        if self.played(result.date).is_some() {
+            return false;
+        }
+        let i = self
+            .results
+            .partition_point(|other| other.date < result.date);
+        self.results.insert(i, result);
+        return true;
+    }
+    // today か、まだ遊んでいなければ昨日まで続けて遊んだ日数
+    pub fn current_streak(&self, today: NaiveDate) -> usize {
+        let mut date = match self.played(today) {
+            Some(_) => today,
+            None => today.pred_opt().unwrap_or(today),
+        };
+        let mut streak = 0;
+        while self.played(date).is_some() {
+            streak += 1;
+            let Some(previous) = date.pred_opt() else {
+                break;
+            };
+            date = previous;
+        }
+        return streak;
+    }
+    pub fn best_streak(&self) -> usize {
+        let mut best = 0;
+        let mut streak = 0;
+        let mut last: Option<NaiveDate> = None;
+        for result in self.results.iter() {
+            streak = match last.and_then(|last| last.succ_opt()) {
+                Some(next) if next == result.date => streak + 1,
+                _ => 1,
+            };
+            best = best.max(streak);
+            last = Some(result.date);
+        }
+        return best;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        return NaiveDate::from_ymd_opt(2026, 10, day).unwrap();
+    }
+
+    fn result(day: u32) -> DailyResult {
+        return DailyResult {
+            date: date(day),
+            score: 1234,
+            max_tile: 128,
+            directions: "LDRU".to_string(),
+        };
+    }
+
+    #[test]
+    fn count_streaks_and_keep_first_attempt() {
+        let mut history = DailyHistory::default();
+        for day in [10, 11, 12, 15, 16] {
+            assert!(history.record(result(day)));
+        }
+        assert!(!history.record(result(16)));
+        assert_eq!(history.current_streak(date(16)), 2);
+        assert_eq!(history.current_streak(date(17)), 2);
+        assert_eq!(history.current_streak(date(18)), 0);
+        assert_eq!(history.best_streak(), 3);
+        assert_ne!(daily_seed(date(16)), daily_seed(date(17)));
+    }
+
+    #[test]
+    fn read_back_share_text() {
+        let result = result(19);
+        let text = result.share_text();
+        assert!(text.starts_with("2048 daily 2026-10-19\nscore 1234 / max tile 128 / 4 moves"));
+        assert_eq!(DailyResult::parse_share_text(&text), Some(result));
+        assert_eq!(
+            DailyResult::parse_share_text(&text.replace("4 moves", "5 moves")),
+            None
+        );
+    }
+}
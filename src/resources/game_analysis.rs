@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+
+use crate::ai::analysis::MoveAnalysis;
+
+// GameOver の後に見直した手. selected が画面に出している手
+#[derive(Resource, Clone, Debug, Default)]
+pub struct GameAnalysis {
+    pub moves: Vec<MoveAnalysis>,
+    pub selected: usize,
+}
+
+impl GameAnalysis {
+    pub fn new(moves: Vec<MoveAnalysis>) -> Self {
+        // 最初は最大の悪手を出す
+        let selected = moves
+            .iter()
+            .enumerate()
+            .filter(|(_, analysis)| analysis.blunder)
+            .max_by(|(_, a), (_, b)| a.loss().total_cmp(&b.loss()))
+            .map_or(0, |(i, _)| i);
+        return Self { moves, selected };
+    }
+    pub fn selected(&self) -> Option<&MoveAnalysis> {
+        return self.moves.get(self.selected);
+    }
+    // selected より後の悪手へ. なければ最初の悪手へ戻る
+    pub fn next_blunder(&mut self) {
+        let blunders: Vec<usize> = self.blunders().collect();
+        if let Some(&i) = blunders
+            .iter()
+            .find(|&&i| i > self.selected)
+            .or(blunders.first())
+        {
+            self.selected = i;
+        }
+    }
+    pub fn previous_blunder(&mut self) {
+        let blunders: Vec<usize> = self.blunders().collect();
+        if let Some(&i) = blunders
+            .iter()
+            .rev()
+            .find(|&&i| i < self.selected)
+            .or(blunders.last())
+        {
+            self.selected = i;
+        }
+    }
+    fn blunders(&self) -> impl Iterator<Item = usize> + '_ {
+        return self
+            .moves
+            .iter()
+            .enumerate()
+            .filter(|(_, analysis)| analysis.blunder)
+            .map(|(i, _)| i);
+    }
+}
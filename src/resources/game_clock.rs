@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+// 新しいゲームを始めてから遊んだ時間. タイトル画面と GameOver の後は進まない
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GameClock {
+    pub elapsed: Duration,
+    // 今の手を待ち始めてからの時間
+    pub turn_elapsed: Duration,
+}
+
+// m:ss の形. 秒未満は切り上げる
+pub fn minutes_seconds(duration: Duration) -> String {
+    let seconds = duration.as_secs_f64().ceil() as u64;
+    return format!("{}:{:02}", seconds / 60, seconds % 60);
+}
+
+impl std::fmt::Display for GameClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let seconds = self.elapsed.as_secs();
+        return write!(f, "{}:{:02}", seconds / 60, seconds % 60);
+    }
+}
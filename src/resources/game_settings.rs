@@ -0,0 +1,551 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+use crate::ai::{ntuple::NTupleNetwork, AgentKind};
+use crate::components::{position::Position, tile::Tile};
+use crate::constants::{
+    color::{
+        BACKGROUND_COLOR, BLOCKER_COLOR, BLUNDER_COLOR, BOARD_COLOR_0, BOARD_COLOR_1,
+        SCORE_TEXT_COLOR, TILE_COLOR_0, TILE_COLOR_1, TILE_TEXT_COLOR,
+    },
+    layout::{
+        GRID_HEIGHT, GRID_WIDTH, MAIN_AND_SCORE_BOARD_MARGIN, MAIN_BOARDS_MARGIN,
+        MAIN_BOARD_PADDING, SCORE_BOARD_HEIGHT, TILE_HEIGHT, TILE_MARGIN_HORIZONTAL,
+        TILE_MARGIN_VERTICAL, TILE_WIDTH,
+    },
+};
+use crate::plugins::calculate::TileMovement;
+use crate::structs::{direction::Direction, game_mode::GameMode};
+
+// Game2048Plugin の設定
+#[derive(Resource, Clone, Debug)]
+pub struct GameSettings {
+    pub board_size: BoardSize,
+    pub spawn: SpawnPolicy,
+    pub theme: Theme,
+    // 横に並べる盤面. 1 つ以上必要
+    pub boards: Vec<BoardSettings>,
+    pub ui: UiSettings,
+    // 1 人用のルール. 対戦では使わない
+    pub mode: GameMode,
+    // 対戦する場合の勝敗の決め方. None なら対戦しない
+    pub versus: Option<VersusSettings>,
+    // Some なら localhost で HTTP の API を提供する
+    pub http_api: Option<HttpApiSettings>,
+    // Some ならキーボードで選んだ危ない手の前に確認する
+    pub coach: Option<CoachSettings>,
+    // Some なら 1 人用のゲームの統計をファイルに残す
+    pub stats: Option<StatsSettings>,
+    // Some なら 1 人用のゲームで実績を解除する
+    pub achievements: Option<AchievementSettings>,
+    // Some ならモードごとの得点表をファイルに残す
+    pub high_scores: Option<HighScoreSettings>,
+    // Some なら GameMode::Daily の記録をファイルに残す
+    pub daily: Option<DailySettings>,
+    // Some なら GameMode::Puzzle のパズルを読み、解いた記録をファイルに残す
+    pub puzzles: Option<PuzzleSettings>,
+    // AgentKind::NTuple の重み
+    pub ntuple: Option<Arc<NTupleNetwork>>,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        return Self {
+            board_size: BoardSize::default(),
+            spawn: SpawnPolicy::default(),
+            theme: Theme::default(),
+            boards: vec![BoardSettings::default()],
+            ui: UiSettings::default(),
+            mode: GameMode::default(),
+            versus: None,
+            http_api: None,
+            coach: None,
+            stats: None,
+            achievements: None,
+            high_scores: None,
+            daily: None,
+            puzzles: None,
+            ntuple: None,
+        };
+    }
+}
+
+impl GameSettings {
+    // Daily の盤面の大きさと Tile の出現. 共有された記録はこのルールで確かめる
+    pub fn daily_rules(&self) -> Self {
+        let mut settings = self.clone();
+        settings.board_size = BoardSize::default();
+        settings.spawn = SpawnPolicy::default();
+        return settings;
+    }
+    pub fn uses_daily_rules(&self) -> bool {
+        return self.board_size == BoardSize::default() && self.spawn == SpawnPolicy::default();
+    }
+    // 2 人対戦. 左の盤面は WASD, 右の盤面は矢印キーで操作する
+    // shared_seed を与えると、両方の盤面で同じ乱数列から Tile が出現する
+    pub fn versus(win_condition: WinCondition, shared_seed: Option<u64>) -> Self {
+        let board = |key_bindings: KeyBindings| BoardSettings {
+            input: InputSettings {
+                key_bindings: vec![key_bindings],
+            },
+            seed: shared_seed,
+            ai: None,
+        };
+        return Self {
+            boards: vec![board(KeyBindings::WASD), board(KeyBindings::ARROWS)],
+            ui: UiSettings {
+                exit_on_game_over: false,
+                ..default()
+            },
+            versus: Some(VersusSettings {
+                win_condition,
+                garbage: None,
+            }),
+            ..default()
+        };
+    }
+    pub fn window_size(&self) -> Vec2 {
+        let board = self.board_size.main_board_size_2d();
+        let n = self.boards.len().max(1) as f32;
+        let width = board.x * n + MAIN_BOARDS_MARGIN * (n - 1.0);
+        match self.ui.score_board {
+            true => {
+                return Vec2::new(
+                    width,
+                    board.y + SCORE_BOARD_HEIGHT + MAIN_AND_SCORE_BOARD_MARGIN,
+                )
+            }
+            false => return Vec2::new(width, board.y),
+        }
+    }
+    // ウィンドウの中心から見た index 番目の MainBoard の中心
+    pub fn main_board_offset(&self, index: usize) -> Vec2 {
+        let window = self.window_size();
+        let board = self.board_size.main_board_size_2d();
+        let x = -window.x / 2.0 + board.x / 2.0 + (board.x + MAIN_BOARDS_MARGIN) * index as f32;
+        match self.ui.score_board {
+            true => return Vec2::new(x, -(SCORE_BOARD_HEIGHT + MAIN_AND_SCORE_BOARD_MARGIN) / 2.0),
+            false => return Vec2::new(x, 0.0),
+        }
+    }
+    // ウィンドウの左端から見た index 番目の MainBoard の左端
+    pub fn main_board_left(&self, index: usize) -> f32 {
+        let board = self.board_size.main_board_size_2d();
+        return (board.x + MAIN_BOARDS_MARGIN) * index as f32;
+    }
+}
+
+// 盤面ごとの設定
+#[derive(Clone, Debug, Default)]
+pub struct BoardSettings {
+    pub input: InputSettings,
+    // None なら GlobalEntropy から分岐させる
+    pub seed: Option<u64>,
+    // Some なら AI が操作する
+    pub ai: Option<AiSettings>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AiSettings {
+    pub agent: AgentKind,
+    // 1 手ごとの間隔 (秒)
+    pub interval: f32,
+}
+
+impl Default for AiSettings {
+    fn default() -> Self {
+        return Self {
+            agent: AgentKind::default(),
+            interval: 0.3,
+        };
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BoardSize {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Default for BoardSize {
+    fn default() -> Self {
+        return Self {
+            width: GRID_WIDTH,
+            height: GRID_HEIGHT,
+        };
+    }
+}
+
+impl BoardSize {
+    pub fn main_board_size_2d(&self) -> Vec2 {
+        let w = TILE_WIDTH * self.width as f32
+            + TILE_MARGIN_HORIZONTAL * (self.width - 1) as f32
+            + MAIN_BOARD_PADDING * 2.0;
+        let h = TILE_HEIGHT * self.height as f32
+            + TILE_MARGIN_VERTICAL * (self.height - 1) as f32
+            + MAIN_BOARD_PADDING * 2.0;
+        return Vec2::new(w, h);
+    }
+    // MainBoard の中心から見た Position の位置
+    pub fn tile_transform(&self, pos: Position, z: f32) -> Transform {
+        let x = (-(self.width as f32) / 2.0 + pos.x as f32 + 0.5)
+            * (TILE_WIDTH + TILE_MARGIN_HORIZONTAL);
+        let y = (-(self.height as f32) / 2.0 + pos.y as f32 + 0.5)
+            * (TILE_HEIGHT + TILE_MARGIN_VERTICAL);
+        return Transform::from_xyz(x, y, z);
+    }
+}
+
+// 1 ターンに出現させる Tile
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpawnPolicy {
+    pub tiles_per_turn: usize,
+    // (Tile, 重み) の組. 重みに比例した確率で選ぶ
+    pub weights: Vec<(Tile, u32)>,
+}
+
+impl Default for SpawnPolicy {
+    fn default() -> Self {
+        return Self {
+            tiles_per_turn: 1,
+            weights: vec![(Tile(1), 1), (Tile(2), 1)],
+        };
+    }
+}
+
+impl SpawnPolicy {
+    // 1 ターンに 1 つ以上出現させ、重みの和が 0 でない
+    pub fn validate(&self) -> Result<(), String> {
+        if self.tiles_per_turn == 0 {
+            return Err("tiles_per_turn must be at least 1".to_string());
+        }
+        if self.total_weight() == 0 {
+            return Err("spawn weights must not all be zero".to_string());
+        }
+        return Ok(());
+    }
+    // 0 <= rnd < total_weight に対応する Tile. validate を通った設定で呼ぶ
+    pub fn choose(&self, rnd: u32) -> Tile {
+        let mut rnd = rnd % self.total_weight();
+        for (tile, weight) in self.weights.iter() {
+            if rnd < *weight {
+                return *tile;
+            }
+            rnd -= weight;
+        }
+        unreachable!("rnd is less than the total weight");
+    }
+    pub fn total_weight(&self) -> u32 {
+        return self.weights.iter().map(|(_, weight)| weight).sum();
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub background: Color,
+    pub board: Color,
+    pub board_tile: Color,
+    // rank = 1 の色と rank >= 11 の色. その間は線形補間
+    pub tile_low: Color,
+    pub tile_high: Color,
+    pub tile_text: Color,
+    pub score_text: Color,
+    pub blocker: Color,
+    // 見直しで悪手とした手
+    pub blunder: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        return Self {
+            background: BACKGROUND_COLOR,
+            board: BOARD_COLOR_0,
+            board_tile: BOARD_COLOR_1,
+            tile_low: TILE_COLOR_0,
+            tile_high: TILE_COLOR_1,
+            tile_text: TILE_TEXT_COLOR,
+            score_text: SCORE_TEXT_COLOR,
+            blocker: BLOCKER_COLOR,
+            blunder: BLUNDER_COLOR,
+        };
+    }
+}
+
+impl Theme {
+    pub fn tile_color(&self, tile: Tile) -> Color {
+        if tile.is_blocker() {
+            return self.blocker;
+        }
+        let Tile(rank) = tile;
+        // rank = 1 -> r = 0.0, rank >= 11 -> r = 1.0
+        let r: f32 = ((rank.max(1) - 1) as f32 / 10.0).min(1.0);
+        return self.tile_low * (1.0 - r) + self.tile_high * r;
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct InputSettings {
+    // 空にするとキーボードからは操作しない
+    pub key_bindings: Vec<KeyBindings>,
+}
+
+impl Default for InputSettings {
+    fn default() -> Self {
+        return Self {
+            key_bindings: vec![KeyBindings::ARROWS],
+        };
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct KeyBindings {
+    pub left: KeyCode,
+    pub right: KeyCode,
+    pub up: KeyCode,
+    pub down: KeyCode,
+}
+
+impl KeyBindings {
+    pub const ARROWS: KeyBindings = KeyBindings {
+        left: KeyCode::ArrowLeft,
+        right: KeyCode::ArrowRight,
+        up: KeyCode::ArrowUp,
+        down: KeyCode::ArrowDown,
+    };
+    pub const WASD: KeyBindings = KeyBindings {
+        left: KeyCode::KeyA,
+        right: KeyCode::KeyD,
+        up: KeyCode::KeyW,
+        down: KeyCode::KeyS,
+    };
+    pub fn just_pressed(&self, keyboard: &ButtonInput<KeyCode>) -> Option<Direction> {
+        if keyboard.just_pressed(self.left) {
+            return Some(Direction::Left);
+        } else if keyboard.just_pressed(self.right) {
+            return Some(Direction::Right);
+        } else if keyboard.just_pressed(self.up) {
+            return Some(Direction::Up);
+        } else if keyboard.just_pressed(self.down) {
+            return Some(Direction::Down);
+        }
+        return None;
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct VersusSettings {
+    pub win_condition: WinCondition,
+    // None なら邪魔な Tile を送らない
+    pub garbage: Option<GarbageRules>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum WinCondition {
+    // 全員が GameOver になった時点の Score で決める
+    #[default]
+    Score,
+    // 先にこの Tile を作った方の勝ち. 誰も作れなければ Score で決める
+    TargetTile(Tile),
+}
+
+// 合体で大きな Tile を作ると、他の盤面に邪魔な Tile を送る
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GarbageRules {
+    // この Tile 以上を合体で作ると 1 つ送る. 1 段大きくなるごとに 1 つ増える
+    pub threshold: Tile,
+    pub kind: GarbageKind,
+    // 送られた側で、何ターン後の Spawn に出現するか
+    pub delay: usize,
+}
+
+impl Default for GarbageRules {
+    fn default() -> Self {
+        return Self {
+            threshold: Tile(6),
+            kind: GarbageKind::Blocker,
+            delay: 1,
+        };
+    }
+}
+
+impl GarbageRules {
+    // movement で送る邪魔な Tile の数
+    pub fn attack(&self, movement: &TileMovement) -> usize {
+        match *movement {
+            TileMovement::Merge { rank, .. } if rank >= self.threshold => {
+                return (rank.0 - self.threshold.0) as usize + 1
+            }
+            _ => return 0,
+        }
+    }
+    pub fn tile(&self) -> Tile {
+        match self.kind {
+            GarbageKind::Blocker => return Tile::BLOCKER,
+            GarbageKind::LowTile(tile) => return tile,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum GarbageKind {
+    // 合体しない Tile::BLOCKER
+    #[default]
+    Blocker,
+    // 普通の Tile を余分に出現させる
+    LowTile(Tile),
+}
+
+// 危ない手の見積もり方
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoachSettings {
+    // horizon 手以内に動かせなくなる割合がこれ以上なら確認する
+    pub loss_threshold: f32,
+    pub horizon: u32,
+    // 見積もりに進める回数
+    pub samples: u32,
+    // 角にある最大の Tile を動かす手も確認する
+    pub guard_corner: bool,
+    // Some なら確認した手をこのファイルに追記する
+    pub log_path: Option<String>,
+}
+
+impl Default for CoachSettings {
+    fn default() -> Self {
+        return Self {
+            loss_threshold: 0.25,
+            horizon: 3,
+            samples: 200,
+            guard_corner: true,
+            log_path: None,
+        };
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StatsSettings {
+    // 統計を保存する JSON のファイル
+    pub path: String,
+    // Stats 画面から書き出すファイル. 拡張子を .csv と .json にする
+    pub export_stem: String,
+}
+
+impl Default for StatsSettings {
+    fn default() -> Self {
+        return Self {
+            path: "stats.json".to_string(),
+            export_stem: "stats_export".to_string(),
+        };
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AchievementSettings {
+    // 実績の定義の JSON. None かファイルがなければビルドに含めた assets/achievements.json
+    pub definitions_path: Option<String>,
+    // 達成した実績を保存する JSON のファイル
+    pub path: String,
+}
+
+impl Default for AchievementSettings {
+    fn default() -> Self {
+        return Self {
+            definitions_path: Some("assets/achievements.json".to_string()),
+            path: "achievements.json".to_string(),
+        };
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HighScoreSettings {
+    // 得点表を保存する JSON のファイル
+    pub path: String,
+}
+
+impl Default for HighScoreSettings {
+    fn default() -> Self {
+        return Self {
+            path: "high_scores.json".to_string(),
+        };
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DailySettings {
+    // 記録を保存する JSON のファイル
+    pub path: String,
+    // Some なら記録した日の共有する文をこのファイルに書く
+    pub share_path: Option<String>,
+}
+
+impl Default for DailySettings {
+    fn default() -> Self {
+        return Self {
+            path: "daily.json".to_string(),
+            share_path: Some("daily_share.txt".to_string()),
+        };
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PuzzleSettings {
+    // パズルの JSON のファイルを置くディレクトリ. ファイル名の順に遊ぶ
+    pub dir: String,
+    // 解いたパズルと最少の手数を保存する JSON のファイル
+    pub path: String,
+    // Some ならこの id のパズルから. None ならまだ解いていない最初のパズルから
+    pub start: Option<String>,
+}
+
+impl Default for PuzzleSettings {
+    fn default() -> Self {
+        return Self {
+            dir: "assets/puzzles".to_string(),
+            path: "puzzles.json".to_string(),
+            start: None,
+        };
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HttpApiSettings {
+    // 127.0.0.1 のこの port で待ち受ける
+    pub port: u16,
+}
+
+impl Default for HttpApiSettings {
+    fn default() -> Self {
+        return Self { port: 2048 };
+    }
+}
+
+// どの UI を含めるか
+#[derive(Clone, Debug)]
+pub struct UiSettings {
+    pub title_menu: bool,
+    pub score_board: bool,
+    // GameOver になったら App を終了する
+    pub exit_on_game_over: bool,
+    // Some なら H キーで、その Agent の選ぶ方向をお知らせに出す
+    pub hint: Option<AgentKind>,
+    // 1 人用の GameOver の後に、AI で手を見直す画面を出す
+    pub analysis: bool,
+    // Some ならタイトル画面の裏で、この AI が盤面を遊ぶ
+    pub demo: Option<AiSettings>,
+}
+
+impl Default for UiSettings {
+    fn default() -> Self {
+        return Self {
+            title_menu: true,
+            score_board: true,
+            exit_on_game_over: true,
+            hint: None,
+            analysis: false,
+            demo: Some(AiSettings {
+                agent: AgentKind::Corner,
+                interval: 0.6,
+            }),
+        };
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::structs::game_mode::GameMode;
+
+// 1 つのモードで残す数
+pub const TABLE_SIZE: usize = 10;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub score: usize,
+    pub max_tile: usize,
+    pub moves: usize,
+    pub seconds: u64,
+}
+
+// モードごとの得点表. キーは GameMode の Display
+#[derive(Resource, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HighScores {
+    pub tables: BTreeMap<String, Vec<HighScore>>,
+}
+
+impl HighScores {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        return serde_json::from_str(&text).map_err(io::Error::other);
+    }
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let text = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        return std::fs::write(path, text);
+    }
+    // ファイルがなければ空から
+    pub fn load_or_default(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Self::default();
+        }
+        return Self::load(path).unwrap_or_else(|e| {
+            eprintln!("failed to load {}: {}", path.display(), e);
+            return Self::default();
+        });
+    }
+    // 高い順に並ぶ
+    pub fn table(&self, mode: &GameMode) -> &[HighScore] {
+        return self
+            .tables
+            .get(&mode.to_string())
+            .map_or(&[], |table| table.as_slice());
+    }
+    pub fn best(&self, mode: &GameMode) -> usize {
+        return self.table(mode).first().map_or(0, |entry| entry.score);
+    }
+    // 表に入れば順位 (0 始まり) を返す. 同じ得点なら先に記録したものが上
+    pub fn record(&mut self, mode: &GameMode, entry: HighScore) -> Option<usize> {
+        let table = self.tables.entry(mode.to_string()).or_default();
+        let rank = table.partition_point(|other| other.score >= entry.score);
+        if rank >= TABLE_SIZE {
+            return None;
+        }
+        table.insert(rank, entry);
+        table.truncate(TABLE_SIZE);
+        return Some(rank);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keep_tables_per_mode() {
+        let mut high_scores = HighScores::default();
+        let classic = GameMode::Classic;
+        let time_attack = GameMode::TimeAttack { seconds: 180 };
+        let entry = |score| HighScore { score, ..default() };
+        for score in 1..=TABLE_SIZE {
+            assert!(high_scores.record(&classic, entry(score * 100)).is_some());
+        }
+        assert_eq!(high_scores.record(&classic, entry(50)), None);
+        assert_eq!(high_scores.record(&classic, entry(550)), Some(5));
+        assert_eq!(high_scores.table(&classic).len(), TABLE_SIZE);
+        assert_eq!(high_scores.best(&classic), 1000);
+        assert_eq!(high_scores.best(&time_attack), 0);
+        assert_eq!(high_scores.record(&time_attack, entry(10)), Some(0));
+        assert_eq!(high_scores.best(&time_attack), 10);
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::tile::Tile;
+use crate::structs::direction::Direction;
+
+// これまでの全てのゲームの統計. StatsSettings::path に JSON で保存する
+#[derive(Resource, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifetimeStats {
+    pub games_played: u64,
+    pub total_moves: u64,
+    pub total_score: u64,
+    // rank -> その rank ができた合体の数
+    pub merges: BTreeMap<u8, u64>,
+    pub highest_tile: usize,
+    // 秒
+    pub time_played: f64,
+    // 2048 に届いたゲームの連続数
+    pub current_2048_streak: u64,
+    pub best_2048_streak: u64,
+    pub moves_left: u64,
+    pub moves_right: u64,
+    pub moves_up: u64,
+    pub moves_down: u64,
+}
+
+impl LifetimeStats {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        return serde_json::from_str(&text).map_err(io::Error::other);
+    }
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        return std::fs::write(path, self.to_json());
+    }
+    // ファイルがなければ最初から
+    pub fn load_or_default(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Self::default();
+        }
+        return Self::load(path).unwrap_or_else(|e| {
+            eprintln!("failed to load {}: {}", path.display(), e);
+            return Self::default();
+        });
+    }
+    pub fn record_move(&mut self, direction: Direction) {
+        self.total_moves += 1;
+        *self.direction_count_mut(direction) += 1;
+    }
+    pub fn record_merge(&mut self, rank: Tile) {
+        *self.merges.entry(rank.0).or_insert(0) += 1;
+    }
+    pub fn record_game(&mut self, score: usize, max_tile: usize) {
+        self.games_played += 1;
+        self.total_score += score as u64;
+        self.highest_tile = self.highest_tile.max(max_tile);
+        if max_tile >= 2048 {
+            self.current_2048_streak += 1;
+            self.best_2048_streak = self.best_2048_streak.max(self.current_2048_streak);
+        } else {
+            self.current_2048_streak = 0;
+        }
+    }
+    pub fn average_score(&self) -> f64 {
+        if self.games_played == 0 {
+            return 0.0;
+        }
+        return self.total_score as f64 / self.games_played as f64;
+    }
+    pub fn direction_count(&self, direction: Direction) -> u64 {
+        match direction {
+            Direction::Left => return self.moves_left,
+            Direction::Right => return self.moves_right,
+            Direction::Up => return self.moves_up,
+            Direction::Down => return self.moves_down,
+        }
+    }
+    fn direction_count_mut(&mut self, direction: Direction) -> &mut u64 {
+        match direction {
+            Direction::Left => return &mut self.moves_left,
+            Direction::Right => return &mut self.moves_right,
+            Direction::Up => return &mut self.moves_up,
+            Direction::Down => return &mut self.moves_down,
+        }
+    }
+    pub fn to_json(&self) -> String {
+        return serde_json::to_string_pretty(self).unwrap_or_default();
+    }
+    // 1 行 1 項目の key,value
+    pub fn to_csv(&self) -> String {
+        let mut text = String::from("key,value\n");
+        let mut row = |key: &str, value: String| {
+            let _ = writeln!(text, "{},{}", key, value);
+        };
+        row("games_played", self.games_played.to_string());
+        row("total_moves", self.total_moves.to_string());
+        row("total_score", self.total_score.to_string());
+        row("average_score", format!("{:.1}", self.average_score()));
+        row("highest_tile", self.highest_tile.to_string());
+        row("time_played", format!("{:.0}", self.time_played));
+        row("current_2048_streak", self.current_2048_streak.to_string());
+        row("best_2048_streak", self.best_2048_streak.to_string());
+        for direction in Direction::ALL {
+            let key = format!("moves_{:?}", direction).to_lowercase();
+            row(&key, self.direction_count(direction).to_string());
+        }
+        for (rank, count) in self.merges.iter() {
+            row(&format!("merges_{}", Tile(*rank)), count.to_string());
+        }
+        return text;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_2048_streak_and_round_trip_json() {
+        let mut stats = LifetimeStats::default();
+        stats.record_move(Direction::Left);
+        stats.record_merge(Tile(11));
+        stats.record_game(20000, 2048);
+        stats.record_game(30000, 4096);
+        stats.record_game(1000, 128);
+        assert_eq!(stats.current_2048_streak, 0);
+        assert_eq!(stats.best_2048_streak, 2);
+        assert_eq!(stats.highest_tile, 4096);
+        assert_eq!(stats.average_score(), 17000.0);
+        assert!(stats.to_csv().contains("merges_2048,1"));
+        let loaded: LifetimeStats = serde_json::from_str(&stats.to_json()).unwrap();
+        assert_eq!(loaded, stats);
+    }
+}
@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+
+use crate::components::board_state::BoardState;
+use crate::structs::direction::Direction;
+
+// 新しいゲームを始めてからの全ての盤面の手
+#[derive(Resource, Clone, Debug, Default)]
+pub struct MoveHistory {
+    pub moves: Vec<MoveRecord>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MoveRecord {
+    // MainBoard::index
+    pub board: usize,
+    // その盤面での何手目か
+    pub turn: usize,
+    // 動かす前の盤面. 後から手を見直すため
+    pub before: BoardState,
+    pub direction: Direction,
+    // 動かした後の Score
+    pub score: usize,
+}
+
+impl MoveHistory {
+    pub fn clear(&mut self) {
+        self.moves.clear();
+    }
+    pub fn push(&mut self, board: usize, before: BoardState, direction: Direction, score: usize) {
+        let turn = self.board_moves(board).count();
+        self.moves.push(MoveRecord {
+            board,
+            turn,
+            before,
+            direction,
+            score,
+        });
+    }
+    pub fn board_moves(&self, board: usize) -> impl Iterator<Item = &MoveRecord> {
+        return self
+            .moves
+            .iter()
+            .filter(move |record| record.board == board);
+    }
+}
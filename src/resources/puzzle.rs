@@ -0,0 +1,326 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::{board_state::BoardState, position::Position, tile::Tile};
+use crate::constants::layout::{MAX_GRID_SIZE, MIN_GRID_SIZE};
+use crate::resources::game_settings::BoardSize;
+
+// 1 つのパズル. PuzzleSettings::dir の JSON のファイルに 1 つずつ書く
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Puzzle {
+    // 解いた記録に使う
+    pub id: String,
+    pub name: String,
+    // 上の行から、空白区切りの Tile の数. 空きマスは "."
+    pub board: Vec<String>,
+    #[serde(default)]
+    pub spawns: PuzzleSpawns,
+    pub objective: PuzzleObjective,
+}
+
+// 毎ターンの Tile の出現
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PuzzleSpawns {
+    // 1 ターンに 1 つずつ順に出す. 埋まっていれば上の行から最初の空きマスに. 尽きたら出さない
+    Fixed { tiles: Vec<PuzzleTile> },
+    // 盤面の乱数をこの seed にして、いつも通りに出す
+    Seeded { seed: u64 },
+}
+
+impl Default for PuzzleSpawns {
+    fn default() -> Self {
+        return Self::Fixed { tiles: Vec::new() };
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PuzzleTile {
+    // 上から数えた行
+    pub row: usize,
+    pub column: usize,
+    pub tile: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PuzzleObjective {
+    ReachTile { tile: usize },
+    // 盤面の Tile を tiles 個以下にする
+    FewTiles { tiles: usize },
+    // moves 手以内に score 点をとる
+    Score { score: usize, moves: usize },
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PuzzleOutcome {
+    #[default]
+    Playing,
+    Solved,
+    Failed,
+}
+
+impl PuzzleObjective {
+    // 動かした後, Tile が出現する前の盤面で調べる
+    pub fn check(&self, board: &BoardState, score: usize, moves: usize) -> PuzzleOutcome {
+        let solved = match *self {
+            Self::ReachTile { tile } => board.max_tile().is_some_and(|max| max.num() >= tile),
+            Self::FewTiles { tiles } => board.tiles().count() <= tiles,
+            Self::Score { score: target, .. } => score >= target,
+        };
+        if solved {
+            return PuzzleOutcome::Solved;
+        }
+        match *self {
+            Self::Score { moves: limit, .. } if moves >= limit => return PuzzleOutcome::Failed,
+            _ => return PuzzleOutcome::Playing,
+        }
+    }
+    pub fn description(&self) -> String {
+        match self {
+            Self::ReachTile { tile } => return format!("make a {} tile", tile),
+            Self::FewTiles { tiles } => return format!("leave {} tiles or fewer", tiles),
+            Self::Score { score, moves } => {
+                return format!("score {} within {} moves", score, moves);
+            }
+        }
+    }
+}
+
+// 2 以上の 2 のべき乗
+fn parse_tile(text: &str) -> Option<Tile> {
+    let num: usize = text.parse().ok()?;
+    if num < 2 || !num.is_power_of_two() {
+        return None;
+    }
+    return Some(Tile(num.ilog2() as u8));
+}
+
+impl Puzzle {
+    // (幅, 高さ)
+    pub fn size(&self) -> (usize, usize) {
+        let width = self
+            .board
+            .first()
+            .map_or(0, |row| row.split_whitespace().count());
+        return (width, self.board.len());
+    }
+    fn position(&self, row: usize, column: usize) -> Position {
+        let (_, height) = self.size();
+        return Position::new(column, height - 1 - row);
+    }
+    pub fn initial_tiles(&self) -> Result<Vec<(Position, Tile)>, String> {
+        let (width, _) = self.size();
+        let mut tiles = Vec::new();
+        for (row, line) in self.board.iter().enumerate() {
+            let cells: Vec<&str> = line.split_whitespace().collect();
+            if cells.len() != width {
+                return Err(format!("row {} has {} cells", row, cells.len()));
+            }
+            for (column, cell) in cells.into_iter().enumerate() {
+                if cell == "." {
+                    continue;
+                }
+                let tile = parse_tile(cell).ok_or_else(|| format!("bad tile: {}", cell))?;
+                tiles.push((self.position(row, column), tile));
+            }
+        }
+        return Ok(tiles);
+    }
+    // Fixed なら出す順の Tile
+    pub fn fixed_spawns(&self) -> Result<Option<Vec<(Position, Tile)>>, String> {
+        let PuzzleSpawns::Fixed { tiles } = &self.spawns else {
+            return Ok(None);
+        };
+        let (width, height) = self.size();
+        let mut spawns = Vec::new();
+        for spawn in tiles.iter() {
+            if spawn.row >= height || spawn.column >= width {
+                return Err(format!("spawn out of the board: {:?}", spawn));
+            }
+            let tile = parse_tile(&spawn.tile.to_string())
+                .ok_or_else(|| format!("bad tile: {}", spawn.tile))?;
+            spawns.push((self.position(spawn.row, spawn.column), tile));
+        }
+        return Ok(Some(spawns));
+    }
+    // 盤面の大きさ、Tile と出現する Tile を確かめる
+    pub fn validate(&self) -> Result<(), String> {
+        let (width, height) = self.size();
+        let range = MIN_GRID_SIZE..=MAX_GRID_SIZE;
+        if !range.contains(&width) || !range.contains(&height) {
+            return Err(format!(
+                "board must be {}x{} to {}x{}, not {}x{}",
+                MIN_GRID_SIZE, MIN_GRID_SIZE, MAX_GRID_SIZE, MAX_GRID_SIZE, width, height
+            ));
+        }
+        self.initial_tiles()?;
+        self.fixed_spawns()?;
+        return Ok(());
+    }
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let puzzle: Self = serde_json::from_str(&text).map_err(io::Error::other)?;
+        puzzle.validate().map_err(io::Error::other)?;
+        return Ok(puzzle);
+    }
+    // dir の *.json をファイル名の順に読む. 読めないものは飛ばす
+    pub fn load_dir(dir: impl AsRef<Path>) -> io::Result<Vec<Self>> {
+        let mut paths: Vec<_> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+        let mut puzzles = Vec::new();
+        for path in paths {
+            match Self::load(&path) {
+                Ok(puzzle) => puzzles.push(puzzle),
+                Err(e) => eprintln!("failed to load {}: {}", path.display(), e),
+            }
+        }
+        return Ok(puzzles);
+    }
+}
+
+// 読み込んだパズルと、今遊んでいるもの
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct Puzzles {
+    pub list: Vec<Puzzle>,
+    pub current: usize,
+    pub outcome: PuzzleOutcome,
+    // パズルに合わせて変える前の盤面の大きさ. Puzzle 以外のモードに戻るときに戻す
+    pub saved_board_size: Option<BoardSize>,
+}
+
+impl Puzzles {
+    pub fn current(&self) -> Option<&Puzzle> {
+        return self.list.get(self.current);
+    }
+}
+
+// 解いたパズルの id -> 最少の手数. PuzzleSettings::path に保存する
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PuzzleProgress {
+    pub solved: BTreeMap<String, usize>,
+}
+
+impl PuzzleProgress {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        return serde_json::from_str(&text).map_err(io::Error::other);
+    }
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let text = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        return std::fs::write(path, text);
+    }
+    // ファイルがなければ空から
+    pub fn load_or_default(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Self::default();
+        }
+        return Self::load(path).unwrap_or_else(|e| {
+            eprintln!("failed to load {}: {}", path.display(), e);
+            return Self::default();
+        });
+    }
+    pub fn best(&self, id: &str) -> Option<usize> {
+        return self.solved.get(id).copied();
+    }
+    // 最少の手数を更新したら true
+    pub fn record(&mut self, id: &str, moves: usize) -> bool {
+        if self.best(id).is_some_and(|best| best <= moves) {
+            return false;
+        }
+        self.solved.insert(id.to_string(), moves);
+        return true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::direction::Direction;
+
+    #[test]
+    fn solve_puzzles_in_assets() {
+        let puzzles = Puzzle::load_dir("assets/puzzles").unwrap();
+        assert!(!puzzles.is_empty());
+        for puzzle in puzzles.iter() {
+            let (width, height) = puzzle.size();
+            let mut board = BoardState::new(width, height);
+            for (position, tile) in puzzle.initial_tiles().unwrap() {
+                board.set(position, Some(tile));
+            }
+            assert_eq!(
+                puzzle.objective.check(&board, 0, 0),
+                PuzzleOutcome::Playing,
+                "{} is solved from the start",
+                puzzle.id
+            );
+        }
+        // 最初のパズルは左に 2 回で 8 ができる
+        let first = &puzzles[0];
+        let mut board = BoardState::new(4, 4);
+        for (position, tile) in first.initial_tiles().unwrap() {
+            board.set(position, Some(tile));
+        }
+        let mut score = 0;
+        for moves in 1..=2 {
+            let (moved, gained) = board.moved(Direction::Left).unwrap();
+            board = moved;
+            score += gained;
+            let outcome = first.objective.check(&board, score, moves);
+            assert_eq!(outcome == PuzzleOutcome::Solved, moves == 2);
+        }
+    }
+
+    #[test]
+    fn reject_boards_out_of_size() {
+        let dir = std::env::temp_dir().join("bevy_2048_puzzle_size");
+        std::fs::create_dir_all(&dir).unwrap();
+        let objective = r#""objective": { "kind": "reach_tile", "tile": 8 }"#;
+        for (name, board, ok) in [
+            ("empty", r#"[]"#, false),
+            ("empty_row", r#"[""]"#, false),
+            ("one", r#"["2"]"#, false),
+            (
+                "wide",
+                &format!("[{0:?}, {0:?}]", vec![". "; 17].concat()),
+                false,
+            ),
+            ("two", r#"["2 .", ". ."]"#, true),
+        ] {
+            let path = dir.join(format!("{}.json", name));
+            let text = format!(
+                r#"{{ "id": "{}", "name": "{}", "board": {}, {} }}"#,
+                name, name, board, objective
+            );
+            std::fs::write(&path, text).unwrap();
+            assert_eq!(Puzzle::load(&path).is_ok(), ok, "{}", name);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn fail_score_objective_after_move_limit() {
+        let objective = PuzzleObjective::Score {
+            score: 40,
+            moves: 3,
+        };
+        let board = BoardState::new(4, 4);
+        assert_eq!(objective.check(&board, 24, 2), PuzzleOutcome::Playing);
+        assert_eq!(objective.check(&board, 24, 3), PuzzleOutcome::Failed);
+        assert_eq!(objective.check(&board, 40, 3), PuzzleOutcome::Solved);
+        let mut progress = PuzzleProgress::default();
+        assert!(progress.record("a", 5));
+        assert!(!progress.record("a", 6));
+        assert!(progress.record("a", 4));
+        assert_eq!(progress.best("a"), Some(4));
+    }
+}
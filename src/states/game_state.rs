@@ -7,6 +7,8 @@ use bevy::ecs::{
 pub enum GameState {
     #[default]
     TitleMenu,
+    // 盤面を作り直してから Spawn へ
+    NewGame,
     Spawn,
     Input,
     Calculate,
@@ -17,7 +19,6 @@ pub enum GameState {
 impl GameState {
     pub fn set_next(self) -> impl FnMut(ResMut<NextState<GameState>>) {
         return move |mut next: ResMut<NextState<GameState>>| {
-            dbg!(self);
             next.set(self);
         };
     }
@@ -1,2 +1,5 @@
+pub mod bitboard;
+pub mod direction;
+pub mod game_mode;
 pub mod grid_array;
 pub mod quater_turn;
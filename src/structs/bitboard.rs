@@ -0,0 +1,332 @@
+use std::sync::OnceLock;
+
+use super::direction::Direction;
+use crate::components::board_state::BoardState;
+use crate::components::{position::Position, tile::Tile};
+use crate::plugins::calculate::{calc_tiles_slice, SliceMovement};
+
+// 4x4 の盤面を 1 マス 4bit (Tile の rank, 空白は 0, 邪魔な Tile は BLOCKER) で 64bit に詰めたもの
+// (x, y) のマスは下位から (y * 4 + x) 番目の 4bit に入る
+// rank は 14 (16384) までしか表せないので、14 同士は合体しない
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Bitboard(pub u64);
+
+pub const BITBOARD_SIZE: usize = 4;
+const MAX_RANK: u8 = 14;
+// Tile::BLOCKER のマス. 動くが合体しない
+pub const BLOCKER: u8 = 15;
+const ROW_MASK: u64 = 0xFFFF;
+
+// 1 行 (16bit) ごとの移動結果
+struct MoveTables {
+    // x = 0 に向かって落とした結果
+    left: Vec<u16>,
+    // x = 3 に向かって落とした結果
+    right: Vec<u16>,
+    score: Vec<u32>,
+}
+
+static MOVE_TABLES: OnceLock<MoveTables> = OnceLock::new();
+
+fn move_tables() -> &'static MoveTables {
+    return MOVE_TABLES.get_or_init(|| {
+        let mut left = vec![0; 1 << 16];
+        let mut right = vec![0; 1 << 16];
+        let mut score = vec![0; 1 << 16];
+        for row in 0..=u16::MAX {
+            let (moved, gained) = calc_row(row);
+            left[row as usize] = moved;
+            score[row as usize] = gained;
+            right[reverse_row(row) as usize] = reverse_row(moved);
+        }
+        return MoveTables { left, right, score };
+    });
+}
+
+// 盤面と同じ規則 (calc_tiles_slice) で 1 行を x = 0 に向かって落とす
+fn calc_row(row: u16) -> (u16, u32) {
+    let slice: Vec<Option<((), Tile)>> = (0..BITBOARD_SIZE)
+        .map(|x| match ((row >> (x * 4)) & 0xF) as u8 {
+            0 => None,
+            BLOCKER => Some(((), Tile::BLOCKER)),
+            rank => Some(((), Tile(rank))),
+        })
+        .collect();
+    let movements = calc_tiles_slice(&slice);
+    let mut moved: u16 = 0;
+    let mut score: u32 = 0;
+    for movement in movements.iter() {
+        let (to, rank) = match *movement {
+            SliceMovement::Slide { from, to, .. } => (to, (row >> (from * 4)) & 0xF),
+            SliceMovement::Merge { rank, .. } if rank.0 > MAX_RANK => {
+                return calc_row_without_overflow(row);
+            }
+            SliceMovement::Merge { to, rank, .. } => {
+                score += rank.num() as u32;
+                (to, rank.0 as u16)
+            }
+        };
+        moved |= rank << (to * 4);
+    }
+    return (moved, score);
+}
+
+// 14 同士が合体してしまう行. 14 は合体させずに詰めるだけにする. BLOCKER も合体しない
+fn calc_row_without_overflow(row: u16) -> (u16, u32) {
+    let ranks = (0..BITBOARD_SIZE)
+        .map(|x| (row >> (x * 4)) & 0xF)
+        .filter(|rank| *rank != 0);
+    let mut result: Vec<u16> = Vec::new();
+    let mut score: u32 = 0;
+    let mut pending: Option<u16> = None;
+    for rank in ranks {
+        match pending.take() {
+            Some(p) if p == rank && p < MAX_RANK as u16 => {
+                result.push(p + 1);
+                score += 1 << (p + 1);
+            }
+            Some(p) => {
+                result.push(p);
+                pending = Some(rank);
+            }
+            None => pending = Some(rank),
+        }
+    }
+    result.extend(pending);
+    let moved = result
+        .iter()
+        .enumerate()
+        .fold(0, |acc, (x, rank)| acc | (rank << (x * 4)));
+    return (moved, score);
+}
+
+fn reverse_row(row: u16) -> u16 {
+    return (row >> 12) | ((row >> 4) & 0x00F0) | ((row << 4) & 0x0F00) | (row << 12);
+}
+
+impl Bitboard {
+    pub fn get(self, pos: Position) -> u8 {
+        return ((self.0 >> Self::shift(pos)) & 0xF) as u8;
+    }
+    pub fn set(&mut self, pos: Position, rank: u8) {
+        let shift = Self::shift(pos);
+        self.0 = (self.0 & !(0xF << shift)) | (((rank & 0xF) as u64) << shift);
+    }
+    fn shift(pos: Position) -> usize {
+        return (pos.y * BITBOARD_SIZE + pos.x) * 4;
+    }
+    fn row(self, y: usize) -> u16 {
+        return ((self.0 >> (y * 16)) & ROW_MASK) as u16;
+    }
+    // (x, y) -> (y, x)
+    pub fn transpose(self) -> Self {
+        let x = self.0;
+        let a1 = x & 0xF0F0_0F0F_F0F0_0F0F;
+        let a2 = x & 0x0000_F0F0_0000_F0F0;
+        let a3 = x & 0x0F0F_0000_0F0F_0000;
+        let a = a1 | (a2 << 12) | (a3 >> 12);
+        let b1 = a & 0xFF00_FF00_00FF_00FF;
+        let b2 = a & 0x00FF_00FF_0000_0000;
+        let b3 = a & 0x0000_0000_FF00_FF00;
+        return Self(b1 | (b2 >> 24) | (b3 << 24));
+    }
+    pub fn empty_count(self) -> usize {
+        // 各マスについて、空白なら最下位 bit だけが立つようにする
+        let mut x = self.0;
+        x |= (x >> 2) & 0x3333_3333_3333_3333;
+        x |= x >> 1;
+        x = !x & 0x1111_1111_1111_1111;
+        return x.count_ones() as usize;
+    }
+    pub fn empty_positions(self) -> impl Iterator<Item = Position> {
+        return Self::positions().filter(move |pos| self.get(*pos) == 0);
+    }
+    // BLOCKER は数えない
+    pub fn max_rank(self) -> u8 {
+        return Self::positions()
+            .map(|pos| self.get(pos))
+            .filter(|rank| *rank != BLOCKER)
+            .max()
+            .unwrap_or(0);
+    }
+    fn positions() -> impl Iterator<Item = Position> {
+        return (0..BITBOARD_SIZE)
+            .flat_map(|x| (0..BITBOARD_SIZE).map(move |y| Position::new(x, y)));
+    }
+    // direction に落とした盤面と得点
+    pub fn slide(self, direction: Direction) -> (Self, u32) {
+        let tables = move_tables();
+        let mut board: u64 = 0;
+        let mut score: u32 = 0;
+        match direction {
+            Direction::Left | Direction::Right => {
+                let table = match direction {
+                    Direction::Left => &tables.left,
+                    _ => &tables.right,
+                };
+                for y in 0..BITBOARD_SIZE {
+                    let row = self.row(y) as usize;
+                    board |= (table[row] as u64) << (y * 16);
+                    score += tables.score[row];
+                }
+            }
+            Direction::Down | Direction::Up => {
+                // 転置すると列が行になり、y = 0 が行の先頭になる
+                let table = match direction {
+                    Direction::Down => &tables.left,
+                    _ => &tables.right,
+                };
+                let transposed = self.transpose();
+                for x in 0..BITBOARD_SIZE {
+                    let row = transposed.row(x) as usize;
+                    board |= (table[row] as u64) << (x * 16);
+                    score += tables.score[row];
+                }
+                board = Self(board).transpose().0;
+            }
+        }
+        return (Self(board), score);
+    }
+    pub fn can_move(self, direction: Direction) -> bool {
+        return self.slide(direction).0 != self;
+    }
+    pub fn legal_directions(self) -> impl Iterator<Item = Direction> {
+        return Direction::ALL
+            .into_iter()
+            .filter(move |direction| self.can_move(*direction));
+    }
+    pub fn is_game_over(self) -> bool {
+        return self.legal_directions().next().is_none();
+    }
+    pub fn from_tiles(tiles: impl IntoIterator<Item = (Position, Tile)>) -> Self {
+        let mut board = Self::default();
+        for (pos, tile) in tiles {
+            match tile.is_blocker() {
+                true => board.set(pos, BLOCKER),
+                false => board.set(pos, tile.0),
+            }
+        }
+        return board;
+    }
+    pub fn tiles(self) -> impl Iterator<Item = (Position, Tile)> {
+        return Self::positions().filter_map(move |pos| match self.get(pos) {
+            0 => None,
+            BLOCKER => Some((pos, Tile::BLOCKER)),
+            rank => Some((pos, Tile(rank))),
+        });
+    }
+}
+
+// 4x4 で、rank が 14 以下の盤面のみ変換できる. 邪魔な Tile は BLOCKER にする
+impl TryFrom<&BoardState> for Bitboard {
+    type Error = ();
+    fn try_from(board: &BoardState) -> Result<Self, Self::Error> {
+        let grid = board.grid();
+        if grid.width() != BITBOARD_SIZE || grid.height() != BITBOARD_SIZE {
+            return Err(());
+        }
+        if board.tiles().any(|(_, tile)| tile.0 > MAX_RANK) {
+            return Err(());
+        }
+        return Ok(Self::from_tiles(board.tiles()));
+    }
+}
+
+impl From<Bitboard> for BoardState {
+    fn from(bitboard: Bitboard) -> Self {
+        let mut board = BoardState::default();
+        for (pos, tile) in bitboard.tiles() {
+            board.set(pos, Some(tile));
+        }
+        return board;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::calculate::calc_tile_movements;
+
+    // テスト用の小さな疑似乱数
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            return self.0;
+        }
+    }
+
+    fn random_board(rng: &mut XorShift) -> BoardState {
+        let mut board = BoardState::default();
+        for pos in Bitboard::positions() {
+            // 空白を多めにして、合体と移動の両方が起きるようにする
+            match rng.next() % 8 {
+                0..=2 => {}
+                3 => board.set(pos, Some(Tile::BLOCKER)),
+                _ => board.set(pos, Some(Tile((rng.next() % 5 + 1) as u8))),
+            }
+        }
+        return board;
+    }
+
+    #[test]
+    fn conversion_round_trip() {
+        let mut rng = XorShift(0x2048);
+        for _ in 0..100 {
+            let board = random_board(&mut rng);
+            let bitboard = Bitboard::try_from(&board).unwrap();
+            assert_eq!(BoardState::from(bitboard), board);
+            assert_eq!(bitboard.empty_count(), board.empty_count());
+            assert_eq!(bitboard.transpose().transpose(), bitboard);
+        }
+    }
+
+    #[test]
+    fn slide_matches_calc_tiles_slice() {
+        let mut rng = XorShift(0x1234_5678);
+        for _ in 0..1000 {
+            let board = random_board(&mut rng);
+            let bitboard = Bitboard::try_from(&board).unwrap();
+            for direction in Direction::ALL {
+                let mut expected = board.clone();
+                let movements = calc_tile_movements(&board, direction.falling_turn());
+                let expected_score = expected.apply(&movements);
+                let (moved, score) = bitboard.slide(direction);
+                assert_eq!(
+                    BoardState::from(moved),
+                    expected,
+                    "{:?}\n{}",
+                    direction,
+                    board
+                );
+                assert_eq!(score as usize, expected_score);
+            }
+        }
+    }
+
+    #[test]
+    fn max_rank_does_not_merge() {
+        let mut board = Bitboard::default();
+        board.set(Position::new(0, 0), MAX_RANK);
+        board.set(Position::new(1, 0), MAX_RANK);
+        assert_eq!(board.slide(Direction::Left), (board, 0));
+        assert!(!board.can_move(Direction::Left));
+    }
+
+    #[test]
+    fn blockers_slide_without_merging() {
+        let mut state = BoardState::default();
+        state.set(Position::new(2, 0), Some(Tile::BLOCKER));
+        state.set(Position::new(3, 0), Some(Tile::BLOCKER));
+        let board = Bitboard::try_from(&state).unwrap();
+        let (moved, score) = board.slide(Direction::Left);
+        assert_eq!(score, 0);
+        assert_eq!(moved.get(Position::new(0, 0)), BLOCKER);
+        assert_eq!(moved.get(Position::new(1, 0)), BLOCKER);
+        assert_eq!(moved.max_rank(), 0);
+        assert_eq!(BoardState::from(moved).tiles().count(), 2);
+    }
+}
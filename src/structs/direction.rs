@@ -0,0 +1,72 @@
+use super::quater_turn::QuarterTurn;
+
+// 盤面上の向き. Up は y が大きくなる向き
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Left,
+        Direction::Right,
+        Direction::Up,
+        Direction::Down,
+    ];
+    pub fn unit(self) -> (isize, isize) {
+        match self {
+            Direction::Left => return (-1, 0),
+            Direction::Right => return (1, 0),
+            Direction::Up => return (0, 1),
+            Direction::Down => return (0, -1),
+        }
+    }
+    // 反時計回りに 90deg 回転させた向き
+    pub fn rotated(self) -> Self {
+        match self {
+            Direction::Left => return Direction::Down,
+            Direction::Right => return Direction::Up,
+            Direction::Up => return Direction::Left,
+            Direction::Down => return Direction::Right,
+        }
+    }
+    // 左右反転させた向き
+    pub fn mirrored(self) -> Self {
+        match self {
+            Direction::Left => return Direction::Right,
+            Direction::Right => return Direction::Left,
+            _ => return self,
+        }
+    }
+    // 通信や記録で使う 1 文字. U, D, L, R
+    pub fn letter(self) -> char {
+        match self {
+            Direction::Left => return 'L',
+            Direction::Right => return 'R',
+            Direction::Up => return 'U',
+            Direction::Down => return 'D',
+        }
+    }
+    pub fn from_letter(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'L' => return Some(Direction::Left),
+            'R' => return Some(Direction::Right),
+            'U' => return Some(Direction::Up),
+            'D' => return Some(Direction::Down),
+            _ => return None,
+        }
+    }
+    // この向きに落とすとき、下に落ちるように盤面を回転させる量
+    // ex: 反時計回りに 90deg 回転させて考えることで左に落ちる
+    pub fn falling_turn(self) -> QuarterTurn {
+        match self {
+            Direction::Down => return QuarterTurn::Deg000,
+            Direction::Left => return QuarterTurn::Deg090,
+            Direction::Up => return QuarterTurn::Deg180,
+            Direction::Right => return QuarterTurn::Deg270,
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+// 1 人用のルール. 得点表はモードごとに分ける
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GameMode {
+    // 動かせなくなるまで
+    #[default]
+    Classic,
+    // seconds 秒の間の得点を競う
+    TimeAttack {
+        seconds: u64,
+    },
+    // 1 手を seconds 秒以内に選ぶ. 過ぎると timeout に従う
+    Blitz {
+        seconds: f32,
+        timeout: BlitzTimeout,
+    },
+    // moves 手の間の得点を競う
+    LimitedMoves {
+        moves: usize,
+    },
+    // Classic と同じルールで、seed をその日の日付から決める. 記録は 1 日 1 回
+    Daily,
+    // PuzzleSettings::dir のパズルを順に解く. 記録は解いた手数だけ
+    Puzzle,
+}
+
+// Blitz で時間が切れたとき
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlitzTimeout {
+    // 動かせる方向からランダムに動かす
+    #[default]
+    RandomMove,
+    // 動かさずに Tile だけ出現させる
+    Pass,
+}
+
+impl GameMode {
+    // タイトル画面で選べるモード
+    pub const PRESETS: [GameMode; 7] = [
+        GameMode::Classic,
+        GameMode::Daily,
+        GameMode::TimeAttack { seconds: 180 },
+        GameMode::TimeAttack { seconds: 300 },
+        GameMode::Blitz {
+            seconds: 3.0,
+            timeout: BlitzTimeout::RandomMove,
+        },
+        GameMode::LimitedMoves { moves: 100 },
+        GameMode::Puzzle,
+    ];
+    // 実績の条件で使う、引数を除いた名前
+    pub fn kind(&self) -> &'static str {
+        match self {
+            GameMode::Classic => return "classic",
+            GameMode::TimeAttack { .. } => return "time",
+            GameMode::Blitz { .. } => return "blitz",
+            GameMode::LimitedMoves { .. } => return "moves",
+            GameMode::Daily => return "daily",
+            GameMode::Puzzle => return "puzzle",
+        }
+    }
+    // タイトル画面のボタンの文字
+    pub fn label(&self) -> String {
+        match self {
+            GameMode::Classic => return "Classic".to_string(),
+            GameMode::TimeAttack { seconds } => return format!("Time {}m", seconds / 60),
+            GameMode::Blitz { .. } => return "Blitz".to_string(),
+            GameMode::LimitedMoves { moves } => return format!("{} Moves", moves),
+            GameMode::Daily => return "Daily".to_string(),
+            GameMode::Puzzle => return "Puzzle".to_string(),
+        }
+    }
+    // PRESETS の次のモード. PRESETS にないモードからは Classic へ
+    pub fn next(&self) -> GameMode {
+        let i = Self::PRESETS.iter().position(|mode| mode == self);
+        return i.map_or(GameMode::Classic, |i| {
+            Self::PRESETS[(i + 1) % Self::PRESETS.len()]
+        });
+    }
+    pub fn time_limit(&self) -> Option<Duration> {
+        match self {
+            GameMode::TimeAttack { seconds } => return Some(Duration::from_secs(*seconds)),
+            _ => return None,
+        }
+    }
+    pub fn move_time_limit(&self) -> Option<Duration> {
+        match self {
+            GameMode::Blitz { seconds, .. } => return Some(Duration::from_secs_f32(*seconds)),
+            _ => return None,
+        }
+    }
+    pub fn move_limit(&self) -> Option<usize> {
+        match self {
+            GameMode::LimitedMoves { moves } => return Some(*moves),
+            _ => return None,
+        }
+    }
+}
+
+// classic, daily, puzzle, time:<秒>, blitz:<秒>[:pass], moves:<手数>
+// 得点表のキーにも使う
+impl fmt::Display for GameMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameMode::Classic => return write!(f, "classic"),
+            GameMode::TimeAttack { seconds } => return write!(f, "time:{}", seconds),
+            GameMode::Blitz { seconds, timeout } => {
+                write!(f, "blitz:{}", seconds)?;
+                if *timeout == BlitzTimeout::Pass {
+                    write!(f, ":pass")?;
+                }
+                return Ok(());
+            }
+            GameMode::LimitedMoves { moves } => return write!(f, "moves:{}", moves),
+            GameMode::Daily => return write!(f, "daily"),
+            GameMode::Puzzle => return write!(f, "puzzle"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseGameModeError(pub String);
+
+impl fmt::Display for ParseGameModeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "unknown game mode: {}", self.0);
+    }
+}
+
+impl std::error::Error for ParseGameModeError {}
+
+impl FromStr for GameMode {
+    type Err = ParseGameModeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseGameModeError(s.to_string());
+        let parts: Vec<&str> = s.split(':').collect();
+        match parts.as_slice() {
+            ["classic"] => return Ok(GameMode::Classic),
+            ["daily"] => return Ok(GameMode::Daily),
+            ["puzzle"] => return Ok(GameMode::Puzzle),
+            ["time", seconds] => {
+                let seconds = seconds.parse().map_err(|_| error())?;
+                return Ok(GameMode::TimeAttack { seconds });
+            }
+            ["blitz", seconds, timeout @ ..] => {
+                let seconds: f32 = seconds.parse().map_err(|_| error())?;
+                let timeout = match timeout {
+                    [] => BlitzTimeout::RandomMove,
+                    ["pass"] => BlitzTimeout::Pass,
+                    _ => return Err(error()),
+                };
+                if seconds <= 0.0 {
+                    return Err(error());
+                }
+                return Ok(GameMode::Blitz { seconds, timeout });
+            }
+            ["moves", moves] => {
+                let moves = moves.parse().map_err(|_| error())?;
+                return Ok(GameMode::LimitedMoves { moves });
+            }
+            _ => return Err(error()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_display_modes() {
+        for mode in GameMode::PRESETS {
+            assert_eq!(mode.to_string().parse(), Ok(mode));
+        }
+        let pass = GameMode::Blitz {
+            seconds: 2.5,
+            timeout: BlitzTimeout::Pass,
+        };
+        assert_eq!("blitz:2.5:pass".parse(), Ok(pass));
+        assert_eq!(pass.to_string(), "blitz:2.5:pass");
+        assert!("time".parse::<GameMode>().is_err());
+        assert_eq!(
+            GameMode::LimitedMoves { moves: 100 }.next(),
+            GameMode::Puzzle
+        );
+    }
+}
@@ -1,89 +1,241 @@
-use std::collections::VecDeque;
-use std::fmt::Debug;
+use std::fmt::{Debug, Display};
+use std::ops::{Index, IndexMut};
 
-use super::quater_turn::QuarterTurn;
-use crate::constants::layout::{GRID_HEIGHT, GRID_WIDTH};
+use super::quater_turn::{QuarterTurn, Symmetry};
+use crate::components::position::Position;
 
-// grid : GridArray<T> は [0][0] から [GRID_WIDTH - 1][GRID_HEIGHT - 1] までの成分を持つ
-pub struct GridArray<T>(pub [[T; GRID_HEIGHT]; GRID_WIDTH]);
+// grid : GridArray<T> は (0, 0) から (width - 1, height - 1) までの成分を持つ
+// 成分は x ごとに並べて保持する. i.e. grid[(x, y)] = cells[x * height + y]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GridArray<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
 
-impl<T: Copy> GridArray<T> {
-    pub fn new(a: T) -> Self {
-        return GridArray([[a; GRID_HEIGHT]; GRID_WIDTH]);
+impl<T: Clone> GridArray<T> {
+    pub fn new(width: usize, height: usize, a: T) -> Self {
+        return Self {
+            width,
+            height,
+            cells: vec![a; width * height],
+        };
+    }
+}
+
+impl<T> GridArray<T> {
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Position) -> T) -> Self {
+        let cells = positions(width, height).map(&mut f).collect();
+        return Self {
+            width,
+            height,
+            cells,
+        };
+    }
+    pub fn width(&self) -> usize {
+        return self.width;
+    }
+    pub fn height(&self) -> usize {
+        return self.height;
+    }
+    pub fn contains(&self, pos: Position) -> bool {
+        return pos.x < self.width && pos.y < self.height;
+    }
+    pub fn get(&self, pos: Position) -> Option<&T> {
+        if !self.contains(pos) {
+            return None;
+        }
+        return self.cells.get(pos.x * self.height + pos.y);
+    }
+    pub fn get_mut(&mut self, pos: Position) -> Option<&mut T> {
+        if !self.contains(pos) {
+            return None;
+        }
+        return self.cells.get_mut(pos.x * self.height + pos.y);
+    }
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        return positions(self.width, self.height);
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        return self.positions().zip(self.cells.iter());
+    }
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        return self.cells.iter();
+    }
+    // x を固定した列. y の小さい方から
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        return self.cells[x * self.height..(x + 1) * self.height].iter();
+    }
+    // y を固定した行. x の小さい方から
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &T> {
+        return (0..self.width).map(move |x| &self[Position::new(x, y)]);
+    }
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        return (0..self.width).map(|x| self.column(x));
+    }
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        return (0..self.height).map(|y| self.row(y));
+    }
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> GridArray<U> {
+        return GridArray {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        };
+    }
+    // 複製せずに変換して見る
+    pub fn transformed(&self, symmetry: Symmetry) -> RotatedGridArray<'_, T> {
+        return RotatedGridArray::new(self, symmetry);
+    }
+    pub fn rotated(&self, turn: QuarterTurn) -> RotatedGridArray<'_, T> {
+        return self.transformed(Symmetry::rotation(turn));
+    }
+    pub fn transposed(&self) -> RotatedGridArray<'_, T> {
+        return self.transformed(Symmetry::TRANSPOSE);
+    }
+    pub fn flipped_x(&self) -> RotatedGridArray<'_, T> {
+        return self.transformed(Symmetry::FLIP_X);
+    }
+    pub fn flipped_y(&self) -> RotatedGridArray<'_, T> {
+        return self.transformed(Symmetry::FLIP_Y);
+    }
+    // 形を変えない対称性
+    pub fn symmetries(&self) -> impl Iterator<Item = Symmetry> {
+        let square = self.width == self.height;
+        return Symmetry::ALL
+            .into_iter()
+            .filter(move |sym| square || !sym.swaps_axes());
+    }
+}
+
+fn positions(width: usize, height: usize) -> impl Iterator<Item = Position> {
+    return (0..width).flat_map(move |x| (0..height).map(move |y| Position::new(x, y)));
+}
+
+impl<T> Index<Position> for GridArray<T> {
+    type Output = T;
+    fn index(&self, pos: Position) -> &T {
+        return self
+            .get(pos)
+            .unwrap_or_else(|| panic!("{:?} is out of the grid", pos));
+    }
+}
+
+impl<T> IndexMut<Position> for GridArray<T> {
+    fn index_mut(&mut self, pos: Position) -> &mut T {
+        return self
+            .get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} is out of the grid", pos));
     }
 }
 
 impl<T: Debug> Debug for GridArray<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_tuple("GridArray").field(&self.0).finish()
+        f.debug_struct("GridArray")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("cells", &self.cells)
+            .finish()
     }
 }
 
-pub struct RotatedGridArray<T> {
-    pub grid_array: GridArray<T>,
-    pub turn: QuarterTurn,
+// y の大きい方 (画面の上) から 1 行ずつ表示
+impl<T: Display> Display for GridArray<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cells: Vec<String> = self.cells.iter().map(|c| c.to_string()).collect();
+        let len = cells.iter().map(|s| s.len()).max().unwrap_or(0);
+        for y in (0..self.height).rev() {
+            let line: Vec<String> = (0..self.width)
+                .map(|x| format!("{:>len$}", cells[x * self.height + y]))
+                .collect();
+            writeln!(f, "{}", line.join(" "))?;
+        }
+        return Ok(());
+    }
 }
 
-impl<T: Debug> Debug for RotatedGridArray<T> {
+// GridArray を複製せずに、symmetry で移したもの
+pub struct RotatedGridArray<'a, T> {
+    pub grid_array: &'a GridArray<T>,
+    pub symmetry: Symmetry,
+}
+
+impl<T> Clone for RotatedGridArray<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for RotatedGridArray<'_, T> {}
+
+impl<T: Debug> Debug for RotatedGridArray<'_, T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RotatedGridArray")
             .field("grid_array", &self.grid_array)
-            .field("turn", &self.turn)
+            .field("symmetry", &self.symmetry)
             .finish()
     }
 }
 
-impl<T> RotatedGridArray<T> {
-    pub fn new(grid_array: GridArray<T>, turn: QuarterTurn) -> Self {
-        Self { grid_array, turn }
+impl<'a, T> RotatedGridArray<'a, T> {
+    pub fn new(grid_array: &'a GridArray<T>, symmetry: Symmetry) -> Self {
+        Self {
+            grid_array,
+            symmetry,
+        }
     }
     pub fn width(&self) -> usize {
-        match self.turn {
-            QuarterTurn::Deg000 | QuarterTurn::Deg180 => return GRID_WIDTH,
-            QuarterTurn::Deg090 | QuarterTurn::Deg270 => return GRID_HEIGHT,
+        match self.symmetry.swaps_axes() {
+            false => return self.grid_array.width,
+            true => return self.grid_array.height,
         }
     }
     pub fn height(&self) -> usize {
-        match self.turn {
-            QuarterTurn::Deg000 | QuarterTurn::Deg180 => return GRID_HEIGHT,
-            QuarterTurn::Deg090 | QuarterTurn::Deg270 => return GRID_WIDTH,
+        match self.symmetry.swaps_axes() {
+            false => return self.grid_array.height,
+            true => return self.grid_array.width,
         }
     }
-    fn get(&self, i: usize, j: usize) -> Option<&T> {
-        let get2 = |i, j| {
-            self.grid_array
-                .0
-                .get(i)
-                .and_then(|y_axis: &[T; GRID_HEIGHT]| (*y_axis).get(j))
-        };
-        match self.turn {
-            QuarterTurn::Deg000 => return get2(i, j),
-            QuarterTurn::Deg090 => return get2(j, GRID_HEIGHT - 1 - i),
-            QuarterTurn::Deg180 => return get2(GRID_WIDTH - 1 - i, GRID_HEIGHT - 1 - j),
-            QuarterTurn::Deg270 => return get2(GRID_WIDTH - 1 - j, i),
+    // 変換後の (i, j) に対応する変換前の Position
+    pub fn position(&self, i: usize, j: usize) -> Position {
+        return self.symmetry.inverse().apply_position(
+            Position::new(i, j),
+            self.width(),
+            self.height(),
+        );
+    }
+    pub fn get(&self, i: usize, j: usize) -> Option<&'a T> {
+        if i >= self.width() || j >= self.height() {
+            return None;
         }
+        return self.grid_array.get(self.position(i, j));
+    }
+    // i を固定した列. j の小さい方から
+    pub fn line(&self, i: usize) -> impl Iterator<Item = &'a T> {
+        let view = *self;
+        return (0..self.height()).map(move |j| view.get(i, j).unwrap());
+    }
+    // 回転後の各列. turn = Deg000 なら下に向かって落ちる順に並ぶ
+    pub fn lines(&self) -> impl Iterator<Item = impl Iterator<Item = &'a T>> {
+        let view = *self;
+        return (0..self.width()).map(move |i| view.line(i));
+    }
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> GridArray<U> {
+        return GridArray::from_fn(self.width(), self.height(), |pos| f(&self[(pos.x, pos.y)]));
     }
 }
 
-impl<T: Clone + Debug> From<RotatedGridArray<T>> for Vec<Vec<T>> {
-    fn from(grid: RotatedGridArray<T>) -> Self {
-        let mut vec: Vec<Vec<T>> = Vec::new();
-        for i in 0..grid.width() {
-            let mut y_axis: Vec<T> = Vec::new();
-            for j in 0..grid.height() {
-                let op = grid.get(i, j);
-                let val = op.unwrap().clone();
-                y_axis.push(val);
-            }
-            vec.push(y_axis);
-        }
-        return vec;
+impl<T: Clone> RotatedGridArray<'_, T> {
+    pub fn to_grid_array(self) -> GridArray<T> {
+        return self.map(|a| a.clone());
     }
 }
 
-impl<T: Clone + Debug> From<RotatedGridArray<T>> for VecDeque<VecDeque<T>> {
-    fn from(val: RotatedGridArray<T>) -> Self {
-        let vec_vec: Vec<Vec<T>> = val.into();
-        return vec_vec.into_iter().map(|vec| vec.into()).collect();
+impl<T> Index<(usize, usize)> for RotatedGridArray<'_, T> {
+    type Output = T;
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        return self
+            .get(i, j)
+            .unwrap_or_else(|| panic!("({}, {}) is out of the grid", i, j));
     }
 }
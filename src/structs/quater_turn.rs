@@ -1,6 +1,10 @@
 use std::fmt::Debug;
 
-#[derive(Default, Clone, Copy, Debug, Eq, PartialEq)]
+use super::{direction::Direction, grid_array::GridArray};
+use crate::components::position::Position;
+
+// 反時計回りの回転
+#[derive(Default, Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub enum QuarterTurn {
     #[default]
     Deg000,
@@ -10,6 +14,12 @@ pub enum QuarterTurn {
 }
 
 impl QuarterTurn {
+    pub const ALL: [QuarterTurn; 4] = [
+        QuarterTurn::Deg000,
+        QuarterTurn::Deg090,
+        QuarterTurn::Deg180,
+        QuarterTurn::Deg270,
+    ];
     pub fn downward_unit(&self) -> (isize, isize) {
         match self {
             QuarterTurn::Deg000 => return (0, -1),
@@ -18,4 +28,159 @@ impl QuarterTurn {
             QuarterTurn::Deg270 => return (1, 0),
         }
     }
+    // 90deg 単位での回転数
+    pub fn count(self) -> usize {
+        match self {
+            QuarterTurn::Deg000 => return 0,
+            QuarterTurn::Deg090 => return 1,
+            QuarterTurn::Deg180 => return 2,
+            QuarterTurn::Deg270 => return 3,
+        }
+    }
+    pub fn from_count(n: usize) -> Self {
+        return Self::ALL[n % 4];
+    }
+    pub fn then(self, next: QuarterTurn) -> Self {
+        return Self::from_count(self.count() + next.count());
+    }
+    pub fn inverse(self) -> Self {
+        return Self::from_count(4 - self.count());
+    }
+}
+
+// 正方形の対称性 (二面体群 D4) の元
+// mirrored なら左右反転 (x -> width - 1 - x) してから turn だけ回転させる
+#[derive(Default, Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Symmetry {
+    pub turn: QuarterTurn,
+    pub mirrored: bool,
+}
+
+impl Symmetry {
+    pub const IDENTITY: Symmetry = Symmetry::new(QuarterTurn::Deg000, false);
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::new(QuarterTurn::Deg000, false),
+        Symmetry::new(QuarterTurn::Deg090, false),
+        Symmetry::new(QuarterTurn::Deg180, false),
+        Symmetry::new(QuarterTurn::Deg270, false),
+        Symmetry::new(QuarterTurn::Deg000, true),
+        Symmetry::new(QuarterTurn::Deg090, true),
+        Symmetry::new(QuarterTurn::Deg180, true),
+        Symmetry::new(QuarterTurn::Deg270, true),
+    ];
+    // (x, y) -> (y, x)
+    pub const TRANSPOSE: Symmetry = Symmetry::new(QuarterTurn::Deg270, true);
+    // (x, y) -> (width - 1 - x, y)
+    pub const FLIP_X: Symmetry = Symmetry::new(QuarterTurn::Deg000, true);
+    // (x, y) -> (x, height - 1 - y)
+    pub const FLIP_Y: Symmetry = Symmetry::new(QuarterTurn::Deg180, true);
+
+    pub const fn new(turn: QuarterTurn, mirrored: bool) -> Self {
+        return Self { turn, mirrored };
+    }
+    pub fn rotation(turn: QuarterTurn) -> Self {
+        return Self::new(turn, false);
+    }
+    // self の後に next を施したもの
+    // 左右反転と回転は M R = R^-1 M の関係にある
+    pub fn then(self, next: Symmetry) -> Self {
+        let turn = match next.mirrored {
+            false => self.turn,
+            true => self.turn.inverse(),
+        };
+        return Self::new(turn.then(next.turn), self.mirrored != next.mirrored);
+    }
+    pub fn inverse(self) -> Self {
+        match self.mirrored {
+            // 反転を含むものは自分自身が逆元
+            true => return self,
+            false => return Self::rotation(self.turn.inverse()),
+        }
+    }
+    // 縦横を入れ替えるかどうか
+    pub fn swaps_axes(self) -> bool {
+        return self.turn.count() % 2 == 1;
+    }
+    // width x height の盤面で pos にある成分が移る先
+    pub fn apply_position(self, pos: Position, width: usize, height: usize) -> Position {
+        let mut pos = pos;
+        let (mut w, mut h) = (width, height);
+        if self.mirrored {
+            pos.x = w - 1 - pos.x;
+        }
+        for _ in 0..self.turn.count() {
+            pos = Position::new(h - 1 - pos.y, pos.x);
+            (w, h) = (h, w);
+        }
+        return pos;
+    }
+    pub fn apply_direction(self, direction: Direction) -> Direction {
+        let mut direction = direction;
+        if self.mirrored {
+            direction = direction.mirrored();
+        }
+        for _ in 0..self.turn.count() {
+            direction = direction.rotated();
+        }
+        return direction;
+    }
+}
+
+// 対称性で移した盤面を同じものとして扱うための代表元
+impl<T: Clone + Ord> GridArray<T> {
+    // 対称な盤面の中で最小のものと、そこへ移す変換
+    pub fn canonical_form(&self) -> (GridArray<T>, Symmetry) {
+        return self
+            .symmetries()
+            .map(|sym| (self.transformed(sym).to_grid_array(), sym))
+            .min_by(|(a, _), (b, _)| a.cmp(b))
+            .expect("symmetries contains the identity");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn symmetries_form_a_group() {
+        let grid = GridArray::from_fn(4, 4, |pos| pos.x * 4 + pos.y);
+        let moved: HashSet<GridArray<usize>> = Symmetry::ALL
+            .iter()
+            .map(|&sym| grid.transformed(sym).to_grid_array())
+            .collect();
+        assert_eq!(moved.len(), 8);
+        for a in Symmetry::ALL {
+            assert_eq!(a.then(Symmetry::IDENTITY), a);
+            assert_eq!(Symmetry::IDENTITY.then(a), a);
+            assert_eq!(a.then(a.inverse()), Symmetry::IDENTITY);
+            assert_eq!(a.inverse().then(a), Symmetry::IDENTITY);
+            for b in Symmetry::ALL {
+                let pos = Position::new(1, 0);
+                let ab = a.then(b).apply_position(pos, 4, 4);
+                assert_eq!(ab, b.apply_position(a.apply_position(pos, 4, 4), 4, 4));
+                assert_eq!(
+                    a.then(b).apply_direction(Direction::Up),
+                    b.apply_direction(a.apply_direction(Direction::Up))
+                );
+                for c in Symmetry::ALL {
+                    assert_eq!(a.then(b).then(c), a.then(b.then(c)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn canonical_form_is_invariant() {
+        for (width, height) in [(4, 4), (3, 2)] {
+            let grid = GridArray::from_fn(width, height, |pos| (pos.x * 7 + pos.y * 3) % 5);
+            let (canonical, sym) = grid.canonical_form();
+            assert_eq!(grid.transformed(sym).to_grid_array(), canonical);
+            for moved in grid.symmetries() {
+                let moved = grid.transformed(moved).to_grid_array();
+                assert_eq!(moved.canonical_form().0, canonical);
+            }
+        }
+    }
 }
@@ -1,5 +1,6 @@
 pub mod bundle;
 pub mod component;
 pub mod constant;
+pub mod demo;
 pub mod plugin;
 pub mod system;
@@ -5,8 +5,8 @@ use super::{
     component,
     constant::{color, font, layout},
 };
-use crate::constants::color::{BOARD_COLOR_0, BOARD_COLOR_1};
-use crate::constants::layout as global_layout;
+use crate::resources::game_settings::{GameSettings, Theme};
+use crate::structs::game_mode::GameMode;
 
 #[derive(Bundle)]
 pub struct Screen {
@@ -20,8 +20,8 @@ impl Default for Screen {
             marker: component::Screen,
             node_bundle: NodeBundle {
                 style: Style {
-                    width: Val::Px(global_layout::WINDOW_WIDTH),
-                    height: Val::Px(global_layout::WINDOW_HEIGHT),
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
                     flex_direction: FlexDirection::Column,
                     align_items: AlignItems::Center,
                     justify_content: JustifyContent::SpaceBetween,
@@ -34,13 +34,17 @@ impl Default for Screen {
 }
 
 impl Screen {
-    pub fn child_builder(font: Handle<Font>) -> impl FnOnce(&mut ChildBuilder) {
+    pub fn child_builder(
+        font: Handle<Font>,
+        settings: &GameSettings,
+    ) -> impl FnOnce(&mut ChildBuilder) {
+        let settings = settings.clone();
         return move |parent| {
-            let title_box = Title::default();
+            let title_box = Title::new(&settings.theme);
             let title_child = title_box.child_builder(font.clone());
             parent.spawn(title_box).with_children(title_child);
-            let menu_box = MenuBox::default();
-            let menu_child = menu_box.child_builder(font.clone());
+            let menu_box = MenuBox::new(&settings);
+            let menu_child = menu_box.child_builder(font.clone(), &settings);
             parent.spawn(menu_box).with_children(menu_child);
         };
     }
@@ -51,25 +55,22 @@ struct Title {
     node: NodeBundle,
 }
 
-impl Default for Title {
-    fn default() -> Self {
+impl Title {
+    fn new(theme: &Theme) -> Self {
         return Self {
             node: NodeBundle {
                 style: Style {
-                    width: Val::Px(layout::TITLE_WIDTH),
+                    width: Val::Percent(100.0),
                     height: Val::Px(layout::TITLE_HEIGHT),
                     align_items: AlignItems::Center,
                     justify_content: JustifyContent::Center,
                     ..default()
                 },
-                background_color: BackgroundColor(BOARD_COLOR_0),
+                background_color: BackgroundColor(theme.board),
                 ..default()
             },
         };
     }
-}
-
-impl Title {
     fn child_builder(&self, font: Handle<Font>) -> impl FnOnce(&mut ChildBuilder) {
         return move |parent| {
             parent.spawn(TextBundle::from_section(
@@ -89,31 +90,35 @@ struct MenuBox {
     node: NodeBundle,
 }
 
-impl Default for MenuBox {
-    fn default() -> Self {
+impl MenuBox {
+    fn new(settings: &GameSettings) -> Self {
         return Self {
             node: NodeBundle {
                 style: Style {
-                    width: Val::Px(layout::MENU_WIDTH),
-                    height: Val::Px(layout::MENU_HEIGHT),
+                    width: Val::Percent(100.0),
+                    height: Val::Px(settings.board_size.main_board_size_2d().y),
                     flex_direction: FlexDirection::Column,
                     align_items: AlignItems::Center,
                     justify_content: JustifyContent::SpaceAround,
                     ..default()
                 },
-                background_color: BackgroundColor(BOARD_COLOR_0),
+                // 裏の盤面のデモが見えるように
+                background_color: BackgroundColor(settings.theme.board.with_a(0.5)),
                 ..default()
             },
         };
     }
-}
-
-impl MenuBox {
-    fn child_builder(&self, font: Handle<Font>) -> impl FnOnce(&mut ChildBuilder) {
+    fn child_builder(
+        &self,
+        font: Handle<Font>,
+        settings: &GameSettings,
+    ) -> impl FnOnce(&mut ChildBuilder) {
+        let theme = settings.theme.clone();
+        let mode = settings.mode;
         return move |parent| {
             for action in component::ButtonAction::iter() {
-                let button = MenuButton::new(action);
-                let child_builder = button.child_builder(font.clone());
+                let button = MenuButton::new(action, &theme);
+                let child_builder = button.child_builder(font.clone(), &mode);
                 parent.spawn(button).with_children(child_builder);
             }
         };
@@ -126,10 +131,10 @@ struct MenuButton {
     button: ButtonBundle,
 }
 
-impl Default for MenuButton {
-    fn default() -> Self {
+impl MenuButton {
+    fn new(action: component::ButtonAction, theme: &Theme) -> Self {
         return Self {
-            action: component::ButtonAction::GameStart,
+            action,
             button: ButtonBundle {
                 style: Style {
                     width: Val::Px(layout::BUTTON_WIDTH),
@@ -139,29 +144,23 @@ impl Default for MenuButton {
                     border: UiRect::all(Val::Px(layout::BUTTON_BORDER)),
                     ..default()
                 },
-                background_color: BOARD_COLOR_0.into(),
-                border_color: BOARD_COLOR_1.into(),
+                background_color: theme.board.into(),
+                border_color: theme.board_tile.into(),
                 ..default()
             },
         };
     }
-}
-
-impl MenuButton {
-    fn new(action: component::ButtonAction) -> Self {
-        return Self {
-            action,
-            ..default()
-        };
-    }
-    fn child_builder(&self, font: Handle<Font>) -> impl FnOnce(&mut ChildBuilder) {
+    fn child_builder(&self, font: Handle<Font>, mode: &GameMode) -> impl FnOnce(&mut ChildBuilder) {
+        let is_mode = matches!(self.action, component::ButtonAction::Mode);
         let val = match self.action {
-            component::ButtonAction::GameStart => "Start",
-            component::ButtonAction::ScoreBoard => "Scores",
-            component::ButtonAction::Quit => "Quit",
+            component::ButtonAction::GameStart => "Start".to_string(),
+            component::ButtonAction::Mode => mode.label(),
+            component::ButtonAction::ScoreBoard => "Scores".to_string(),
+            component::ButtonAction::Achievements => "Awards".to_string(),
+            component::ButtonAction::Quit => "Quit".to_string(),
         };
         return move |parent| {
-            parent.spawn(TextBundle::from_section(
+            let mut text = parent.spawn(TextBundle::from_section(
                 val,
                 TextStyle {
                     font,
@@ -169,6 +168,9 @@ impl MenuButton {
                     font_size: font::MENU_SIZE,
                 },
             ));
+            if is_mode {
+                text.insert(component::ModeText);
+            }
         };
     }
 }
@@ -4,9 +4,16 @@ use strum_macros::EnumIter;
 #[derive(Component)]
 pub struct Screen;
 
+// Mode のボタンの文字
+#[derive(Component)]
+pub struct ModeText;
+
 #[derive(Component, EnumIter)]
 pub enum ButtonAction {
     GameStart,
+    // 押すたびに GameMode::PRESETS の次のモードにする
+    Mode,
     ScoreBoard,
+    Achievements,
     Quit,
 }
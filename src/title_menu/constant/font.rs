@@ -3,4 +3,4 @@ use crate::constants::font;
 pub const NAME: &str = font::MAIN_FONT_NAME;
 
 pub const TITLE_SIZE: f32 = 60.0;
-pub const MENU_SIZE: f32 = 40.0;
+pub const MENU_SIZE: f32 = 32.0;
@@ -1,11 +1,7 @@
 use crate::constants::layout;
 
-pub const TITLE_WIDTH: f32 = layout::MAIN_BOARD_WIDTH;
 pub const TITLE_HEIGHT: f32 = layout::SCORE_BOARD_HEIGHT;
 
-pub const MENU_WIDTH: f32 = layout::MAIN_BOARD_WIDTH;
-pub const MENU_HEIGHT: f32 = layout::MAIN_BOARD_HEIGHT;
-
 pub const BUTTON_WIDTH: f32 = 250.0;
-pub const BUTTON_HEIGHT: f32 = 72.0;
+pub const BUTTON_HEIGHT: f32 = 56.0;
 pub const BUTTON_BORDER: f32 = 8.0;
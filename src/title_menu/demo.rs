@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+
+use crate::ai::Agent;
+use crate::components::{board_state::BoardState, main_board::MainBoard};
+use crate::headless::game::Game;
+use crate::resources::game_settings::GameSettings;
+
+// 1 局目の seed. 終局するたびに 1 ずつ進める
+pub const DEMO_SEED: u64 = 2048;
+
+// タイトル画面の裏で、index = 0 の盤面を AI に遊ばせる
+// Score や MoveHistory には残さない
+#[derive(Resource)]
+pub struct AttractDemo {
+    game: Game,
+    seed: u64,
+    agent: Box<dyn Agent>,
+    timer: Timer,
+}
+
+impl AttractDemo {
+    // 1 手進める. 終局していたら次の seed で始め直す
+    fn advance(&mut self, settings: &GameSettings) {
+        if self.game.is_game_over() || self.game.board().legal_directions().next().is_none() {
+            self.seed += 1;
+            self.game = Game::new(settings, self.seed);
+            return;
+        }
+        let direction = self.agent.choose(self.game.board());
+        self.game.step(direction);
+    }
+}
+
+pub fn start_demo(mut commands: Commands, settings: Res<GameSettings>) {
+    let Some(ai) = settings.ui.demo else {
+        return;
+    };
+    commands.insert_resource(AttractDemo {
+        game: Game::new(&settings, DEMO_SEED),
+        seed: DEMO_SEED,
+        agent: ai.agent.build(&settings, DEMO_SEED),
+        timer: Timer::from_seconds(ai.interval, TimerMode::Repeating),
+    });
+}
+
+pub fn play_demo(
+    time: Res<Time>,
+    settings: Res<GameSettings>,
+    demo: Option<ResMut<AttractDemo>>,
+    mut query: Query<(&MainBoard, &mut BoardState)>,
+) {
+    let Some(mut demo) = demo else {
+        return;
+    };
+    // 最初は始めの盤面をそのまま出す
+    let first = demo.is_added();
+    if !demo.timer.tick(time.delta()).just_finished() && !first {
+        return;
+    }
+    if !first {
+        demo.advance(&settings);
+    }
+    for (main_board, mut board) in query.iter_mut() {
+        if main_board.index == 0 {
+            *board = demo.game.board().clone();
+        }
+    }
+}
+
+// Start を押したら止めて、盤面を空に戻す
+pub fn stop_demo(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    mut query: Query<&mut BoardState, With<MainBoard>>,
+) {
+    commands.remove_resource::<AttractDemo>();
+    let size = settings.board_size;
+    for mut board in query.iter_mut() {
+        *board = BoardState::new(size.width, size.height);
+    }
+}
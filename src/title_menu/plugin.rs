@@ -1,17 +1,35 @@
 use bevy::prelude::*;
 
-use super::system;
+use super::{demo, system};
+use crate::bundle::tile::sync_tiles;
+use crate::error::handle_query_entity_errors;
+use crate::plugins::movement::update_tiles;
 use crate::states::game_state::GameState;
 
 pub struct TitleMenuPlugin;
 
 impl Plugin for TitleMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::TitleMenu), system::create_screen);
+        app.add_systems(
+            OnEnter(GameState::TitleMenu),
+            (system::create_screen, demo::start_demo),
+        );
         app.add_systems(
             Update,
-            (system::menu_action).run_if(in_state(GameState::TitleMenu)),
+            (
+                system::menu_action,
+                (
+                    demo::play_demo,
+                    sync_tiles,
+                    update_tiles.pipe(handle_query_entity_errors),
+                )
+                    .chain(),
+            )
+                .run_if(in_state(GameState::TitleMenu)),
+        );
+        app.add_systems(
+            OnExit(GameState::TitleMenu),
+            (system::remove_screen, demo::stop_demo, sync_tiles).chain(),
         );
-        app.add_systems(OnExit(GameState::TitleMenu), system::remove_screen);
     }
 }
@@ -1,23 +1,37 @@
 use bevy::{app::AppExit, prelude::*};
 
 use super::{bundle, component, constant::font};
+use crate::resources::game_settings::GameSettings;
 use crate::states::game_state::GameState;
 
-pub fn create_screen(mut commands: Commands, asset_server: Res<AssetServer>) {
+pub fn create_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
+) {
     let font = asset_server.load(font::NAME);
     commands
         .spawn(bundle::Screen::default())
-        .with_children(bundle::Screen::child_builder(font));
+        .with_children(bundle::Screen::child_builder(font, &settings));
 }
 
 pub fn remove_screen(query: Query<Entity, With<component::Screen>>, mut commands: Commands) {
     commands.entity(query.single()).despawn_recursive();
 }
 
+type PressedButtonQuery<'w, 's, 'a> = Query<
+    'w,
+    's,
+    (&'a Interaction, &'a component::ButtonAction),
+    (Changed<Interaction>, With<Button>),
+>;
+
 pub fn menu_action(
-    query: Query<(&Interaction, &component::ButtonAction), (Changed<Interaction>, With<Button>)>,
+    query: PressedButtonQuery,
     mut exit: EventWriter<AppExit>,
     mut game_state: ResMut<NextState<GameState>>,
+    mut settings: ResMut<GameSettings>,
+    mut mode_text_query: Query<&mut Text, With<component::ModeText>>,
 ) {
     for (interaction, button_action) in query.iter() {
         if *interaction == Interaction::Pressed {
@@ -25,7 +39,14 @@ pub fn menu_action(
                 component::ButtonAction::GameStart => {
                     game_state.set(GameState::Spawn);
                 }
-                component::ButtonAction::ScoreBoard => {}
+                component::ButtonAction::Mode => {
+                    settings.mode = settings.mode.next();
+                    for mut text in mode_text_query.iter_mut() {
+                        text.sections[0].value = settings.mode.label();
+                    }
+                }
+                // Stats 画面と実績の一覧はそれぞれの Plugin が開く
+                component::ButtonAction::ScoreBoard | component::ButtonAction::Achievements => {}
                 component::ButtonAction::Quit => {
                     exit.send(AppExit);
                 }